@@ -0,0 +1,21 @@
+//! Static library(ar archive) utilities.
+
+mod ar_file;
+mod ar_header;
+mod member;
+
+pub use ar_file::*;
+pub use ar_header::*;
+pub use member::*;
+
+/// magic string at the beginning of archives
+pub const ARMAG: &[u8; 8] = b"!<arch>\n";
+/// terminator of each member header
+pub const ARFMAG: &[u8; 2] = b"`\n";
+
+/// name of the symbol index member
+pub const SYMBOL_INDEX_NAME: &str = "/";
+/// name of the symbol index member with 64-bit offsets
+pub const SYMBOL_INDEX64_NAME: &str = "/SYM64/";
+/// name of the long name table member(GNU)
+pub const LONG_NAME_TABLE_NAME: &str = "//";
@@ -0,0 +1,399 @@
+use std::fs::File;
+use std::io::Read;
+
+use thiserror::Error as TError;
+
+use crate::archive::{self, ArHdr, Member};
+use crate::*;
+
+#[derive(TError, Debug)]
+pub enum ReadArchiveError {
+    #[error("can't read `{file_path}` => `{k}`")]
+    CantRead {
+        file_path: String,
+        k: std::io::Error,
+    },
+    #[error("input is not an ar archive")]
+    NotArchive,
+    #[error("member header at offset {offset:#x} is broken")]
+    InvalidHeader { offset: usize },
+    #[error("member at offset {offset:#x} is out of bounds => size {size:#x}")]
+    MemberOutOfBounds { offset: usize, size: usize },
+    #[error("long name index {name_idx} is out of the long name table")]
+    InvalidLongName { name_idx: usize },
+    #[error("symbol index is broken")]
+    InvalidSymbolIndex,
+}
+
+/// An entry of the archive symbol index
+#[derive(Default, Debug, Clone, Hash, PartialOrd, Ord, PartialEq, Eq)]
+pub struct ArchiveSymbol {
+    pub name: String,
+    /// index of the member which defines the symbol in `Archive.members`
+    pub member: usize,
+}
+
+/// A static library in the System V/GNU ar format.
+///
+/// # Examples
+///
+/// ```
+/// use elf_utilities::archive;
+///
+/// let ar = archive::parse_archive("src/parser/testdata/libfoo.a").unwrap();
+///
+/// assert_eq!(2, ar.members.len());
+/// assert_eq!("add.o", ar.member_defining("add").unwrap().name);
+/// ```
+#[derive(Default, Debug, Clone, Hash, PartialOrd, Ord, PartialEq, Eq)]
+pub struct Archive {
+    /// the symbol index read from the archive.
+    /// it is regenerated from `members` on writing.
+    pub symbols: Vec<ArchiveSymbol>,
+    pub members: Vec<Member>,
+}
+
+/// parse an archive file
+pub fn parse_archive(file_path: &str) -> Result<Archive, ReadArchiveError> {
+    let read_error = |k| ReadArchiveError::CantRead {
+        file_path: file_path.to_string(),
+        k,
+    };
+    let mut f = File::open(file_path).map_err(read_error)?;
+    let mut buf = Vec::new();
+    f.read_to_end(&mut buf).map_err(read_error)?;
+
+    parse_archive_bytes(&buf)
+}
+
+/// parse an archive from an in-memory image
+pub fn parse_archive_bytes(buf: &[u8]) -> Result<Archive, ReadArchiveError> {
+    if !buf.starts_with(archive::ARMAG) {
+        return Err(ReadArchiveError::NotArchive);
+    }
+
+    let mut ar = Archive::default();
+    // シンボルインデックスはメンバヘッダのオフセットを指している
+    let mut index: Vec<(String, usize)> = Vec::new();
+    let mut member_offsets = Vec::new();
+    let mut long_names: &[u8] = &[];
+
+    let mut offset = archive::ARMAG.len();
+    while offset < buf.len() {
+        let hdr = buf
+            .get(offset..)
+            .and_then(ArHdr::parse)
+            .ok_or(ReadArchiveError::InvalidHeader { offset })?;
+        let size = hdr
+            .size()
+            .ok_or(ReadArchiveError::InvalidHeader { offset })?;
+        let data = offset
+            .checked_add(ArHdr::SIZE)
+            .and_then(|start| buf.get(start..start.checked_add(size)?))
+            .ok_or(ReadArchiveError::MemberOutOfBounds { offset, size })?;
+
+        match hdr.name() {
+            archive::SYMBOL_INDEX_NAME => index = parse_symbol_index(data, 4)?,
+            archive::SYMBOL_INDEX64_NAME => index = parse_symbol_index(data, 8)?,
+            archive::LONG_NAME_TABLE_NAME => long_names = data,
+            name => {
+                let name = resolve_name(name, long_names)?;
+                ar.members.push(Member {
+                    name,
+                    date: hdr.date().unwrap_or(0),
+                    uid: hdr.uid().unwrap_or(0),
+                    gid: hdr.gid().unwrap_or(0),
+                    mode: hdr.mode().unwrap_or(0),
+                    data: data.to_vec(),
+                });
+                member_offsets.push(offset);
+            }
+        }
+
+        // メンバは2バイト境界に整列される
+        offset += ArHdr::SIZE + size + size % 2;
+    }
+
+    for (name, member_offset) in index {
+        let member = member_offsets
+            .iter()
+            .position(|o| *o == member_offset)
+            .ok_or(ReadArchiveError::InvalidSymbolIndex)?;
+        ar.symbols.push(ArchiveSymbol { name, member });
+    }
+
+    Ok(ar)
+}
+
+impl Archive {
+    pub fn add_member(&mut self, member: Member) {
+        self.members.push(member);
+    }
+
+    /// get the first member which has the name
+    pub fn member_by_name(&self, name: &str) -> Option<&Member> {
+        self.members.iter().find(|m| m.name == name)
+    }
+
+    /// get the member which defines the symbol, looked up via the symbol index
+    pub fn member_defining(&self, symbol_name: &str) -> Option<&Member> {
+        self.symbols
+            .iter()
+            .find(|sym| sym.name == symbol_name)
+            .and_then(|sym| self.members.get(sym.member))
+    }
+
+    /// iterate ELF members with parsing them
+    pub fn elf_members(
+        &self,
+    ) -> impl Iterator<Item = (&str, Result<file::ELF, parser::ReadELFError>)> {
+        self.members
+            .iter()
+            .filter(|m| m.is_elf())
+            .map(|m| (m.name.as_str(), m.parse_elf()))
+    }
+
+    /// build the symbol index from global symbols of members
+    pub fn symbol_index(&self) -> Vec<ArchiveSymbol> {
+        self.members
+            .iter()
+            .enumerate()
+            .flat_map(|(member, m)| {
+                m.global_symbols()
+                    .into_iter()
+                    .map(move |name| ArchiveSymbol { name, member })
+            })
+            .collect()
+    }
+
+    /// create an archive in the GNU format.
+    /// the symbol index is regenerated from members,
+    /// and names longer than 15 bytes are stored in the long name table.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let symbols = self.symbol_index();
+
+        // 長い名前は "name/\n" の形で `//` メンバに格納する
+        let mut long_names = Vec::new();
+        let names: Vec<String> = self
+            .members
+            .iter()
+            .map(|m| {
+                if m.name.len() < 16 && !m.name.contains('/') {
+                    format!("{}/", m.name)
+                } else {
+                    let name = format!("/{}", long_names.len());
+                    long_names.extend_from_slice(m.name.as_bytes());
+                    long_names.extend_from_slice(b"/\n");
+                    name
+                }
+            })
+            .collect();
+        if long_names.len() % 2 != 0 {
+            long_names.push(b'\n');
+        }
+
+        // 各メンバのオフセットはシンボルインデックスの大きさに依存する
+        let member_offsets = |index_size: usize| {
+            let mut offset = archive::ARMAG.len();
+            if !symbols.is_empty() {
+                offset += ArHdr::SIZE + index_size;
+            }
+            if !long_names.is_empty() {
+                offset += ArHdr::SIZE + long_names.len();
+            }
+            self.members
+                .iter()
+                .map(|m| {
+                    let member_offset = offset;
+                    offset += ArHdr::SIZE + m.data.len() + m.data.len() % 2;
+                    member_offset
+                })
+                .collect::<Vec<usize>>()
+        };
+        let mut word_size = 4;
+        let mut offsets = member_offsets(symbol_index_size(&symbols, word_size));
+        if offsets.last().is_some_and(|o| *o > u32::MAX as usize) {
+            word_size = 8;
+            offsets = member_offsets(symbol_index_size(&symbols, word_size));
+        }
+
+        let mut bytes = archive::ARMAG.to_vec();
+        if !symbols.is_empty() {
+            let index = symbol_index_to_bytes(&symbols, &offsets, word_size);
+            let name = if word_size == 4 {
+                archive::SYMBOL_INDEX_NAME
+            } else {
+                archive::SYMBOL_INDEX64_NAME
+            };
+            bytes.append(&mut ArHdr::new(name, 0, 0, 0, 0, index.len()).to_bytes());
+            bytes.extend(index);
+        }
+        if !long_names.is_empty() {
+            let mut hdr = ArHdr::default();
+            hdr.ar_name[..2].copy_from_slice(b"//");
+            let size = long_names.len().to_string();
+            hdr.ar_size[..size.len()].copy_from_slice(size.as_bytes());
+            bytes.append(&mut hdr.to_bytes());
+            bytes.extend_from_slice(&long_names);
+        }
+
+        for (m, name) in self.members.iter().zip(names.iter()) {
+            let hdr = ArHdr::new(name, m.date, m.uid, m.gid, m.mode, m.data.len());
+            bytes.append(&mut hdr.to_bytes());
+            bytes.extend_from_slice(&m.data);
+            if m.data.len() % 2 != 0 {
+                bytes.push(b'\n');
+            }
+        }
+
+        bytes
+    }
+}
+
+/// decode the symbol index.
+/// numbers are big-endian regardless of members' data encoding.
+fn parse_symbol_index(
+    data: &[u8],
+    word_size: usize,
+) -> Result<Vec<(String, usize)>, ReadArchiveError> {
+    let word = |idx: usize| -> Result<usize, ReadArchiveError> {
+        let start = idx * word_size;
+        let bytes = data
+            .get(start..start + word_size)
+            .ok_or(ReadArchiveError::InvalidSymbolIndex)?;
+        Ok(bytes.iter().fold(0usize, |v, b| (v << 8) | *b as usize))
+    };
+
+    let number = word(0)?;
+    let offsets = (1..=number).map(word).collect::<Result<Vec<_>, _>>()?;
+
+    let names_start = (number + 1)
+        .checked_mul(word_size)
+        .ok_or(ReadArchiveError::InvalidSymbolIndex)?;
+    let mut names = data
+        .get(names_start..)
+        .ok_or(ReadArchiveError::InvalidSymbolIndex)?
+        .split(|c| *c == 0x00);
+
+    offsets
+        .into_iter()
+        .map(|offset| {
+            let name = names.next().ok_or(ReadArchiveError::InvalidSymbolIndex)?;
+            Ok((String::from_utf8_lossy(name).into_owned(), offset))
+        })
+        .collect()
+}
+
+fn symbol_index_size(symbols: &[ArchiveSymbol], word_size: usize) -> usize {
+    let size = word_size * (symbols.len() + 1)
+        + symbols.iter().map(|sym| sym.name.len() + 1).sum::<usize>();
+    size + size % 2
+}
+
+fn symbol_index_to_bytes(
+    symbols: &[ArchiveSymbol],
+    offsets: &[usize],
+    word_size: usize,
+) -> Vec<u8> {
+    let be_word = |v: usize| (v as u64).to_be_bytes()[8 - word_size..].to_vec();
+
+    let mut bytes = be_word(symbols.len());
+    for sym in symbols.iter() {
+        bytes.append(&mut be_word(offsets[sym.member]));
+    }
+    for sym in symbols.iter() {
+        bytes.extend_from_slice(sym.name.as_bytes());
+        bytes.push(0x00);
+    }
+    if bytes.len() % 2 != 0 {
+        bytes.push(0x00);
+    }
+    bytes
+}
+
+/// resolve `/123` to a name in the long name table, and strip the GNU terminator `/`
+fn resolve_name(name: &str, long_names: &[u8]) -> Result<String, ReadArchiveError> {
+    if let Some(name_idx) = name.strip_prefix('/').and_then(|n| n.parse::<usize>().ok()) {
+        let entry = long_names
+            .get(name_idx..)
+            .ok_or(ReadArchiveError::InvalidLongName { name_idx })?;
+        let len = entry
+            .windows(2)
+            .position(|w| w == b"/\n")
+            .ok_or(ReadArchiveError::InvalidLongName { name_idx })?;
+        return Ok(String::from_utf8_lossy(&entry[..len]).into_owned());
+    }
+
+    Ok(name.strip_suffix('/').unwrap_or(name).to_string())
+}
+
+#[cfg(test)]
+mod ar_file_tests {
+    use super::*;
+
+    #[test]
+    fn parse_archive_test() {
+        let ar = parse_archive("src/parser/testdata/libfoo.a").unwrap();
+
+        let names: Vec<&str> = ar.members.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(vec!["add.o", "a_very_long_member_name.o"], names);
+        assert_eq!(0o644, ar.members[0].mode);
+
+        let symbols: Vec<(&str, usize)> = ar
+            .symbols
+            .iter()
+            .map(|sym| (sym.name.as_str(), sym.member))
+            .collect();
+        assert_eq!(
+            vec![
+                ("add", 0),
+                ("weak_fn", 0),
+                ("counter", 0),
+                ("mul_by_adding", 1)
+            ],
+            symbols
+        );
+        assert_eq!(
+            "a_very_long_member_name.o",
+            ar.member_defining("mul_by_adding").unwrap().name
+        );
+        assert!(ar.member_defining("hidden").is_none());
+
+        for (_, f) in ar.elf_members() {
+            assert!(matches!(f, Ok(file::ELF::ELF64(_))));
+        }
+
+        assert!(matches!(
+            parse_archive_bytes(b"!<arch\n"),
+            Err(ReadArchiveError::NotArchive)
+        ));
+    }
+
+    #[test]
+    fn write_archive_test() {
+        let raw = std::fs::read("src/parser/testdata/libfoo.a").unwrap();
+        let mut ar = parse_archive_bytes(&raw).unwrap();
+
+        // `ar rcsD` と同じバイト列を生成する
+        assert_eq!(ar.symbol_index(), ar.symbols);
+        assert_eq!(raw, ar.to_bytes());
+
+        // シンボルを持たないメンバと奇数長のメンバ
+        ar.add_member(Member::new("README", b"odd".to_vec()));
+        let elf = std::fs::read("src/parser/testdata/sample").unwrap();
+        ar.add_member(Member::new("sample_executable.o", elf));
+
+        let reparsed = parse_archive_bytes(&ar.to_bytes()).unwrap();
+        assert_eq!(ar.members, reparsed.members);
+        assert_eq!(ar.symbol_index(), reparsed.symbols);
+        assert_eq!(
+            b"odd".to_vec(),
+            reparsed.member_by_name("README").unwrap().data
+        );
+        // 実行ファイルの.symtabにある大域シンボルも索引に含まれる
+        assert_eq!(
+            "sample_executable.o",
+            reparsed.member_defining("main").unwrap().name
+        );
+    }
+}
@@ -0,0 +1,123 @@
+use crate::archive;
+
+/// Member header. all fields are ASCII padded with spaces.
+#[derive(Debug, Clone, Copy, Hash, PartialOrd, Ord, PartialEq, Eq)]
+#[repr(C)]
+pub struct ArHdr {
+    /// member name, terminated by `/` in the GNU format
+    pub ar_name: [u8; 16],
+    /// modification time in decimal
+    pub ar_date: [u8; 12],
+    /// owner id in decimal
+    pub ar_uid: [u8; 6],
+    /// group id in decimal
+    pub ar_gid: [u8; 6],
+    /// file mode in octal
+    pub ar_mode: [u8; 8],
+    /// member size in decimal
+    pub ar_size: [u8; 10],
+    /// always `ARFMAG`
+    pub ar_fmag: [u8; 2],
+}
+
+impl Default for ArHdr {
+    fn default() -> Self {
+        Self {
+            ar_name: [b' '; 16],
+            ar_date: [b' '; 12],
+            ar_uid: [b' '; 6],
+            ar_gid: [b' '; 6],
+            ar_mode: [b' '; 8],
+            ar_size: [b' '; 10],
+            ar_fmag: *archive::ARFMAG,
+        }
+    }
+}
+
+impl ArHdr {
+    pub const SIZE: usize = 60;
+
+    /// create a header with formatting each field
+    pub fn new(name: &str, date: u64, uid: u32, gid: u32, mode: u32, size: usize) -> Self {
+        let mut hdr = Self::default();
+        fill(&mut hdr.ar_name, name);
+        fill(&mut hdr.ar_date, &date.to_string());
+        fill(&mut hdr.ar_uid, &uid.to_string());
+        fill(&mut hdr.ar_gid, &gid.to_string());
+        fill(&mut hdr.ar_mode, &format!("{:o}", mode));
+        fill(&mut hdr.ar_size, &size.to_string());
+        hdr
+    }
+
+    /// decode a header from the head of `buf`.
+    /// return `None` if `buf` is too short or `ar_fmag` is broken.
+    pub fn parse(buf: &[u8]) -> Option<Self> {
+        let bytes = buf.get(..Self::SIZE)?;
+        let mut hdr = Self::default();
+        hdr.ar_name.copy_from_slice(&bytes[0..16]);
+        hdr.ar_date.copy_from_slice(&bytes[16..28]);
+        hdr.ar_uid.copy_from_slice(&bytes[28..34]);
+        hdr.ar_gid.copy_from_slice(&bytes[34..40]);
+        hdr.ar_mode.copy_from_slice(&bytes[40..48]);
+        hdr.ar_size.copy_from_slice(&bytes[48..58]);
+        hdr.ar_fmag.copy_from_slice(&bytes[58..60]);
+
+        if &hdr.ar_fmag != archive::ARFMAG {
+            return None;
+        }
+        Some(hdr)
+    }
+
+    /// the raw name without trailing spaces
+    pub fn name(&self) -> &str {
+        field_str(&self.ar_name)
+    }
+    pub fn date(&self) -> Option<u64> {
+        parse_field(&self.ar_date, 10)
+    }
+    pub fn uid(&self) -> Option<u32> {
+        parse_field(&self.ar_uid, 10).map(|v| v as u32)
+    }
+    pub fn gid(&self) -> Option<u32> {
+        parse_field(&self.ar_gid, 10).map(|v| v as u32)
+    }
+    pub fn mode(&self) -> Option<u32> {
+        parse_field(&self.ar_mode, 8).map(|v| v as u32)
+    }
+    pub fn size(&self) -> Option<usize> {
+        parse_field(&self.ar_size, 10).map(|v| v as usize)
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(Self::SIZE);
+        bytes.extend_from_slice(&self.ar_name);
+        bytes.extend_from_slice(&self.ar_date);
+        bytes.extend_from_slice(&self.ar_uid);
+        bytes.extend_from_slice(&self.ar_gid);
+        bytes.extend_from_slice(&self.ar_mode);
+        bytes.extend_from_slice(&self.ar_size);
+        bytes.extend_from_slice(&self.ar_fmag);
+        bytes
+    }
+}
+
+fn fill(field: &mut [u8], v: &str) {
+    // 収まらない値は切り詰める
+    let len = v.len().min(field.len());
+    field[..len].copy_from_slice(&v.as_bytes()[..len]);
+}
+
+fn field_str(field: &[u8]) -> &str {
+    std::str::from_utf8(field)
+        .unwrap_or("")
+        .trim_end_matches(' ')
+}
+
+fn parse_field(field: &[u8], radix: u32) -> Option<u64> {
+    let s = field_str(field);
+    // 空のフィールドは0として扱う
+    if s.is_empty() {
+        return Some(0);
+    }
+    u64::from_str_radix(s, radix).ok()
+}
@@ -0,0 +1,79 @@
+use crate::parser::{self, ReadELFError};
+use crate::*;
+
+/// A member file in an archive
+#[derive(Default, Debug, Clone, Hash, PartialOrd, Ord, PartialEq, Eq)]
+pub struct Member {
+    /// file name, long names are already resolved
+    pub name: String,
+    pub date: u64,
+    pub uid: u32,
+    pub gid: u32,
+    pub mode: u32,
+    pub data: Vec<u8>,
+}
+
+impl Member {
+    /// create a member with deterministic attributes like `ar D`(date/uid/gid are 0, mode is 0644).
+    pub fn new(name: &str, data: Vec<u8>) -> Self {
+        Self {
+            name: name.to_string(),
+            date: 0,
+            uid: 0,
+            gid: 0,
+            mode: 0o644,
+            data,
+        }
+    }
+
+    pub fn is_elf(&self) -> bool {
+        self.data.starts_with(&[0x7f, 0x45, 0x4c, 0x46])
+    }
+
+    /// parse the member as an ELF object
+    pub fn parse_elf(&self) -> Result<file::ELF, ReadELFError> {
+        parser::parse_elf_bytes(&self.data)
+    }
+
+    /// names of symbols which are listed in the symbol index,
+    /// i.e. defined(including common) non-local symbols in `.symtab`.
+    /// non-ELF members have no symbols.
+    pub fn global_symbols(&self) -> Vec<String> {
+        if !self.is_elf() {
+            return Vec::new();
+        }
+
+        let is_global = |bind: symbol::Bind, shndx: Elf64Section| {
+            bind != symbol::Bind::Local && shndx != section::SHN_UNDEF
+        };
+        match self.parse_elf() {
+            Ok(file::ELF::ELF64(f)) => f
+                .sections
+                .iter()
+                .filter(|sct| sct.header.get_type() == section::Type::SymTab)
+                .flat_map(|sct| match &sct.contents {
+                    section::Contents64::Symbols(syms) => syms
+                        .iter()
+                        .filter(|sym| is_global(sym.get_bind(), sym.st_shndx))
+                        .map(|sym| sym.symbol_name.clone())
+                        .collect(),
+                    _ => Vec::new(),
+                })
+                .collect(),
+            Ok(file::ELF::ELF32(f)) => f
+                .sections
+                .iter()
+                .filter(|sct| sct.header.get_type() == section::Type::SymTab)
+                .flat_map(|sct| match &sct.contents {
+                    section::Contents32::Symbols(syms) => syms
+                        .iter()
+                        .filter(|sym| is_global(sym.get_bind(), sym.st_shndx))
+                        .map(|sym| sym.symbol_name.clone())
+                        .collect(),
+                    _ => Vec::new(),
+                })
+                .collect(),
+            Err(_) => Vec::new(),
+        }
+    }
+}
@@ -0,0 +1,13 @@
+//! Linux core dump(`ET_CORE`) utilities.
+
+mod auxv;
+mod core_file;
+mod file_note;
+mod prpsinfo;
+mod prstatus;
+
+pub use auxv::*;
+pub use core_file::*;
+pub use file_note::*;
+pub use prpsinfo::*;
+pub use prstatus::*;
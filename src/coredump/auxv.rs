@@ -0,0 +1,49 @@
+use crate::*;
+use serde::{Deserialize, Serialize};
+
+/// End of vector
+pub const AT_NULL: u64 = 0;
+/// Program headers for program
+pub const AT_PHDR: u64 = 3;
+/// Size of program header entry
+pub const AT_PHENT: u64 = 4;
+/// Number of program headers
+pub const AT_PHNUM: u64 = 5;
+/// System page size
+pub const AT_PAGESZ: u64 = 6;
+/// Base address of interpreter
+pub const AT_BASE: u64 = 7;
+/// Entry point of program
+pub const AT_ENTRY: u64 = 9;
+/// Machine dependent hints about processor capabilities
+pub const AT_HWCAP: u64 = 16;
+/// Address of 16 random bytes
+pub const AT_RANDOM: u64 = 25;
+/// Filename of program
+pub const AT_EXECFN: u64 = 31;
+/// Address of the vDSO
+pub const AT_SYSINFO_EHDR: u64 = 33;
+
+/// An entry of the auxiliary vector(`NT_AUXV`) in 64-bit core dumps
+#[derive(
+    Default, Debug, Clone, Copy, Hash, PartialOrd, Ord, PartialEq, Eq, Serialize, Deserialize,
+)]
+#[repr(C)]
+pub struct AuxvEntry {
+    /// Entry type(`AT_*`)
+    pub a_type: Elf64Xword,
+    /// Entry value
+    pub a_val: Elf64Xword,
+}
+
+impl AuxvEntry {
+    pub const SIZE: usize = 16;
+
+    /// decode entries until `AT_NULL`
+    pub fn parse_vector(data: header::Data, desc: &[u8]) -> Vec<Self> {
+        desc.chunks_exact(Self::SIZE)
+            .filter_map(|entry| data.deserialize::<Self>(entry).ok())
+            .take_while(|entry| entry.a_type != AT_NULL)
+            .collect()
+    }
+}
@@ -0,0 +1,249 @@
+use crate::coredump::{self, AuxvEntry, FileMapping, PrPsInfo, PrStatus};
+use crate::parser::ReadELFError;
+use crate::view::ElfView64;
+use crate::*;
+
+/// A borrowed view over a 64-bit Linux core dump.
+///
+/// # Examples
+///
+/// ```
+/// use elf_utilities::coredump::CoreDump64;
+///
+/// let bytes = std::fs::read("src/parser/testdata/core").unwrap();
+/// let core = CoreDump64::new(&bytes).unwrap();
+///
+/// assert_eq!("crash", core.process_info().unwrap().unwrap().pr_fname);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct CoreDump64<'a> {
+    view: ElfView64<'a>,
+}
+
+impl<'a> CoreDump64<'a> {
+    /// create a view with checking `e_type` is `ET_CORE`.
+    pub fn new(buf: &'a [u8]) -> Result<Self, ReadELFError> {
+        let view = ElfView64::new(buf)?;
+        if view.ehdr().get_type() != header::Type::Core {
+            return Err(ReadELFError::NotCoreDump);
+        }
+        Ok(Self { view })
+    }
+
+    /// the underlying view to access program headers
+    pub fn view(&self) -> &ElfView64<'a> {
+        &self.view
+    }
+
+    /// all notes in `PT_NOTE` segments
+    pub fn notes(&self) -> Result<Vec<note::Note>, ReadELFError> {
+        let mut notes = Vec::new();
+        for idx in 0..self.view.segment_number() {
+            if self.view.phdr(idx)?.get_type() == segment::Type::Note {
+                notes.append(&mut self.view.segment_notes(idx)?.notes);
+            }
+        }
+        Ok(notes)
+    }
+
+    /// status of each thread, the first one is the thread which caused the dump.
+    pub fn threads(&self) -> Result<Vec<PrStatus>, ReadELFError> {
+        let machine = self.view.ehdr().get_machine();
+        self.core_notes(note::NT_PRSTATUS)?
+            .iter()
+            .map(|n| {
+                PrStatus::parse(self.view.data(), &machine, &n.desc).ok_or(
+                    ReadELFError::InvalidCoreNote {
+                        n_type: note::NT_PRSTATUS,
+                    },
+                )
+            })
+            .collect()
+    }
+
+    /// `NT_PRPSINFO` of the process
+    pub fn process_info(&self) -> Result<Option<PrPsInfo>, ReadELFError> {
+        match self.core_notes(note::NT_PRPSINFO)?.first() {
+            Some(n) => PrPsInfo::parse(self.view.data(), &n.desc).map(Some).ok_or(
+                ReadELFError::InvalidCoreNote {
+                    n_type: note::NT_PRPSINFO,
+                },
+            ),
+            None => Ok(None),
+        }
+    }
+
+    /// the auxiliary vector(`NT_AUXV`) without the terminator
+    pub fn auxv(&self) -> Result<Vec<AuxvEntry>, ReadELFError> {
+        Ok(match self.core_notes(note::NT_AUXV)?.first() {
+            Some(n) => AuxvEntry::parse_vector(self.view.data(), &n.desc),
+            None => Vec::new(),
+        })
+    }
+
+    /// the value of the auxiliary vector entry `a_type`(e.g. `AT_ENTRY`)
+    pub fn auxv_value(&self, a_type: u64) -> Result<Option<u64>, ReadELFError> {
+        Ok(self
+            .auxv()?
+            .iter()
+            .find(|entry| entry.a_type == a_type)
+            .map(|entry| entry.a_val))
+    }
+
+    /// mapped files(`NT_FILE`)
+    pub fn file_mappings(&self) -> Result<Vec<FileMapping>, ReadELFError> {
+        match self.core_notes(note::NT_FILE)?.first() {
+            Some(n) => coredump::parse_file_mappings(self.view.data(), &n.desc).ok_or(
+                ReadELFError::InvalidCoreNote {
+                    n_type: note::NT_FILE,
+                },
+            ),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// read `len` bytes at `vaddr` from `PT_LOAD` segments.
+    /// return `None` if any byte in the range is not dumped(`p_filesz` is smaller than `p_memsz`
+    /// for pages which the kernel skipped).
+    pub fn read_memory(&self, vaddr: u64, len: usize) -> Option<Vec<u8>> {
+        let end = vaddr.checked_add(len as u64)?;
+        // lenはファイルサイズを超えうるので，確保は読めた分だけにする
+        let mut memory = Vec::with_capacity(len.min(self.view.bytes().len()));
+
+        // 連続したPT_LOADにまたがる読み出しに対応する
+        let mut addr = vaddr;
+        while addr < end {
+            let phdr = (0..self.view.segment_number())
+                .filter_map(|idx| self.view.phdr(idx).ok())
+                .find(|phdr| {
+                    phdr.get_type() == segment::Type::Load
+                        && phdr.p_vaddr <= addr
+                        && addr - phdr.p_vaddr < phdr.p_filesz
+                })?;
+            // 壊れたプログラムヘッダでもオーバーフローさせない
+            let chunk_end = end.min(phdr.p_vaddr.checked_add(phdr.p_filesz)?);
+            let offset = phdr.p_offset.checked_add(addr - phdr.p_vaddr)?;
+            let offset_end = offset.checked_add(chunk_end - addr)?;
+            let bytes = self
+                .view
+                .bytes()
+                .get(offset as usize..offset_end as usize)?;
+            memory.extend_from_slice(bytes);
+            addr = chunk_end;
+        }
+
+        Some(memory)
+    }
+
+    fn core_notes(&self, n_type: u32) -> Result<Vec<note::Note>, ReadELFError> {
+        Ok(self
+            .notes()?
+            .into_iter()
+            .filter(|n| n.name == note::ELF_NOTE_CORE && n.n_type == n_type)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod core_file_tests {
+    use super::*;
+    use crate::coredump::{Registers, AT_ENTRY, AT_PAGESZ};
+    use std::convert::TryInto;
+
+    #[test]
+    fn core_dump_test() {
+        let bytes = std::fs::read("src/parser/testdata/core").unwrap();
+        let core = CoreDump64::new(&bytes).unwrap();
+
+        let threads = core.threads().unwrap();
+        assert_eq!(1, threads.len());
+        let status = &threads[0];
+        assert_eq!(11, status.si_signo);
+        assert_eq!(11, status.pr_cursig);
+        assert_eq!(15305, status.pr_pid);
+        assert_eq!(15299, status.pr_ppid);
+        assert_eq!(1, status.pr_fpvalid);
+        assert!(matches!(status.pr_reg, Registers::X86_64(_)));
+        assert_eq!(Some(0x5570f11f4148), status.pr_reg.pc());
+        assert_eq!(Some(0x7ffd0bebe360), status.pr_reg.sp());
+
+        let info = core.process_info().unwrap().unwrap();
+        assert_eq!('R', info.pr_sname);
+        assert_eq!("crash", info.pr_fname);
+        assert_eq!("./crash ", info.pr_psargs);
+        assert_eq!(15305, info.pr_pid);
+
+        assert_eq!(22, core.auxv().unwrap().len());
+        assert_eq!(Some(0x1000), core.auxv_value(AT_PAGESZ).unwrap());
+        assert_eq!(Some(0x5570f11f4040), core.auxv_value(AT_ENTRY).unwrap());
+
+        let mappings = core.file_mappings().unwrap();
+        assert_eq!(15, mappings.len());
+        assert_eq!(0x5570f11f3000, mappings[0].start);
+        assert_eq!(0x5570f11f4000, mappings[0].end);
+        assert_eq!(0, mappings[0].offset);
+        assert_eq!("/tmp/core/crash", mappings[0].path);
+        assert_eq!(0x1000, mappings[1].offset);
+        assert!(mappings
+            .iter()
+            .any(|m| m.path == "/usr/lib/x86_64-linux-gnu/libc.so.6"));
+
+        // 書き込み可能なページはダンプされている
+        let g = core.read_memory(0x5570f11f7010, 4).unwrap();
+        assert_eq!(0x1235, i32::from_le_bytes(g.try_into().unwrap()));
+        // テキストセグメントはp_filesz == 0
+        assert!(core.read_memory(0x5570f11f4000, 4).is_none());
+        // 隣接したPT_LOADにまたがる読み出し
+        assert_eq!(
+            Some(0x20),
+            core.read_memory(0x7f0a2e36eff0, 0x20).map(|m| m.len())
+        );
+    }
+
+    #[test]
+    fn read_memory_overflow_test() {
+        let bytes = std::fs::read("src/parser/testdata/core").unwrap();
+        let view = CoreDump64::new(&bytes).unwrap().view;
+        let load_idx = (0..view.segment_number())
+            .find(|idx| {
+                let phdr = view.phdr(*idx).unwrap();
+                phdr.p_vaddr <= 0x5570f11f7010 && 0x5570f11f7010 < phdr.p_vaddr + phdr.p_filesz
+            })
+            .unwrap();
+
+        // p_offsetが巨大な壊れたPT_LOADでもパニックしない
+        let mut broken = bytes.clone();
+        let start = view.ehdr().e_phoff as usize + segment::Phdr64::SIZE * load_idx + 8;
+        broken[start..start + 8].fill(0xff);
+        let core = CoreDump64::new(&broken).unwrap();
+        assert!(core.read_memory(0x5570f11f7010, 4).is_none());
+        assert!(core.read_memory(0, usize::MAX).is_none());
+    }
+
+    #[test]
+    fn not_core_dump_test() {
+        let bytes = std::fs::read("src/parser/testdata/sample").unwrap();
+        assert!(matches!(
+            CoreDump64::new(&bytes),
+            Err(ReadELFError::NotCoreDump)
+        ));
+    }
+
+    #[test]
+    fn aarch64_prstatus_test() {
+        let mut desc = vec![0u8; 392];
+        desc[0..4].copy_from_slice(&11i32.to_le_bytes());
+        desc[32..36].copy_from_slice(&42i32.to_le_bytes());
+        // sp, pc
+        desc[112 + 31 * 8..112 + 32 * 8].copy_from_slice(&0xfffff000u64.to_le_bytes());
+        desc[112 + 32 * 8..112 + 33 * 8].copy_from_slice(&0x400123u64.to_le_bytes());
+        desc[384..388].copy_from_slice(&1i32.to_le_bytes());
+
+        let status = PrStatus::parse(header::Data::LSB2, &header::Machine::AArch64, &desc).unwrap();
+        assert_eq!(11, status.si_signo);
+        assert_eq!(42, status.pr_pid);
+        assert_eq!(Some(0x400123), status.pr_reg.pc());
+        assert_eq!(Some(0xfffff000), status.pr_reg.sp());
+        assert_eq!(1, status.pr_fpvalid);
+    }
+}
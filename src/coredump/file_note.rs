@@ -0,0 +1,40 @@
+use crate::*;
+
+/// A file mapped into the process(an entry of `NT_FILE`)
+#[derive(Default, Debug, Clone, Hash, PartialOrd, Ord, PartialEq, Eq)]
+pub struct FileMapping {
+    pub start: Elf64Addr,
+    pub end: Elf64Addr,
+    /// offset in the file in bytes
+    pub offset: Elf64Off,
+    pub path: String,
+}
+
+/// decode the `NT_FILE` descriptor of 64-bit core dumps.
+///
+/// the descriptor is `count`, `page_size`, `count` triples of `(start, end, page offset)`
+/// and then `count` NUL-terminated paths.
+pub fn parse_file_mappings(data: header::Data, desc: &[u8]) -> Option<Vec<FileMapping>> {
+    let word = |idx: usize| -> Option<u64> {
+        let start = idx.checked_mul(8)?;
+        data.deserialize(desc.get(start..start.checked_add(8)?)?)
+            .ok()
+    };
+
+    let count = word(0)? as usize;
+    let page_size = word(1)?;
+    let names_start = count.checked_mul(24)?.checked_add(16)?;
+    let mut paths = desc.get(names_start..)?.split(|c| *c == 0x00);
+
+    (0..count)
+        .map(|i| {
+            let path = paths.next()?;
+            Some(FileMapping {
+                start: word(2 + 3 * i)?,
+                end: word(3 + 3 * i)?,
+                offset: word(4 + 3 * i)?.checked_mul(page_size)?,
+                path: String::from_utf8_lossy(path).into_owned(),
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,79 @@
+use crate::*;
+use serde::Deserialize;
+
+/// Process information(`NT_PRPSINFO`) in 64-bit core dumps
+#[derive(Default, Debug, Clone, Hash, PartialOrd, Ord, PartialEq, Eq)]
+pub struct PrPsInfo {
+    /// numeric process state
+    pub pr_state: i8,
+    /// char for `pr_state` like `R`
+    pub pr_sname: char,
+    pub pr_zomb: bool,
+    pub pr_nice: i8,
+    pub pr_flag: u64,
+    pub pr_uid: u32,
+    pub pr_gid: u32,
+    pub pr_pid: i32,
+    pub pr_ppid: i32,
+    pub pr_pgrp: i32,
+    pub pr_sid: i32,
+    /// filename of executable
+    pub pr_fname: String,
+    /// initial part of arg list
+    pub pr_psargs: String,
+}
+
+/// fields before `pr_fname`
+#[derive(Deserialize)]
+#[repr(C)]
+struct PrPsInfoHead {
+    pr_state: i8,
+    pr_sname: u8,
+    pr_zomb: i8,
+    pr_nice: i8,
+    _pad: u32,
+    pr_flag: u64,
+    pr_uid: u32,
+    pr_gid: u32,
+    pr_pid: i32,
+    pr_ppid: i32,
+    pr_pgrp: i32,
+    pr_sid: i32,
+}
+
+impl PrPsInfo {
+    pub const SIZE: usize = 136;
+    const FNAME_OFFSET: usize = 40;
+    const FNAME_SIZE: usize = 16;
+    const PSARGS_SIZE: usize = 80;
+
+    /// decode the descriptor
+    pub fn parse(data: header::Data, desc: &[u8]) -> Option<Self> {
+        let head: PrPsInfoHead = data.deserialize(desc).ok()?;
+        let fname = desc.get(Self::FNAME_OFFSET..Self::FNAME_OFFSET + Self::FNAME_SIZE)?;
+        let psargs_offset = Self::FNAME_OFFSET + Self::FNAME_SIZE;
+        let psargs = desc.get(psargs_offset..psargs_offset + Self::PSARGS_SIZE)?;
+
+        Some(Self {
+            pr_state: head.pr_state,
+            pr_sname: head.pr_sname as char,
+            pr_zomb: head.pr_zomb != 0,
+            pr_nice: head.pr_nice,
+            pr_flag: head.pr_flag,
+            pr_uid: head.pr_uid,
+            pr_gid: head.pr_gid,
+            pr_pid: head.pr_pid,
+            pr_ppid: head.pr_ppid,
+            pr_pgrp: head.pr_pgrp,
+            pr_sid: head.pr_sid,
+            pr_fname: c_string(fname),
+            pr_psargs: c_string(psargs),
+        })
+    }
+}
+
+fn c_string(bytes: &[u8]) -> String {
+    // NUL終端されていない場合は配列全体を用いる
+    let len = bytes.iter().position(|c| *c == 0x00).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..len]).into_owned()
+}
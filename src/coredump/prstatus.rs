@@ -0,0 +1,195 @@
+use crate::*;
+use serde::{Deserialize, Serialize};
+
+/// x86_64 general purpose registers(`user_regs_struct`)
+#[derive(
+    Default, Debug, Clone, Copy, Hash, PartialOrd, Ord, PartialEq, Eq, Serialize, Deserialize,
+)]
+#[repr(C)]
+pub struct X86_64Registers {
+    pub r15: u64,
+    pub r14: u64,
+    pub r13: u64,
+    pub r12: u64,
+    pub rbp: u64,
+    pub rbx: u64,
+    pub r11: u64,
+    pub r10: u64,
+    pub r9: u64,
+    pub r8: u64,
+    pub rax: u64,
+    pub rcx: u64,
+    pub rdx: u64,
+    pub rsi: u64,
+    pub rdi: u64,
+    pub orig_rax: u64,
+    pub rip: u64,
+    pub cs: u64,
+    pub eflags: u64,
+    pub rsp: u64,
+    pub ss: u64,
+    pub fs_base: u64,
+    pub gs_base: u64,
+    pub ds: u64,
+    pub es: u64,
+    pub fs: u64,
+    pub gs: u64,
+}
+
+/// AArch64 general purpose registers(`user_pt_regs`)
+#[derive(
+    Default, Debug, Clone, Copy, Hash, PartialOrd, Ord, PartialEq, Eq, Serialize, Deserialize,
+)]
+#[repr(C)]
+pub struct AArch64Registers {
+    /// x0 - x30
+    pub regs: [u64; 31],
+    pub sp: u64,
+    pub pc: u64,
+    pub pstate: u64,
+}
+
+/// registers in `NT_PRSTATUS`, the layout depends on `e_machine`
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub enum Registers {
+    X86_64(X86_64Registers),
+    AArch64(AArch64Registers),
+    /// registers of other architectures
+    Unknown(Vec<u8>),
+}
+
+/// `struct timeval` in 64-bit core dumps
+#[derive(
+    Default, Debug, Clone, Copy, Hash, PartialOrd, Ord, PartialEq, Eq, Serialize, Deserialize,
+)]
+#[repr(C)]
+pub struct TimeVal {
+    pub tv_sec: i64,
+    pub tv_usec: i64,
+}
+
+/// Status of a thread(`NT_PRSTATUS`) in 64-bit core dumps
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct PrStatus {
+    /// signal number
+    pub si_signo: i32,
+    pub si_code: i32,
+    pub si_errno: i32,
+    /// current signal
+    pub pr_cursig: u16,
+    /// set of pending signals
+    pub pr_sigpend: u64,
+    /// set of held signals
+    pub pr_sighold: u64,
+    pub pr_pid: i32,
+    pub pr_ppid: i32,
+    pub pr_pgrp: i32,
+    pub pr_sid: i32,
+    /// user time
+    pub pr_utime: TimeVal,
+    /// system time
+    pub pr_stime: TimeVal,
+    /// cumulative user time
+    pub pr_cutime: TimeVal,
+    /// cumulative system time
+    pub pr_cstime: TimeVal,
+    pub pr_reg: Registers,
+    /// true if math co-processor being used
+    pub pr_fpvalid: i32,
+}
+
+/// fields before `pr_reg`, the layout is common to 64-bit Linux
+#[derive(Deserialize)]
+#[repr(C)]
+struct PrStatusHead {
+    si_signo: i32,
+    si_code: i32,
+    si_errno: i32,
+    pr_cursig: u16,
+    _pad: u16,
+    pr_sigpend: u64,
+    pr_sighold: u64,
+    pr_pid: i32,
+    pr_ppid: i32,
+    pr_pgrp: i32,
+    pr_sid: i32,
+    pr_utime: TimeVal,
+    pr_stime: TimeVal,
+    pr_cutime: TimeVal,
+    pr_cstime: TimeVal,
+}
+
+impl X86_64Registers {
+    pub const SIZE: usize = 216;
+}
+
+impl AArch64Registers {
+    pub const SIZE: usize = 272;
+}
+
+impl Registers {
+    /// the program counter
+    pub fn pc(&self) -> Option<u64> {
+        match self {
+            Self::X86_64(regs) => Some(regs.rip),
+            Self::AArch64(regs) => Some(regs.pc),
+            Self::Unknown(_) => None,
+        }
+    }
+
+    /// the stack pointer
+    pub fn sp(&self) -> Option<u64> {
+        match self {
+            Self::X86_64(regs) => Some(regs.rsp),
+            Self::AArch64(regs) => Some(regs.sp),
+            Self::Unknown(_) => None,
+        }
+    }
+}
+
+impl PrStatus {
+    /// offset of `pr_reg`
+    const REG_OFFSET: usize = 112;
+
+    /// decode the descriptor.
+    /// registers of machines other than x86_64/AArch64 are left as raw bytes
+    /// between `pr_reg` and `pr_fpvalid`.
+    pub fn parse(data: header::Data, machine: &header::Machine, desc: &[u8]) -> Option<Self> {
+        let head: PrStatusHead = data.deserialize(desc).ok()?;
+
+        let reg_size = match machine {
+            header::Machine::X8664 => X86_64Registers::SIZE,
+            header::Machine::AArch64 => AArch64Registers::SIZE,
+            // pr_fpvalidと末尾のパディングを除いた部分をレジスタとみなす
+            _ => desc.len().checked_sub(Self::REG_OFFSET + 8)?,
+        };
+        let reg_bytes = desc.get(Self::REG_OFFSET..Self::REG_OFFSET + reg_size)?;
+        let pr_reg = match machine {
+            header::Machine::X8664 => Registers::X86_64(data.deserialize(reg_bytes).ok()?),
+            header::Machine::AArch64 => Registers::AArch64(data.deserialize(reg_bytes).ok()?),
+            _ => Registers::Unknown(reg_bytes.to_vec()),
+        };
+        let pr_fpvalid = data
+            .deserialize(desc.get(Self::REG_OFFSET + reg_size..)?)
+            .ok()?;
+
+        Some(Self {
+            si_signo: head.si_signo,
+            si_code: head.si_code,
+            si_errno: head.si_errno,
+            pr_cursig: head.pr_cursig,
+            pr_sigpend: head.pr_sigpend,
+            pr_sighold: head.pr_sighold,
+            pr_pid: head.pr_pid,
+            pr_ppid: head.pr_ppid,
+            pr_pgrp: head.pr_pgrp,
+            pr_sid: head.pr_sid,
+            pr_utime: head.pr_utime,
+            pr_stime: head.pr_stime,
+            pr_cutime: head.pr_cutime,
+            pr_cstime: head.pr_cstime,
+            pr_reg,
+            pr_fpvalid,
+        })
+    }
+}
@@ -17,7 +17,12 @@ impl Dyn32 {
     }
 
     pub fn to_le_bytes(&self) -> Vec<u8> {
-        bincode::serialize(self).unwrap()
+        self.to_bytes(header::Data::LSB2)
+    }
+
+    /// Create Vec<u8> from this with the given data encoding.
+    pub fn to_bytes(&self, data: header::Data) -> Vec<u8> {
+        data.serialize(self)
     }
 
     pub fn deserialize(buf: &[u8], start: usize) -> Result<Self, Box<dyn std::error::Error>> {
@@ -80,6 +80,10 @@ pub enum EntryType {
     LoProc,
     /// End of processor specific
     HiProc,
+    /// Address of version definition table
+    VerDef,
+    /// Number of version definitions
+    VerDefNum,
     /// Address of table with needed versions
     VerNeed,
     /// Number of needed versions
@@ -143,6 +147,8 @@ impl From<i64> for EntryType {
             0x6ffffff9 => EntryType::RelaCount,
             0x6ffffffa => EntryType::RelCount,
             0x6ffffffb => EntryType::Flags1,
+            0x6ffffffc => EntryType::VerDef,
+            0x6ffffffd => EntryType::VerDefNum,
             0x6ffffffe => EntryType::VerNeed,
             0x6fffffff => EntryType::VerNeedNum,
             _ => EntryType::Any(v),
@@ -0,0 +1,70 @@
+use std::collections::HashSet;
+
+use crate::*;
+
+/// Values in the dynamic section which are resolved through the dynamic string table.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct DynamicInfo {
+    /// `DT_NEEDED` libraries in order
+    pub needed: Vec<String>,
+    /// `DT_SONAME`
+    pub soname: Option<String>,
+    /// `DT_RPATH` entries separated by `:`
+    pub rpath: Vec<String>,
+    /// `DT_RUNPATH` entries separated by `:`
+    pub runpath: Vec<String>,
+    /// decoded `DT_FLAGS`
+    pub flags: HashSet<dynamic::Flag>,
+    /// decoded `DT_FLAGS_1`
+    pub flags_1: HashSet<dynamic::Flag>,
+}
+
+impl DynamicInfo {
+    /// build from `(d_tag, d_un)` pairs.
+    /// `string` resolves an offset in the dynamic string table.
+    pub(crate) fn from_entries<I, F>(entries: I, string: F) -> Self
+    where
+        I: Iterator<Item = (dynamic::EntryType, u64)>,
+        F: Fn(u64) -> Option<String>,
+    {
+        let mut info = Self::default();
+        for (tag, value) in entries {
+            match tag {
+                // DT_NULL以降のエントリは無視する
+                dynamic::EntryType::Null => break,
+                dynamic::EntryType::Needed => info.needed.extend(string(value)),
+                dynamic::EntryType::SOName => info.soname = string(value),
+                dynamic::EntryType::RPath => info.rpath.extend(split_paths(string(value))),
+                dynamic::EntryType::RunPath => info.runpath.extend(split_paths(string(value))),
+                dynamic::EntryType::Flags => info
+                    .flags
+                    .extend(split_bits(value, dynamic::Flag::from_def)),
+                dynamic::EntryType::Flags1 => info
+                    .flags_1
+                    .extend(split_bits(value, dynamic::Flag::from_1)),
+                _ => {}
+            }
+        }
+        info
+    }
+}
+
+fn split_paths(paths: Option<String>) -> Vec<String> {
+    match paths {
+        Some(paths) => paths
+            .split(':')
+            .filter(|p| !p.is_empty())
+            .map(|p| p.to_string())
+            .collect(),
+        None => Vec::new(),
+    }
+}
+
+fn split_bits(value: u64, f: fn(u64) -> dynamic::Flag) -> Vec<dynamic::Flag> {
+    // 各ビットを個別のフラグとして変換する
+    (0..64)
+        .map(|bit| 1u64 << bit)
+        .filter(|mask| value & mask != 0)
+        .map(f)
+        .collect()
+}
@@ -6,3 +6,4 @@ pub use elf64::*;
 mod base;
 mod elf32;
 mod elf64;
+mod layout;
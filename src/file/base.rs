@@ -15,22 +15,11 @@ pub struct ELFDumper {
 }
 
 impl ELF {
-    pub(crate) fn as_64bit(self) -> ELF64 {
+    /// create a binary with the data encoding written in `e_ident`
+    pub(crate) fn to_bytes(&self) -> Vec<u8> {
         match self {
-            ELF::ELF64(e) => e,
-            _ => unreachable!(),
-        }
-    }
-    pub(crate) fn as_32bit(self) -> ELF32 {
-        match self {
-            ELF::ELF32(e) => e,
-            _ => unreachable!(),
-        }
-    }
-    pub(crate) fn to_le_bytes(&self) -> Vec<u8> {
-        match self {
-            ELF::ELF64(e) => e.to_le_bytes(),
-            ELF::ELF32(e) => e.to_le_bytes(),
+            ELF::ELF64(e) => e.to_bytes(e.ehdr.get_data()),
+            ELF::ELF32(e) => e.to_bytes(e.ehdr.get_data()),
         }
     }
 }
@@ -45,7 +34,7 @@ impl ELFDumper {
         output_filename: &str,
         permission: u32,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        let bytes = self.file.to_le_bytes();
+        let bytes = self.file.to_bytes();
 
         #[cfg(target_family = "unix")]
         let file = std::fs::OpenOptions::new()
@@ -1,7 +1,11 @@
+use std::collections::{HashMap, HashSet};
+use std::convert::TryFrom;
+
+use super::layout;
 use crate::{
-    header,
+    dynamic, header, objcopy, relocation,
     section::{self, Section32},
-    segment,
+    segment, strip, symbol, version, Elf32Addr, Elf32Off, Elf32Word,
 };
 
 #[repr(C)]
@@ -14,45 +18,929 @@ pub struct ELF32 {
 
 impl ELF32 {
     /// add a section with creating new entry of section table and etc.
+    /// file offsets are not updated, so call `finalize()` before creating a binary.
     pub fn add_section(&mut self, mut sct: section::Section32) {
         // ehdr.e_shstrndxの変更のために計算
         let is_section_name_table = sct.name == ".shstrtab";
 
-        // 新しいセクションのsh_name等を計算する為に
-        // 現在の末尾のセクションを取得する
-        let last_sct_idx = self.sections.len() - 1;
-        self.fill_elf_info(&mut sct, last_sct_idx, &self.sections[last_sct_idx]);
-
-        // セクションの追加 => SHTの開始オフセットが変更される
-        self.ehdr.e_shoff += sct.header.sh_size;
-        self.ehdr.e_shnum += 1;
+        self.fill_elf_info(&mut sct);
 
+        // 末尾に追加するので，既存のセクション番号は変わらない
         self.sections.push(sct);
 
-        if is_section_name_table {
-            self.ehdr.e_shstrndx = self.sections.len() as u16 - 1;
-        }
+        let shstrndx = if is_section_name_table {
+            self.sections.len() - 1
+        } else {
+            self.shstrndx()
+        };
+        self.update_header_numbers(shstrndx);
     }
 
+    /// add a program header.
+    /// file offsets are not updated, so call `finalize()` before creating a binary.
     pub fn add_segment(&mut self, sgt: segment::Segment32) {
-        // PHTに追加される => SHTのオフセットと各セクションのオフセットが変更される
-        self.ehdr.e_shoff += segment::Phdr32::SIZE as u32;
+        self.segments.push(sgt);
+        self.update_header_numbers(self.shstrndx());
+    }
+
+    /// remove the section at `sct_idx` and fix up all section indices referring to others.
+    /// references to the removed section(`sh_link`, `st_shndx` and etc.) become 0.
+    /// return `None` if `sct_idx` is 0 or out of range.
+    pub fn remove_section(&mut self, sct_idx: usize) -> Option<Section32> {
+        if sct_idx == 0 || sct_idx >= self.sections.len() {
+            return None;
+        }
+        let index_map: Vec<Option<usize>> = (0..self.sections.len())
+            .map(|idx| match idx.cmp(&sct_idx) {
+                std::cmp::Ordering::Less => Some(idx),
+                std::cmp::Ordering::Equal => None,
+                std::cmp::Ordering::Greater => Some(idx - 1),
+            })
+            .collect();
+
+        let removed = self.sections.remove(sct_idx);
+        self.remap_section_indices(&index_map);
+        Some(removed)
+    }
+
+    /// move the section at `from` to `to` like `Vec::remove()` and `Vec::insert()`,
+    /// and fix up all section indices referring to others.
+    /// return `false` if either of them is 0 or out of range.
+    pub fn move_section(&mut self, from: usize, to: usize) -> bool {
+        let len = self.sections.len();
+        if from == 0 || to == 0 || from >= len || to >= len {
+            return false;
+        }
+        let mut order: Vec<usize> = (0..len).collect();
+        let moved = order.remove(from);
+        order.insert(to, moved);
+
+        // order[新しい番号] = 古い番号 なので逆写像を作る
+        let mut index_map = vec![None; len];
+        for (new_idx, old_idx) in order.iter().enumerate() {
+            index_map[*old_idx] = Some(new_idx);
+        }
+
+        let sct = self.sections.remove(from);
+        self.sections.insert(to, sct);
+        self.remap_section_indices(&index_map);
+        true
+    }
+
+    /// rename the section at `sct_idx` and rebuild `.shstrtab`.
+    /// when `.shstrtab` also holds symbol names, its strings are kept and the new name is appended.
+    /// return `false` if `sct_idx` is 0 or out of range.
+    pub fn rename_section(&mut self, sct_idx: usize, name: String) -> bool {
+        if sct_idx == 0 || sct_idx >= self.sections.len() {
+            return false;
+        }
+        self.sections[sct_idx].name = name;
+        self.rebuild_shstrtab();
+        true
+    }
+
+    /// remove debugging sections and symbols like GNU strip.
+    /// symbols referenced by relocations or section groups are always kept,
+    /// so relocation sections of `ET_REL` stay consistent.
+    /// `.symtab` and its string table are removed if no symbol remains in `StripMode::All`,
+    /// or in `StripMode::Unneeded` for executables and shared objects.
+    /// `finalize()` is called at the end.
+    pub fn strip(&mut self, opts: &strip::StripOptions) {
+        let mut removed: HashSet<usize> = self
+            .sections
+            .iter()
+            .enumerate()
+            .skip(1)
+            .filter(|(_, sct)| strip::is_debug_section(&sct.name))
+            .map(|(idx, _)| idx)
+            .collect();
+        // 削除されるセクションに対する再配置セクションも削除する
+        for (idx, sct) in self.sections.iter().enumerate() {
+            let ty = sct.header.get_type();
+            if (ty == section::Type::Rela || ty == section::Type::Rel)
+                && removed.contains(&(sct.header.sh_info as usize))
+            {
+                removed.insert(idx);
+            }
+        }
+
+        let symtab_indices: Vec<usize> = self
+            .sections
+            .iter()
+            .enumerate()
+            .filter(|(_, sct)| sct.header.get_type() == section::Type::SymTab)
+            .map(|(idx, _)| idx)
+            .collect();
+        for symtab_idx in symtab_indices {
+            self.strip_symbols(symtab_idx, &removed, opts);
+
+            let is_empty = matches!(
+                &self.sections[symtab_idx].contents,
+                section::Contents32::Symbols(syms) if syms.len() <= 1
+            );
+            let linked = |removed: &HashSet<usize>, target: usize| {
+                self.sections.iter().enumerate().any(|(idx, sct)| {
+                    idx != 0 && !removed.contains(&idx) && sct.header.sh_link as usize == target
+                })
+            };
+            if !strip::removes_empty_symtab(opts.mode, self.ehdr.get_type())
+                || !is_empty
+                || linked(&removed, symtab_idx)
+            {
+                continue;
+            }
+            removed.insert(symtab_idx);
+            // 文字列テーブルは他から参照されていなければ削除する
+            let strtab_idx = self.sections[symtab_idx].header.sh_link as usize;
+            if strtab_idx != 0 && strtab_idx != self.shstrndx() && !linked(&removed, strtab_idx) {
+                removed.insert(strtab_idx);
+            }
+        }
+
+        let mut removed: Vec<usize> = removed.into_iter().collect();
+        removed.sort_unstable();
+        for sct_idx in removed.into_iter().rev() {
+            self.remove_section(sct_idx);
+        }
+        self.finalize();
+    }
+
+    /// recompute `.shstrtab`, `sh_size` and every file offset from scratch.
+    /// call this after adding or modifying sections/segments, before creating a binary.
+    /// `sh_addr`, `p_vaddr`, `p_filesz` and `p_memsz` are kept as they are.
+    pub fn finalize(&mut self) {
+        self.rebuild_shstrtab();
+
+        // SHT_SYMTAB_SHNDXの要素数をシンボル数に合わせる
+        let symbol_numbers: Vec<Option<usize>> = self
+            .sections
+            .iter()
+            .map(|sct| match &sct.contents {
+                section::Contents32::Symbols(syms) => Some(syms.len()),
+                _ => None,
+            })
+            .collect();
         for sct in self.sections.iter_mut() {
-            sct.header.sh_offset += segment::Phdr32::SIZE as u32;
+            let sym_number = symbol_numbers
+                .get(sct.header.sh_link as usize)
+                .copied()
+                .flatten();
+            if let (section::Contents32::SymTabShNdx(ref mut indices), Some(sym_number)) =
+                (&mut sct.contents, sym_number)
+            {
+                indices.resize(sym_number, 0);
+            }
         }
-        self.ehdr.e_phnum += 1;
 
-        self.segments.push(sgt);
+        // NoBitsセクションはファイル上のバイトを持たないので，sh_sizeはそのまま
+        for sct in self.sections.iter_mut().skip(1) {
+            if sct.header.get_type() != section::Type::NoBits {
+                sct.header.sh_size = sct.contents.size() as u32;
+            }
+        }
+
+        // PHTはEhdrの直後に置く
+        self.ehdr.e_ehsize = header::Ehdr32::SIZE;
+        self.ehdr.e_phentsize = segment::Phdr32::SIZE as u16;
+        self.ehdr.e_shentsize = section::Shdr32::SIZE as u16;
+        let pht_size = segment::Phdr32::SIZE as u32 * self.segments.len() as u32;
+        self.ehdr.e_phoff = if self.segments.is_empty() {
+            0
+        } else {
+            header::Ehdr32::SIZE as u32
+        };
+
+        let sections: Vec<segment::SectionExtent> = self
+            .sections
+            .iter()
+            .map(|sct| (&sct.header).into())
+            .collect();
+        let segments: Vec<segment::SegmentExtent> = self
+            .segments
+            .iter()
+            .map(|seg| (&seg.header).into())
+            .collect();
+        let l = layout::layout(
+            header::Ehdr32::SIZE as u64 + pht_size as u64,
+            self.ehdr.e_phoff as u64,
+            &sections,
+            &segments,
+        );
+
+        for (sct, offset) in self.sections.iter_mut().zip(l.section_offsets) {
+            sct.header.sh_offset = offset as u32;
+        }
+        for (seg, offset) in self.segments.iter_mut().zip(l.segment_offsets) {
+            seg.header.p_offset = offset as u32;
+            if seg.header.get_type() == segment::Type::Phdr {
+                seg.header.p_filesz = pht_size;
+                seg.header.p_memsz = pht_size;
+            }
+        }
+
+        self.ehdr.e_shoff = layout::align_up(l.end, 4) as u32;
+        self.update_header_numbers(self.shstrndx());
+    }
+
+    /// replace the program headers with ones generated from section flags.
+    /// consecutive `SHF_ALLOC` sections which have the same `SHF_WRITE`/`SHF_EXECINSTR` are grouped into a `PT_LOAD`,
+    /// and `sh_addr` of them is assigned from `opts.base_address`.
+    /// `finalize()` is called at the end, so the file offsets are also updated.
+    pub fn generate_segments(&mut self, opts: segment::GenerateOptions) {
+        let sources: Vec<segment::SectionSource> = self
+            .sections
+            .iter()
+            .map(|sct| segment::SectionSource {
+                name: &sct.name,
+                ty: sct.header.get_type(),
+                flags: sct.header.get_flags(),
+                size: if sct.header.get_type() == section::Type::NoBits {
+                    sct.header.sh_size as u64
+                } else {
+                    sct.contents.size() as u64
+                },
+                align: sct.header.sh_addralign as u64,
+            })
+            .collect();
+        let generated = segment::generate(
+            &sources,
+            &opts,
+            header::Ehdr32::SIZE as u64,
+            segment::Phdr32::SIZE as u64,
+            4,
+        );
+
+        for (sct, addr) in self.sections.iter_mut().zip(generated.addresses) {
+            if let Some(addr) = addr {
+                sct.header.sh_addr = addr as u32;
+            }
+        }
+
+        self.segments = generated
+            .segments
+            .into_iter()
+            .map(|seg| {
+                let mut phdr = segment::Phdr32 {
+                    p_vaddr: seg.vaddr as u32,
+                    p_paddr: seg.vaddr as u32,
+                    p_filesz: seg.filesz as u32,
+                    p_memsz: seg.memsz as u32,
+                    p_align: seg.align as u32,
+                    ..Default::default()
+                };
+                phdr.set_type(seg.ty);
+                phdr.set_flags(seg.flags.iter());
+                segment::Segment32 { header: phdr }
+            })
+            .collect();
+        self.finalize();
+    }
+
+    /// the index of `.shstrtab`.
+    /// `SHN_XINDEX` in `e_shstrndx` is resolved with `sh_link` of section 0.
+    pub fn shstrndx(&self) -> usize {
+        if self.ehdr.e_shstrndx == section::SHN_XINDEX {
+            self.sections
+                .first()
+                .map_or(0, |sct| sct.header.sh_link as usize)
+        } else {
+            self.ehdr.e_shstrndx as usize
+        }
+    }
+
+    /// the index of the section which the symbol is defined in.
+    /// `SHN_XINDEX` in `st_shndx` is resolved with the `SHT_SYMTAB_SHNDX` section linked to the symbol table.
+    pub fn symbol_section_index(&self, symtab_idx: usize, sym_idx: usize) -> Option<usize> {
+        let sym = match &self.sections.get(symtab_idx)?.contents {
+            section::Contents32::Symbols(syms) => syms.get(sym_idx)?,
+            _ => return None,
+        };
+        if sym.st_shndx != section::SHN_XINDEX {
+            return Some(sym.st_shndx as usize);
+        }
+
+        self.sections.iter().find_map(|sct| match &sct.contents {
+            section::Contents32::SymTabShNdx(indices)
+                if sct.header.sh_link as usize == symtab_idx =>
+            {
+                indices.get(sym_idx).map(|shndx| *shndx as usize)
+            }
+            _ => None,
+        })
+    }
+
+    /// set the index of the section which the symbol is defined in.
+    /// an index not less than `SHN_LORESERVE` is stored in the `SHT_SYMTAB_SHNDX` section linked to the symbol table,
+    /// and `.symtab_shndx` is appended to the section table if it doesn't exist.
+    /// return `false` if the symbol is not found.
+    pub fn set_symbol_section_index(
+        &mut self,
+        symtab_idx: usize,
+        sym_idx: usize,
+        shndx: usize,
+    ) -> bool {
+        let sym_number = match self
+            .sections
+            .get_mut(symtab_idx)
+            .map(|sct| &mut sct.contents)
+        {
+            Some(section::Contents32::Symbols(syms)) if sym_idx < syms.len() => {
+                syms[sym_idx].st_shndx = if shndx >= section::SHN_LORESERVE as usize {
+                    section::SHN_XINDEX
+                } else {
+                    shndx as u16
+                };
+                syms.len()
+            }
+            _ => return false,
+        };
+
+        let extended = shndx >= section::SHN_LORESERVE as usize;
+        let shndx_idx = match self.symtab_shndx_index(symtab_idx) {
+            Some(shndx_idx) => shndx_idx,
+            None if extended => self.add_symtab_shndx(symtab_idx),
+            None => return true,
+        };
+        if let section::Contents32::SymTabShNdx(ref mut indices) = self.sections[shndx_idx].contents
+        {
+            indices.resize(indices.len().max(sym_number), 0);
+            // SHN_XINDEXでないシンボルの要素は0
+            indices[sym_idx] = if extended { shndx as Elf32Word } else { 0 };
+        }
+        true
+    }
+
+    /// the signature symbol name of the section group at `group_idx`
+    pub fn group_signature(&self, group_idx: usize) -> Option<&str> {
+        let group = self.sections.get(group_idx)?;
+        if !matches!(group.contents, section::Contents32::Group(_)) {
+            return None;
+        }
+
+        // sh_linkはシンボルテーブル，sh_infoはその中のシンボル番号
+        match &self.sections.get(group.header.sh_link as usize)?.contents {
+            section::Contents32::Symbols(syms) => syms
+                .get(group.header.sh_info as usize)
+                .map(|sym| sym.symbol_name.as_str()),
+            _ => None,
+        }
+    }
+
+    /// get the index of the section group which has the section at `sct_idx` as its member
+    pub fn group_of(&self, sct_idx: usize) -> Option<usize> {
+        self.sections.iter().position(|sct| match &sct.contents {
+            section::Contents32::Group(group) => group.sections.contains(&(sct_idx as Elf32Word)),
+            _ => false,
+        })
+    }
+
+    /// add a section group(`SHT_GROUP`) and its member sections.
+    /// the signature is the symbol at `signature_idx` in the symbol table at `symtab_idx`.
+    /// return the index of the group section.
+    pub fn add_group(
+        &mut self,
+        name: String,
+        symtab_idx: usize,
+        signature_idx: usize,
+        flags: Elf32Word,
+        members: Vec<section::Section32>,
+    ) -> usize {
+        // 末尾に追加されるので，グループとメンバの番号は連続する
+        let group_idx = self.sections.len();
+        let member_indices = (0..members.len())
+            .map(|i| (group_idx + 1 + i) as Elf32Word)
+            .collect();
+
+        let hdr = section::ShdrPreparation32 {
+            sh_addralign: section::Group::ENTRY_SIZE as Elf32Word,
+            ..Default::default()
+        }
+        .ty(section::Type::Group)
+        .link(symtab_idx as Elf32Word)
+        .info(signature_idx as Elf32Word);
+        let mut group = section::Section32::new(
+            name,
+            hdr,
+            section::Contents32::Group(section::Group::new(flags, member_indices)),
+        );
+        group.header.sh_entsize = section::Group::ENTRY_SIZE as Elf32Word;
+        self.add_section(group);
+
+        for mut member in members {
+            member.header.sh_flags |= Elf32Word::from(section::Flag::Group);
+            self.add_section(member);
+        }
+
+        group_idx
+    }
+
+    /// find a defined dynamic symbol by name.
+    /// `.gnu.hash` or `.hash` linked with `.dynsym` is used like the dynamic linker,
+    /// and all symbols are scanned if neither exists.
+    pub fn lookup_dynamic_symbol(&self, name: &str) -> Option<&symbol::Symbol32> {
+        let dynsym_idx = self
+            .sections
+            .iter()
+            .position(|sct| sct.header.get_type() == section::Type::DynSym)?;
+        let syms = match &self.sections[dynsym_idx].contents {
+            section::Contents32::Symbols(syms) => syms,
+            _ => return None,
+        };
+
+        // 未定義シンボルは検索対象外
+        let matches = |sym_idx: usize| {
+            syms.get(sym_idx)
+                .is_some_and(|sym| sym.symbol_name == name && sym.st_shndx != section::SHN_UNDEF)
+        };
+        let hash_table = |ty: section::Type| {
+            self.sections
+                .iter()
+                .find(|sct| {
+                    sct.header.get_type() == ty && sct.header.sh_link as usize == dynsym_idx
+                })
+                .map(|sct| &sct.contents)
+        };
+
+        let sym_idx = match (
+            hash_table(section::Type::GNUHash),
+            hash_table(section::Type::Hash),
+        ) {
+            (Some(section::Contents32::GnuHash(table)), _) => {
+                table.lookup(header::Class::Bit32, name, matches)
+            }
+            (_, Some(section::Contents32::SysvHash(table))) => table.lookup(name, matches),
+            _ => (0..syms.len()).find(|sym_idx| matches(*sym_idx)),
+        };
+        sym_idx.map(|sym_idx| &syms[sym_idx])
+    }
+
+    /// needed libraries, soname, rpath/runpath and flags in the dynamic section.
+    /// strings are resolved through the string table at `DT_STRTAB`,
+    /// or the one linked by `sh_link` if no section is located at the address.
+    pub fn dynamic_info(&self) -> Option<dynamic::DynamicInfo> {
+        let dyn_sct = self
+            .sections
+            .iter()
+            .find(|sct| matches!(sct.contents, section::Contents32::Dynamics(_)))?;
+        let dynamics = match &dyn_sct.contents {
+            section::Contents32::Dynamics(dynamics) => dynamics,
+            _ => return None,
+        };
+
+        let strtab_addr = dynamics
+            .iter()
+            .find(|dyn_ent| dyn_ent.get_type() == dynamic::EntryType::StrTab)
+            .map(|dyn_ent| dyn_ent.d_un);
+        let strtab = strtab_addr
+            .and_then(|addr| {
+                self.sections.iter().find(|sct| {
+                    sct.header.sh_addr == addr
+                        && matches!(sct.contents, section::Contents32::StrTab(_))
+                })
+            })
+            .or_else(|| self.sections.get(dyn_sct.header.sh_link as usize));
+        let strs = match strtab.map(|sct| &sct.contents) {
+            Some(section::Contents32::StrTab(strs)) => strs.as_slice(),
+            _ => &[],
+        };
+
+        Some(dynamic::DynamicInfo::from_entries(
+            dynamics
+                .iter()
+                .map(|dyn_ent| (dyn_ent.get_type(), dyn_ent.d_un as u64)),
+            |idx| section::string_at(strs, idx as usize).map(|s| s.to_string()),
+        ))
+    }
+
+    /// the version of the dynamic symbol at `sym_idx` looked up via `.gnu.version`.
+    /// return `None` if the symbol is local or unversioned.
+    pub fn symbol_version(&self, sym_idx: usize) -> Option<version::SymbolVersion> {
+        let versym = self.sections.iter().find_map(|sct| match &sct.contents {
+            section::Contents32::VersionSymbols(versyms) => versyms.get(sym_idx).copied(),
+            _ => None,
+        })?;
+        let ver_idx = version::versym_index(versym);
+        if ver_idx == version::VER_NDX_LOCAL || ver_idx == version::VER_NDX_GLOBAL {
+            return None;
+        }
+        let hidden = versym & version::VERSYM_HIDDEN != 0;
+
+        // .gnu.version_r => .gnu.version_d の順に，バージョン番号が一致するものを探す
+        for sct in self.sections.iter() {
+            match &sct.contents {
+                section::Contents32::VersionRequirements(reqs) => {
+                    for req in reqs.iter() {
+                        if let Some(entry) = req.entries.iter().find(|e| e.other == ver_idx) {
+                            return Some(version::SymbolVersion {
+                                name: entry.name.v.clone(),
+                                file: Some(req.file.v.clone()),
+                                hidden,
+                            });
+                        }
+                    }
+                }
+                section::Contents32::VersionDefinitions(defs) => {
+                    if let Some(def) = defs.iter().find(|d| d.ndx == ver_idx) {
+                        return Some(version::SymbolVersion {
+                            name: def.name().to_string(),
+                            file: None,
+                            hidden,
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        None
     }
 
+    /// the dynamic symbol name decorated with its version like `printf@GLIBC_2.2.5`
+    pub fn versioned_dynamic_symbol_name(&self, sym_idx: usize) -> Option<String> {
+        let sym = self.sections.iter().find_map(|sct| match &sct.contents {
+            section::Contents32::Symbols(syms)
+                if sct.header.get_type() == section::Type::DynSym =>
+            {
+                syms.get(sym_idx)
+            }
+            _ => None,
+        })?;
+
+        match self.symbol_version(sym_idx) {
+            Some(ver) => Some(ver.decorate(&sym.symbol_name)),
+            None => Some(sym.symbol_name.clone()),
+        }
+    }
+
+    /// whether the file requires the version(e.g. `GLIBC_2.34`) from any shared object
+    pub fn requires_version(&self, name: &str) -> bool {
+        self.sections.iter().any(|sct| match &sct.contents {
+            section::Contents32::VersionRequirements(reqs) => reqs
+                .iter()
+                .any(|req| req.entries.iter().any(|e| e.name.v == name)),
+            _ => false,
+        })
+    }
+
+    /// translate a virtual address into a file offset via `PT_LOAD` segments.
+    /// addresses in the zero-filled part(`p_filesz..p_memsz`) have no offset.
+    pub fn vaddr_to_offset(&self, vaddr: Elf32Addr) -> Option<Elf32Off> {
+        self.segments
+            .iter()
+            .map(|sgt| &sgt.header)
+            .find(|phdr| {
+                phdr.get_type() == segment::Type::Load
+                    && phdr.p_vaddr <= vaddr
+                    && vaddr - phdr.p_vaddr < phdr.p_filesz
+            })
+            .map(|phdr| phdr.p_offset + (vaddr - phdr.p_vaddr))
+    }
+
+    /// translate a file offset into a virtual address via `PT_LOAD` segments.
+    pub fn offset_to_vaddr(&self, offset: Elf32Off) -> Option<Elf32Addr> {
+        self.segments
+            .iter()
+            .map(|sgt| &sgt.header)
+            .find(|phdr| {
+                phdr.get_type() == segment::Type::Load
+                    && phdr.p_offset <= offset
+                    && offset - phdr.p_offset < phdr.p_filesz
+            })
+            .map(|phdr| phdr.p_vaddr + (offset - phdr.p_offset))
+    }
+
+    /// the index of the `PT_LOAD` segment which maps `addr`, including the zero-filled part.
+    pub fn segment_containing_addr(&self, addr: Elf32Addr) -> Option<usize> {
+        self.segments.iter().position(|sgt| {
+            sgt.header.get_type() == segment::Type::Load
+                && sgt.header.p_vaddr <= addr
+                && addr - sgt.header.p_vaddr < sgt.header.p_memsz
+        })
+    }
+
+    /// the index of the allocated section which contains `addr`.
+    /// `.tbss` is skipped since it overlaps the following sections in the address space.
+    pub fn section_containing_addr(&self, addr: Elf32Addr) -> Option<usize> {
+        self.sections.iter().position(|sct| {
+            let flags = sct.header.get_flags();
+            let is_tbss = flags.contains(&section::Flag::TLS)
+                && sct.header.get_type() == section::Type::NoBits;
+            flags.contains(&section::Flag::Alloc)
+                && !is_tbss
+                && sct.header.sh_addr <= addr
+                && addr - sct.header.sh_addr < sct.header.sh_size
+        })
+    }
+
+    /// read `len` bytes at `vaddr` as the loader maps them.
+    /// bytes in `p_filesz..p_memsz`(e.g. `.bss`) and gaps between sections are zero.
+    /// return `None` if any byte in the range is not mapped by `PT_LOAD`,
+    /// or if the range has file bytes but no section(or header) covers them,
+    /// e.g. the file has no section headers.
+    pub fn read_memory(&self, vaddr: Elf32Addr, len: usize) -> Option<Vec<u8>> {
+        let end = vaddr.checked_add(Elf32Addr::try_from(len).ok()?)?;
+
+        // 範囲全体がPT_LOADでマップされているか確かめる
+        // ファイル上のバイトを持つ部分を含むかも調べておく
+        let mut addr = vaddr;
+        let mut file_backed = false;
+        while addr < end {
+            let phdr = &self.segments[self.segment_containing_addr(addr)?].header;
+            file_backed |= addr < phdr.p_vaddr.checked_add(phdr.p_filesz)?;
+            addr = phdr.p_vaddr.checked_add(phdr.p_memsz)?;
+        }
+
+        let data = self.ehdr.get_data();
+        let mut memory = vec![0; len];
+        let mut covered = false;
+        let mut overlay = |start: Elf32Addr, bytes: &[u8]| {
+            let stop = end.min(start.saturating_add(bytes.len() as Elf32Addr));
+            let from = start.max(vaddr);
+            if from < stop {
+                memory[(from - vaddr) as usize..(stop - vaddr) as usize]
+                    .copy_from_slice(&bytes[(from - start) as usize..(stop - start) as usize]);
+                covered = true;
+            }
+        };
+
+        for sct in self.sections.iter() {
+            if !sct.header.get_flags().contains(&section::Flag::Alloc)
+                || sct.header.get_type() == section::Type::NoBits
+            {
+                continue;
+            }
+            // 範囲に重なるセクションだけをバイト列にする
+            let sct_end = sct.header.sh_addr.saturating_add(sct.header.sh_size);
+            if sct_end <= vaddr || end <= sct.header.sh_addr {
+                continue;
+            }
+            let bytes = sct.to_bytes(data);
+            overlay(
+                sct.header.sh_addr,
+                &bytes[..bytes.len().min(sct.header.sh_size as usize)],
+            );
+        }
+
+        // ELFヘッダとプログラムヘッダテーブルは先頭のPT_LOADにマップされることが多い
+        if let Some(ehdr_addr) = self.offset_to_vaddr(0) {
+            overlay(ehdr_addr, &self.ehdr.to_bytes(data));
+        }
+        if let Some(pht_addr) = self.offset_to_vaddr(self.ehdr.e_phoff) {
+            let pht: Vec<u8> = self
+                .segments
+                .iter()
+                .flat_map(|sgt| sgt.header.to_bytes(data))
+                .collect();
+            overlay(pht_addr, &pht);
+        }
+
+        // セクションヘッダが無い等で，ファイル上の内容が一切分からない
+        if file_backed && !covered {
+            return None;
+        }
+        Some(memory)
+    }
+
+    /// indices of sections in the segment at `seg_idx`, like `readelf -l` shows.
+    /// `.tbss` is only in `PT_TLS` as binutils does.
+    pub fn sections_in_segment(&self, seg_idx: usize) -> Vec<usize> {
+        let seg = match self.segments.get(seg_idx) {
+            Some(seg) => segment::SegmentExtent::from(&seg.header),
+            None => return Vec::new(),
+        };
+
+        // NULLセクションは対象外
+        (1..self.sections.len())
+            .filter(|sct_idx| {
+                let sct = segment::SectionExtent::from(&self.sections[*sct_idx].header);
+                segment::section_in_segment(&sct, &seg)
+            })
+            .collect()
+    }
+
+    /// section indices for each segment
+    pub fn segment_sections(&self) -> Vec<Vec<usize>> {
+        (0..self.segments.len())
+            .map(|seg_idx| self.sections_in_segment(seg_idx))
+            .collect()
+    }
+
+    /// segment indices for each section, the inverse of `segment_sections()`
+    pub fn section_segments(&self) -> Vec<Vec<usize>> {
+        let mut mapping = vec![Vec::new(); self.sections.len()];
+        for (seg_idx, sct_indices) in self.segment_sections().into_iter().enumerate() {
+            for sct_idx in sct_indices {
+                mapping[sct_idx].push(seg_idx);
+            }
+        }
+        mapping
+    }
+
+    /// assign `sh_addr` of `SHF_ALLOC` sections from `base` in the order of the section table.
+    /// return the end address of the last section.
+    pub fn assign_section_addresses(&mut self, base: Elf32Addr) -> Elf32Addr {
+        let mut addr = base;
+        for sct in self.sections.iter_mut() {
+            if !sct.header.get_flags().contains(&section::Flag::Alloc) {
+                continue;
+            }
+            let align = sct.header.sh_addralign.max(1);
+            addr = addr.div_ceil(align) * align;
+            sct.header.sh_addr = addr;
+            addr += sct.header.sh_size;
+        }
+        addr
+    }
+
+    /// apply all `SHT_RELA` sections to `Contents32::Raw` of their target sections.
+    /// `SHT_REL` sections aren't supported and make this fail.
+    /// symbols are resolved with `sh_addr` of the section they're defined in,
+    /// and undefined ones are looked up by `resolve`.
+    pub fn apply_relocations<F>(&mut self, resolve: F) -> Result<(), relocation::RelocationError>
+    where
+        F: Fn(&str) -> Option<Elf32Addr>,
+    {
+        if self.ehdr.get_type() != header::Type::Rel {
+            return Err(relocation::RelocationError::NotRelocatable);
+        }
+        let machine = self.ehdr.get_machine();
+        let data = self.ehdr.get_data();
+
+        for rela_idx in 0..self.sections.len() {
+            let relas = match &self.sections[rela_idx].contents {
+                section::Contents32::RelaSymbols(relas) => relas.clone(),
+                // 暗黙の加数を持つSHT_RELは適用できないので，黙って飛ばさずにエラーとする
+                section::Contents32::RelSymbols(_) => {
+                    return Err(relocation::RelocationError::Unsupported {
+                        name: format!("SHT_REL section {}", self.sections[rela_idx].name),
+                    })
+                }
+                _ => continue,
+            };
+            let symtab_idx = self.sections[rela_idx].header.sh_link as usize;
+            let target_idx = self.sections[rela_idx].header.sh_info as usize;
+            let target_addr = match self.sections.get(target_idx) {
+                Some(sct) => sct.header.sh_addr,
+                None => {
+                    return Err(relocation::RelocationError::InvalidTarget {
+                        sct_idx: target_idx,
+                    })
+                }
+            };
+
+            // 書き込み前に全てのシンボルアドレスを解決しておく
+            let mut addresses = Vec::with_capacity(relas.len());
+            for rela in relas.iter() {
+                addresses.push(self.relocation_symbol_address(
+                    symtab_idx,
+                    rela.get_sym() as usize,
+                    &resolve,
+                )?);
+            }
+
+            let buf = match &mut self.sections[target_idx].contents {
+                section::Contents32::Raw(buf) => buf,
+                _ => {
+                    return Err(relocation::RelocationError::InvalidTarget {
+                        sct_idx: target_idx,
+                    })
+                }
+            };
+            for (rela, s) in relas.iter().zip(addresses) {
+                relocation::relocate(
+                    rela.kind(&machine),
+                    data,
+                    s as u64,
+                    rela.get_addend() as i64,
+                    target_addr.wrapping_add(rela.get_offset()) as u64,
+                    buf,
+                    rela.get_offset() as usize,
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn relocation_symbol_address<F>(
+        &self,
+        symtab_idx: usize,
+        sym_idx: usize,
+        resolve: &F,
+    ) -> Result<Elf32Addr, relocation::RelocationError>
+    where
+        F: Fn(&str) -> Option<Elf32Addr>,
+    {
+        // シンボルを持たない再配置
+        if sym_idx == 0 {
+            return Ok(0);
+        }
+        let invalid_symbol = relocation::RelocationError::InvalidSymbol {
+            symtab_idx,
+            sym_idx,
+        };
+        let sym = match self.sections.get(symtab_idx).map(|sct| &sct.contents) {
+            Some(section::Contents32::Symbols(syms)) => syms.get(sym_idx).ok_or(invalid_symbol)?,
+            _ => return Err(invalid_symbol),
+        };
+
+        match sym.st_shndx {
+            section::SHN_ABS => Ok(sym.st_value),
+            section::SHN_UNDEF | section::SHN_COMMON => match resolve(&sym.symbol_name) {
+                Some(addr) => Ok(addr),
+                None if sym.get_bind() == symbol::Bind::Weak => Ok(0),
+                None => Err(relocation::RelocationError::UndefinedSymbol {
+                    name: sym.symbol_name.clone(),
+                }),
+            },
+            _ => {
+                let sct = self
+                    .symbol_section_index(symtab_idx, sym_idx)
+                    .and_then(|sct_idx| self.sections.get(sct_idx))
+                    .ok_or(relocation::RelocationError::InvalidSymbol {
+                        symtab_idx,
+                        sym_idx,
+                    })?;
+                Ok(sct.header.sh_addr.wrapping_add(sym.st_value))
+            }
+        }
+    }
+
+    /// create a flat image like `objcopy -O binary`.
+    /// the file bytes of all `PT_LOAD`s are placed relative to the lowest `p_paddr`,
+    /// and gaps are filled with zero.
+    /// broken `PT_LOAD`s or an image larger than `objcopy::MAX_RAW_BINARY_SIZE` are errors.
+    pub fn to_raw_binary(&self) -> Result<Vec<u8>, objcopy::ObjcopyError> {
+        let file_binary = self.to_bytes(self.ehdr.get_data());
+        let loads: Vec<objcopy::LoadImage> = self
+            .segments
+            .iter()
+            .filter(|seg| seg.header.get_type() == segment::Type::Load)
+            .map(|seg| objcopy::LoadImage {
+                paddr: seg.header.p_paddr as u64,
+                offset: seg.header.p_offset as u64,
+                filesz: seg.header.p_filesz as u64,
+            })
+            .collect();
+        objcopy::flatten_loads(&file_binary, &loads)
+    }
+
+    /// write the contents of the section named `name` to `file_path` like `objcopy --dump-section`.
+    pub fn dump_section(&self, name: &str, file_path: &str) -> Result<(), objcopy::ObjcopyError> {
+        let sct = self
+            .sections
+            .iter()
+            .find(|sct| sct.name == name)
+            .ok_or_else(|| objcopy::ObjcopyError::SectionNotFound {
+                name: name.to_string(),
+            })?;
+        if sct.header.get_type() == section::Type::NoBits {
+            return Err(objcopy::ObjcopyError::NoContents {
+                name: name.to_string(),
+            });
+        }
+        objcopy::write_file(file_path, &sct.to_bytes(self.ehdr.get_data()))
+    }
+
+    /// add a section whose contents are read from `file_path` like `objcopy --add-section`.
+    /// return the index of the new section.
+    pub fn add_section_from_file(
+        &mut self,
+        name: String,
+        hdr: section::ShdrPreparation32,
+        file_path: &str,
+    ) -> Result<usize, objcopy::ObjcopyError> {
+        let bytes = objcopy::read_file(file_path)?;
+        self.add_section(Section32::new(name, hdr, section::Contents32::Raw(bytes)));
+        Ok(self.sections.len() - 1)
+    }
+
+    /// create a little-endian binary
     pub fn to_le_bytes(&self) -> Vec<u8> {
+        self.to_bytes(header::Data::LSB2)
+    }
+
+    /// create a big-endian binary
+    pub fn to_be_bytes(&self) -> Vec<u8> {
+        self.to_bytes(header::Data::MSB2)
+    }
+
+    /// create a binary with the given data encoding.
+    /// `e_ident[EI_DATA]` of the emitted header is set to `data`, whatever `ehdr` has.
+    pub fn to_bytes(&self, data: header::Data) -> Vec<u8> {
         let mut file_binary: Vec<u8> = Vec::new();
 
-        let mut header_binary = self.ehdr.to_le_bytes();
+        // 読み手は EI_DATA を見てエンディアンを決めるので，出力に合わせて書き換える
+        let mut ehdr = self.ehdr;
+        ehdr.set_data(data);
+        let mut header_binary = ehdr.to_bytes(data);
         file_binary.append(&mut header_binary);
 
+        if !self.segments.is_empty() {
+            layout::pad_to(&mut file_binary, self.ehdr.e_phoff as u64);
+        }
         for seg in self.segments.iter() {
-            let mut phdr_binary = seg.header.to_le_bytes();
+            let mut phdr_binary = seg.header.to_bytes(data);
             file_binary.append(&mut phdr_binary);
         }
 
@@ -60,63 +948,390 @@ impl ELF32 {
         sections.sort_by_key(|sct| sct.header.sh_offset);
 
         for (idx, sct) in sections.iter().enumerate() {
-            let mut section_binary = sct.to_le_bytes();
-            if idx >= 1 && sct.header.sh_addralign > 1 {
-                if sct.header.sh_addr == 0 {
-                    if file_binary.len() < sct.header.sh_offset as usize {
-                        file_binary.append(&mut vec![
-                            0x00;
-                            sct.header.sh_offset as usize
-                                - file_binary.len()
-                        ]);
-                    }
-                } else {
-                    if file_binary.len() < sct.header.sh_addr as usize {
-                        file_binary.append(&mut vec![
-                            0x00;
-                            sct.header.sh_addr as usize
-                                - file_binary.len()
-                        ]);
-                    }
-                };
+            // NoBitsセクションはファイル上のバイトを持たない
+            if sct.header.get_type() == section::Type::NoBits {
+                continue;
+            }
+            let mut section_binary = sct.to_bytes(data);
+            // sh_addrは仮想アドレスなので，ファイル上の位置にはsh_offsetを用いる
+            if idx >= 1 {
+                layout::pad_to(&mut file_binary, sct.header.sh_offset as u64);
             }
 
             file_binary.append(&mut section_binary);
         }
 
-        if file_binary.len() < self.ehdr.e_shoff as usize {
-            file_binary.append(&mut vec![
-                0x00;
-                self.ehdr.e_shoff as usize - file_binary.len()
-            ]);
-        }
+        layout::pad_to(&mut file_binary, self.ehdr.e_shoff as u64);
 
         for sct in self.sections.iter() {
-            let mut shdr_binary = sct.header.to_le_bytes();
+            let mut shdr_binary = sct.header.to_bytes(data);
             file_binary.append(&mut shdr_binary);
         }
         file_binary
     }
 
-    /// sh_nameやsh_offset等の調整
-    fn fill_elf_info(&self, new_sct: &mut Section32, prev_sct_idx: usize, prev_sct: &Section32) {
-        let prev_name_idx = prev_sct.header.sh_name;
-        let prev_name_len = prev_sct.name.as_bytes().len() as u32;
-        let prev_offset = prev_sct.header.sh_offset;
-        let prev_size = prev_sct.header.sh_size;
+    /// e_shnum/e_shstrndx/e_phnumの更新
+    /// 収まらない値はNULLセクションのsh_size/sh_link/sh_infoに格納する
+    fn update_header_numbers(&mut self, shstrndx: usize) {
+        let shnum = self.sections.len();
+        let phnum = self.segments.len();
+        let (mut sh_size, mut sh_link, mut sh_info) = (0, 0, 0);
 
-        // <prev_section_name> の後に0x00が入るので，+1
-        new_sct.header.sh_name = prev_name_idx + prev_name_len + 1;
+        if shnum >= section::SHN_LORESERVE as usize {
+            self.ehdr.e_shnum = 0;
+            sh_size = shnum as u32;
+        } else {
+            self.ehdr.e_shnum = shnum as u16;
+        }
 
-        // NULLセクションのすぐ次に挿入する場合，
-        // sh_offsetはEhdr32::SIZE + PHT's SIZEという感じになる．
-        if prev_sct_idx == 0 {
-            new_sct.header.sh_offset = header::Ehdr32::SIZE as u32
-                + segment::Phdr32::SIZE as u32 * self.segments.len() as u32;
+        if shstrndx >= section::SHN_LORESERVE as usize {
+            self.ehdr.e_shstrndx = section::SHN_XINDEX;
+            sh_link = shstrndx as u32;
         } else {
-            new_sct.header.sh_offset = prev_offset + prev_size;
+            self.ehdr.e_shstrndx = shstrndx as u16;
         }
 
+        if phnum >= segment::PN_XNUM as usize {
+            self.ehdr.e_phnum = segment::PN_XNUM;
+            sh_info = phnum as u32;
+        } else {
+            self.ehdr.e_phnum = phnum as u16;
+        }
+
+        if let Some(null_sct) = self.sections.first_mut() {
+            null_sct.header.sh_size = sh_size;
+            null_sct.header.sh_link = sh_link;
+            null_sct.header.sh_info = sh_info;
+        }
+    }
+
+    /// シンボルテーブルから不要なシンボルを取り除き，
+    /// 再配置・グループ・SHT_SYMTAB_SHNDXのシンボル番号と文字列テーブルを更新する
+    fn strip_symbols(
+        &mut self,
+        symtab_idx: usize,
+        removed: &HashSet<usize>,
+        opts: &strip::StripOptions,
+    ) {
+        // 再配置やグループのシグネチャとして参照されているシンボル
+        let mut referenced: HashSet<usize> = HashSet::new();
+        for (idx, sct) in self.sections.iter().enumerate() {
+            if removed.contains(&idx) || sct.header.sh_link as usize != symtab_idx {
+                continue;
+            }
+            match &sct.contents {
+                section::Contents32::RelaSymbols(relas) => {
+                    referenced.extend(relas.iter().map(|rela| rela.get_sym() as usize))
+                }
+                section::Contents32::RelSymbols(rels) => {
+                    referenced.extend(rels.iter().map(|rel| rel.get_sym() as usize))
+                }
+                section::Contents32::Group(_) => {
+                    referenced.insert(sct.header.sh_info as usize);
+                }
+                _ => {}
+            }
+        }
+
+        let syms = match &self.sections[symtab_idx].contents {
+            section::Contents32::Symbols(syms) => syms,
+            _ => return,
+        };
+        let keeps: Vec<bool> = syms
+            .iter()
+            .enumerate()
+            .map(|(sym_idx, sym)| {
+                let in_removed_section = self
+                    .symbol_section_index(symtab_idx, sym_idx)
+                    .is_some_and(|shndx| removed.contains(&shndx));
+                sym_idx == 0
+                    || strip::keeps_symbol(
+                        opts,
+                        self.ehdr.get_type(),
+                        &sym.symbol_name,
+                        sym.get_bind(),
+                        referenced.contains(&sym_idx),
+                        in_removed_section,
+                    )
+            })
+            .collect();
+
+        let mut index_map = vec![None; keeps.len()];
+        let mut new_idx = 0;
+        for (old_idx, keep) in keeps.iter().enumerate() {
+            if *keep {
+                index_map[old_idx] = Some(new_idx);
+                new_idx += 1;
+            }
+        }
+        let remap = |sym_idx: usize| index_map.get(sym_idx).copied().flatten().unwrap_or(0);
+
+        // 参照しているセクションの更新
+        for sct in self.sections.iter_mut() {
+            if sct.header.sh_link as usize != symtab_idx {
+                continue;
+            }
+            match sct.contents {
+                section::Contents32::RelaSymbols(ref mut relas) => {
+                    for rela in relas.iter_mut() {
+                        let sym = remap(rela.get_sym() as usize) as Elf32Word;
+                        rela.set_info((sym << 8) | rela.get_type());
+                    }
+                }
+                section::Contents32::RelSymbols(ref mut rels) => {
+                    for rel in rels.iter_mut() {
+                        let sym = remap(rel.get_sym() as usize) as Elf32Word;
+                        rel.set_info((sym << 8) | rel.get_type());
+                    }
+                }
+                section::Contents32::SymTabShNdx(ref mut indices) => {
+                    let mut keep = keeps.iter();
+                    indices.retain(|_| *keep.next().unwrap_or(&true));
+                }
+                section::Contents32::Group(_) => {
+                    sct.header.sh_info = remap(sct.header.sh_info as usize) as u32;
+                }
+                _ => {}
+            }
+        }
+
+        let strtab_idx = self.sections[symtab_idx].header.sh_link as usize;
+        // 文字列テーブルがこのシンボルテーブル専用なら作り直す
+        let rebuild_strtab =
+            strtab_idx != 0
+                && strtab_idx != self.shstrndx()
+                && strtab_idx < self.sections.len()
+                && self.sections.iter().enumerate().all(|(idx, sct)| {
+                    idx == symtab_idx || sct.header.sh_link as usize != strtab_idx
+                });
+
+        let symtab = &mut self.sections[symtab_idx];
+        if let section::Contents32::Symbols(ref mut syms) = symtab.contents {
+            let mut keep = keeps.iter();
+            syms.retain(|_| *keep.next().unwrap());
+            // sh_infoは最初の非ローカルシンボルの番号
+            symtab.header.sh_info = syms
+                .iter()
+                .position(|sym| sym.get_bind() != symbol::Bind::Local)
+                .unwrap_or(syms.len()) as u32;
+
+            if !rebuild_strtab {
+                return;
+            }
+            let mut names = Vec::new();
+            let mut name_indices: HashMap<String, u32> = HashMap::new();
+            let mut name_idx = 1;
+            for sym in syms.iter_mut() {
+                if sym.symbol_name.is_empty() {
+                    sym.st_name = 0;
+                    continue;
+                }
+                sym.st_name = *name_indices
+                    .entry(sym.symbol_name.clone())
+                    .or_insert_with(|| {
+                        let idx = name_idx;
+                        name_idx += sym.symbol_name.len() as u32 + 1;
+                        names.push(sym.symbol_name.clone());
+                        idx
+                    });
+            }
+            self.sections[strtab_idx].contents = section::Contents32::new_string_table(names);
+        }
+    }
+
+    /// セクションの並びが変わった後に，セクション番号を参照している箇所を書き換える
+    /// `index_map[古い番号]`は新しい番号で，削除されたセクションはNone
+    fn remap_section_indices(&mut self, index_map: &[Option<usize>]) {
+        let old_shstrndx = self.shstrndx();
+        let remap = |idx: usize| index_map.get(idx).copied().flatten().unwrap_or(0);
+
+        // SHN_XINDEXが必要になったシンボル(シンボルテーブル, シンボル, 新しい番号)
+        let mut extended = Vec::new();
+        for (sct_idx, sct) in self.sections.iter_mut().enumerate().skip(1) {
+            if sct.header.sh_link != 0 {
+                sct.header.sh_link = remap(sct.header.sh_link as usize) as u32;
+            }
+            // 再配置セクションのsh_infoは適用先のセクション番号
+            let ty = sct.header.get_type();
+            if ty == section::Type::Rela
+                || ty == section::Type::Rel
+                || sct.header.get_flags().contains(&section::Flag::InfoLink)
+            {
+                sct.header.sh_info = remap(sct.header.sh_info as usize) as u32;
+            }
+
+            match sct.contents {
+                section::Contents32::Symbols(ref mut syms) => {
+                    for (sym_idx, sym) in syms.iter_mut().enumerate() {
+                        let shndx = sym.st_shndx;
+                        if shndx == section::SHN_UNDEF || shndx >= section::SHN_LORESERVE {
+                            continue;
+                        }
+                        let new_idx = remap(shndx as usize);
+                        if new_idx >= section::SHN_LORESERVE as usize {
+                            extended.push((sct_idx, sym_idx, new_idx));
+                        } else {
+                            sym.st_shndx = new_idx as u16;
+                        }
+                    }
+                }
+                section::Contents32::SymTabShNdx(ref mut indices) => {
+                    for shndx in indices.iter_mut().filter(|shndx| **shndx != 0) {
+                        *shndx = remap(*shndx as usize) as u32;
+                    }
+                }
+                section::Contents32::Group(ref mut group) => {
+                    group.sections = group
+                        .sections
+                        .iter()
+                        .filter_map(|idx| index_map.get(*idx as usize).copied().flatten())
+                        .map(|idx| idx as u32)
+                        .collect();
+                }
+                _ => {}
+            }
+        }
+
+        let shstrndx = remap(old_shstrndx);
+        self.update_header_numbers(shstrndx);
+        // 既存のSHT_SYMTAB_SHNDXを書き換えた後に設定する
+        for (symtab_idx, sym_idx, new_idx) in extended {
+            self.set_symbol_section_index(symtab_idx, sym_idx, new_idx);
+        }
+        self.rebuild_shstrtab();
+    }
+
+    /// シンボルテーブルにリンクされたSHT_SYMTAB_SHNDXのセクション番号
+    fn symtab_shndx_index(&self, symtab_idx: usize) -> Option<usize> {
+        self.sections.iter().position(|sct| {
+            sct.header.sh_link as usize == symtab_idx
+                && matches!(sct.contents, section::Contents32::SymTabShNdx(_))
+        })
+    }
+
+    /// 空の.symtab_shndxを末尾に追加する
+    /// 末尾に置くので，既存のセクション番号は変わらない
+    fn add_symtab_shndx(&mut self, symtab_idx: usize) -> usize {
+        let hdr = section::ShdrPreparation32 {
+            sh_addralign: 4,
+            ..Default::default()
+        }
+        .ty(section::Type::SymTabShNdx)
+        .link(symtab_idx as Elf32Word);
+        let mut sct = Section32::new(
+            ".symtab_shndx".to_string(),
+            hdr,
+            section::Contents32::SymTabShNdx(Vec::new()),
+        );
+        sct.header.sh_entsize = 4;
+        self.sections.push(sct);
+        self.update_header_numbers(self.shstrndx());
+        self.rebuild_shstrtab();
+        self.sections.len() - 1
+    }
+
+    /// .shstrtabを各セクション名から作り直し，sh_nameを設定する
+    fn rebuild_shstrtab(&mut self) {
+        let shstrndx = self.shstrndx();
+        // e_shstrndxが壊れていても，文字列テーブル以外を上書きしない
+        if shstrndx == 0
+            || self
+                .sections
+                .get(shstrndx)
+                .is_none_or(|sct| sct.header.get_type() != section::Type::StrTab)
+        {
+            return;
+        }
+        // シンボル名と共用されている文字列テーブルは作り直さずに拡張する
+        let shared = self.sections.iter().any(|sct| {
+            sct.header.sh_link as usize == shstrndx
+                && matches!(
+                    sct.contents,
+                    section::Contents32::Symbols(_) | section::Contents32::Dynamics(_)
+                )
+        });
+        if shared {
+            self.extend_shared_shstrtab(shstrndx);
+            return;
+        }
+
+        let mut names = Vec::new();
+        let mut name_indices: HashMap<String, u32> = HashMap::new();
+        let mut name_idx = 1;
+        for sct in self.sections.iter() {
+            if sct.name.is_empty() || name_indices.contains_key(&sct.name) {
+                continue;
+            }
+            name_indices.insert(sct.name.clone(), name_idx);
+            name_idx += sct.name.len() as u32 + 1;
+            names.push(sct.name.clone());
+        }
+
+        for sct in self.sections.iter_mut() {
+            sct.header.sh_name = name_indices.get(&sct.name).copied().unwrap_or(0);
+        }
+        self.sections[shstrndx].contents = section::Contents32::new_string_table(names);
+    }
+
+    /// シンボル名の位置が変わらないように既存の文字列は残し，
+    /// 見つからないセクション名だけを末尾に追加する
+    fn extend_shared_shstrtab(&mut self, shstrndx: usize) {
+        let mut strs = match &self.sections[shstrndx].contents {
+            section::Contents32::StrTab(strs) => strs.clone(),
+            _ => return,
+        };
+        for sct in self.sections.iter_mut().skip(1) {
+            if sct.name.is_empty()
+                || section::string_at(&strs, sct.header.sh_name as usize) == Some(sct.name.as_str())
+            {
+                continue;
+            }
+            let name_idx = match strs.iter().find(|s| s.v == sct.name) {
+                Some(s) => s.idx,
+                None => {
+                    let idx = strs.last().map_or(1, |s| s.idx + s.v.len() + 1);
+                    strs.push(section::StrTabEntry {
+                        v: sct.name.clone(),
+                        idx,
+                    });
+                    idx
+                }
+            };
+            sct.header.sh_name = name_idx as u32;
+        }
+        self.sections[shstrndx].contents = section::Contents32::StrTab(strs);
+    }
+
+    /// sh_nameとsh_sizeの設定
+    /// ファイルオフセットはfinalize()で計算する
+    fn fill_elf_info(&mut self, new_sct: &mut Section32) {
         new_sct.header.sh_size = new_sct.contents.size() as u32;
+
+        let shstrndx = self.shstrndx();
+        match self.sections.get_mut(shstrndx) {
+            Some(shstrtab)
+                if shstrndx != 0 && shstrtab.header.get_type() == section::Type::StrTab =>
+            {
+                let shstrtab_len = shstrtab.contents.size();
+                // 既存の.shstrtabの末尾に名前を追加する
+                if let section::Contents32::StrTab(ref mut tab) = shstrtab.contents {
+                    new_sct.header.sh_name = shstrtab_len as u32;
+                    tab.push(section::StrTabEntry {
+                        v: new_sct.name.clone(),
+                        idx: shstrtab_len,
+                    });
+                }
+                shstrtab.header.sh_size = shstrtab.contents.size() as u32;
+            }
+            _ => {
+                // .shstrtabは後から追加されるので，
+                // 名前はセクションの順に並んでいるものとする
+                if let Some(prev_sct) = self.sections.last() {
+                    // <prev_section_name> の後に0x00が入るので，+1
+                    new_sct.header.sh_name =
+                        prev_sct.header.sh_name + prev_sct.name.len() as u32 + 1;
+                }
+            }
+        }
     }
 }
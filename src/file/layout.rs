@@ -0,0 +1,236 @@
+//! Class-independent file layout used by `finalize()`.
+
+use crate::{
+    section,
+    segment::{self, SectionExtent, SegmentExtent},
+};
+
+/// file offsets computed by `layout()`
+pub(crate) struct Layout {
+    pub section_offsets: Vec<u64>,
+    pub segment_offsets: Vec<u64>,
+    /// the end of the last section data
+    pub end: u64,
+}
+
+/// round `v` up to a multiple of `align`
+pub(crate) fn align_up(v: u64, align: u64) -> u64 {
+    if align <= 1 {
+        v
+    } else {
+        v.div_ceil(align) * align
+    }
+}
+
+/// pad `file_binary` with zero up to `offset`.
+/// the writers use this for `e_phoff`, `sh_offset` and `e_shoff`,
+/// so file positions never come from `sh_addr`.
+pub(crate) fn pad_to(file_binary: &mut Vec<u8>, offset: u64) {
+    if (file_binary.len() as u64) < offset {
+        file_binary.resize(offset as usize, 0x00);
+    }
+}
+
+/// the smallest offset not less than `min` which is congruent to `vaddr` modulo `align`
+fn congruent_offset(min: u64, vaddr: u64, align: u64) -> u64 {
+    if align <= 1 {
+        return min;
+    }
+    let rem = vaddr % align;
+    let base = min - min % align + rem;
+    if base < min {
+        base + align
+    } else {
+        base
+    }
+}
+
+fn load_contains(seg: &SegmentExtent, addr: u64, size: u64) -> bool {
+    seg.ty == segment::Type::Load
+        && seg.memsz > 0
+        && addr >= seg.vaddr
+        && addr - seg.vaddr < seg.memsz
+        && (addr - seg.vaddr).saturating_add(size) <= seg.memsz
+}
+
+/// lay out sections after the headers(`headers_end`).
+/// `SHF_ALLOC` sections in a `PT_LOAD` are placed first in the order of the segments,
+/// keeping the distance from the start of the segment, so `p_offset ≡ p_vaddr (mod p_align)` holds.
+/// the other sections follow them in the order of the section table.
+/// section 0 and `SHT_NOBITS` sections don't occupy file bytes.
+pub(crate) fn layout(
+    headers_end: u64,
+    phoff: u64,
+    sections: &[SectionExtent],
+    segments: &[SegmentExtent],
+) -> Layout {
+    let mut pos = headers_end;
+    let mut section_offsets = vec![0; sections.len()];
+    let mut load_offsets: Vec<Option<u64>> = vec![None; segments.len()];
+
+    // 各セクションを含む最初のPT_LOAD
+    let load_of: Vec<Option<usize>> = sections
+        .iter()
+        .enumerate()
+        .map(|(sct_idx, sct)| {
+            if sct_idx == 0 || !sct.alloc {
+                return None;
+            }
+            let size = if sct.ty == section::Type::NoBits {
+                0
+            } else {
+                sct.size
+            };
+            segments
+                .iter()
+                .position(|seg| load_contains(seg, sct.addr, size))
+        })
+        .collect();
+
+    // PT_LOADに含まれるセクションを先に配置する
+    // 非ALLOCセクションが間に挟まっていても，セグメントのファイルイメージは崩れない
+    let mut load_indices: Vec<usize> = (0..segments.len())
+        .filter(|seg_idx| load_of.contains(&Some(*seg_idx)))
+        .collect();
+    load_indices.sort_by_key(|seg_idx| segments[*seg_idx].vaddr);
+    for load_idx in load_indices {
+        let seg = &segments[load_idx];
+        let members: Vec<usize> = (0..sections.len())
+            .filter(|sct_idx| load_of[*sct_idx] == Some(load_idx))
+            .collect();
+        let first_distance = members
+            .iter()
+            .map(|sct_idx| sections[*sct_idx].addr - seg.vaddr)
+            .min()
+            .unwrap_or(0);
+        let seg_offset = congruent_offset(pos.saturating_sub(first_distance), seg.vaddr, seg.align);
+        load_offsets[load_idx] = Some(seg_offset);
+
+        for sct_idx in members {
+            let sct = &sections[sct_idx];
+            let offset = seg_offset + (sct.addr - seg.vaddr);
+            section_offsets[sct_idx] = offset;
+            if sct.ty != section::Type::NoBits {
+                pos = pos.max(offset + sct.size);
+            }
+        }
+    }
+
+    for (sct_idx, sct) in sections.iter().enumerate().skip(1) {
+        if load_of[sct_idx].is_some() {
+            continue;
+        }
+        let offset = align_up(pos, sct.align);
+        section_offsets[sct_idx] = offset;
+        if sct.ty != section::Type::NoBits {
+            pos = offset + sct.size;
+        }
+    }
+
+    let mut segment_offsets = Vec::with_capacity(segments.len());
+    for (seg_idx, seg) in segments.iter().enumerate() {
+        let offset = match seg.ty {
+            segment::Type::Phdr => phoff,
+            segment::Type::Load => match load_offsets[seg_idx] {
+                Some(offset) => offset,
+                // セクションを含まないPT_LOADはヘッダを含むものとみなす
+                None if seg.offset == 0 => congruent_offset(0, seg.vaddr, seg.align),
+                None => congruent_offset(pos, seg.vaddr, seg.align),
+            },
+            _ => {
+                // PT_INTERP/PT_DYNAMIC等は含まれるPT_LOADから計算する
+                let containing = segments.iter().enumerate().find(|(idx, load)| {
+                    load_offsets[*idx].is_some() && load_contains(load, seg.vaddr, seg.memsz)
+                });
+                match containing {
+                    Some((load_idx, load)) => {
+                        load_offsets[load_idx].unwrap() + (seg.vaddr - load.vaddr)
+                    }
+                    None => sections
+                        .iter()
+                        .zip(section_offsets.iter())
+                        .skip(1)
+                        .find(|(sct, _)| sct.alloc && seg.memsz > 0 && sct.addr == seg.vaddr)
+                        .map_or(seg.offset, |(_, offset)| *offset),
+                }
+            }
+        };
+        segment_offsets.push(offset);
+    }
+
+    Layout {
+        section_offsets,
+        segment_offsets,
+        end: pos,
+    }
+}
+
+#[cfg(test)]
+mod layout_tests {
+    use super::*;
+
+    fn sct(ty: section::Type, alloc: bool, addr: u64, size: u64, align: u64) -> SectionExtent {
+        SectionExtent {
+            ty,
+            alloc,
+            tls: false,
+            addr,
+            offset: 0,
+            size,
+            align,
+        }
+    }
+
+    fn load(offset: u64, vaddr: u64, memsz: u64) -> SegmentExtent {
+        SegmentExtent {
+            ty: segment::Type::Load,
+            offset,
+            vaddr,
+            filesz: memsz,
+            memsz,
+            align: 0x1000,
+        }
+    }
+
+    #[test]
+    fn congruent_offset_test() {
+        assert_eq!(0x234, congruent_offset(0x200, 0x401234, 0x1000));
+        assert_eq!(0x2010, congruent_offset(0x1100, 0x403010, 0x1000));
+        assert_eq!(0x78, congruent_offset(0x78, 0x400000, 1));
+        assert_eq!(0x80, align_up(0x78, 0x10));
+    }
+
+    #[test]
+    fn layout_test() {
+        let sections = vec![
+            sct(section::Type::Null, false, 0, 0, 0),
+            sct(section::Type::ProgBits, true, 0x401000, 0x10, 16),
+            sct(section::Type::ProgBits, true, 0x402000, 0x8, 8),
+            sct(section::Type::NoBits, true, 0x402008, 0x100, 8),
+            sct(section::Type::StrTab, false, 0, 0x11, 1),
+        ];
+        let segments = vec![load(0, 0x400000, 0x1010), load(0x1000, 0x402000, 0x108)];
+        let l = layout(0xb0, 0x40, &sections, &segments);
+
+        assert_eq!(vec![0, 0x1000, 0x2000, 0x2008, 0x2008], l.section_offsets);
+        assert_eq!(vec![0, 0x2000], l.segment_offsets);
+        assert_eq!(0x2019, l.end);
+    }
+
+    #[test]
+    fn layout_interleaved_test() {
+        // .text, .comment(非ALLOC), .text2 の順に並んでいても.text2はPT_LOAD内に置かれる
+        let sections = vec![
+            sct(section::Type::Null, false, 0, 0, 0),
+            sct(section::Type::ProgBits, true, 0x400060, 0x30, 16),
+            sct(section::Type::ProgBits, false, 0, 0x200, 1),
+            sct(section::Type::ProgBits, true, 0x400090, 0x10, 16),
+        ];
+        let segments = vec![load(0, 0x400000, 0xa0)];
+        let l = layout(0x60, 0x40, &sections, &segments);
+
+        assert_eq!(vec![0, 0x60, 0xa0, 0x90], l.section_offsets);
+        assert_eq!(vec![0], l.segment_offsets);
+        assert_eq!(0x2a0, l.end);
+    }
+}
@@ -18,3 +18,6 @@ pub use elf_type::*;
 pub use machine::*;
 pub use osabi::*;
 pub use version::*;
+
+/// Size of `e_ident`
+pub const EI_NIDENT: usize = 16;
@@ -22,8 +22,8 @@ impl Ehdr {
     /// プログラムヘッダテーブルが存在するかチェック
     pub fn pht_exists(&self) -> bool {
         match self {
-            Ehdr::Ehdr64(ehdr) => ehdr.e_phnum != 0,
-            Ehdr::Ehdr32(ehdr) => ehdr.e_phnum != 0,
+            Ehdr::Ehdr64(ehdr) => ehdr.pht_exists(),
+            Ehdr::Ehdr32(ehdr) => ehdr.pht_exists(),
         }
     }
 
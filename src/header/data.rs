@@ -1,3 +1,7 @@
+use bincode::Options;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Data {
     // invalid data encoding
     None,
@@ -22,6 +26,35 @@ impl Data {
             Self::Any(c) => *c,
         }
     }
+
+    /// Whether this encoding stores multi-byte values in big-endian order.
+    /// anything but `MSB2` is treated as little-endian.
+    pub fn is_big_endian(&self) -> bool {
+        matches!(self, Self::MSB2)
+    }
+
+    /// encode a structure with this data encoding
+    pub(crate) fn serialize<T: Serialize>(&self, v: &T) -> Vec<u8> {
+        // 固定長エンコーディングにしないと，ELFの構造体レイアウトと一致しない
+        let opts = bincode::DefaultOptions::new().with_fixint_encoding();
+        if self.is_big_endian() {
+            opts.with_big_endian().serialize(v).unwrap()
+        } else {
+            opts.with_little_endian().serialize(v).unwrap()
+        }
+    }
+
+    /// decode a structure from the head of `buf` with this data encoding
+    pub(crate) fn deserialize<'a, T: Deserialize<'a>>(&self, buf: &'a [u8]) -> bincode::Result<T> {
+        let opts = bincode::DefaultOptions::new()
+            .with_fixint_encoding()
+            .allow_trailing_bytes();
+        if self.is_big_endian() {
+            opts.with_big_endian().deserialize(buf)
+        } else {
+            opts.with_little_endian().deserialize(buf)
+        }
+    }
 }
 
 impl From<u8> for Data {
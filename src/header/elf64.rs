@@ -92,9 +92,20 @@ impl Ehdr64 {
         self.e_machine = e_machine.to_bytes();
     }
 
+    /// whether the program header table exists.
+    /// `e_phnum` is `PN_XNUM` if the real number is in `sh_info` of section 0.
+    pub fn pht_exists(&self) -> bool {
+        self.e_phnum != 0
+    }
+
     /// Create Vec<u8> from this.
     pub fn to_le_bytes(&self) -> Vec<u8> {
-        bincode::serialize(self).unwrap()
+        self.to_bytes(header::Data::LSB2)
+    }
+
+    /// Create Vec<u8> from this with the given data encoding.
+    pub fn to_bytes(&self, data: header::Data) -> Vec<u8> {
+        data.serialize(self)
     }
 
     pub fn deserialize(buf: &[u8], start: usize) -> Result<Self, Box<dyn std::error::Error>> {
@@ -179,13 +179,21 @@ pub enum Machine {
     SNP1K,
     // STMicroelectronics ST200 microcontroller
     ST200,
+    // ARM AARCH64
+    AArch64,
+    // RISC-V
+    RiscV,
     Any(Elf64Half),
 }
 
 impl Machine {
     pub fn to_bytes(&self) -> Elf64Half {
         match self {
+            Self::Intel386 => 3,
+            Self::Arm => 40,
             Self::X8664 => 62,
+            Self::AArch64 => 183,
+            Self::RiscV => 243,
             Self::Any(c) => *c,
             _ => panic!("not implemented -> {:?}", self),
         }
@@ -195,7 +203,11 @@ impl Machine {
 impl From<Elf64Half> for Machine {
     fn from(bytes: Elf64Half) -> Self {
         match bytes {
+            3 => Self::Intel386,
+            40 => Self::Arm,
             62 => Self::X8664,
+            183 => Self::AArch64,
+            243 => Self::RiscV,
             _ => Self::Any(bytes),
         }
     }
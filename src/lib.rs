@@ -1,11 +1,19 @@
+pub mod archive;
+pub mod coredump;
 pub mod dynamic;
 pub mod file;
 pub mod header;
+pub mod link;
+pub mod note;
+pub mod objcopy;
 pub mod parser;
 pub mod relocation;
 pub mod section;
 pub mod segment;
+pub mod strip;
 pub mod symbol;
+pub mod version;
+pub mod view;
 
 #[allow(unused)]
 /* Type for a 16-bit quantity.  */
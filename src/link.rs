@@ -0,0 +1,32 @@
+//! Static linking utilities.
+
+mod linker;
+
+pub use linker::*;
+
+/// the default entry point symbol
+pub const DEFAULT_ENTRY: &str = "_start";
+/// the default address of the first `PT_LOAD`(same as GNU ld on x86_64)
+pub const DEFAULT_BASE_ADDRESS: u64 = 0x400000;
+/// the default page size
+pub const DEFAULT_PAGE_SIZE: u64 = 0x1000;
+
+/// the output section name for an input section like GNU ld's default script.
+///
+/// # Examples
+///
+/// ```
+/// use elf_utilities::link;
+///
+/// assert_eq!(".text", link::output_section_name(".text.main"));
+/// assert_eq!(".rodata", link::output_section_name(".rodata.str1.1"));
+/// assert_eq!(".init_array", link::output_section_name(".init_array"));
+/// ```
+pub fn output_section_name(name: &str) -> &str {
+    for prefix in [".text", ".rodata", ".data", ".bss"].iter() {
+        if name == *prefix || name.starts_with(&format!("{}.", prefix)) {
+            return prefix;
+        }
+    }
+    name
+}
@@ -0,0 +1,939 @@
+use std::collections::{HashMap, HashSet};
+
+use thiserror::Error as TError;
+
+use crate::section::{Contents64, Section64};
+use crate::*;
+
+#[derive(TError, Debug, PartialEq, Eq)]
+pub enum LinkError {
+    #[error("no input objects")]
+    NoInput,
+    #[error("object {obj_idx} is not a relocatable object")]
+    NotRelocatable { obj_idx: usize },
+    #[error("object {obj_idx} is for a different machine")]
+    MachineMismatch { obj_idx: usize },
+    #[error("duplicate symbol `{name}` in object {obj_idx}")]
+    DuplicateSymbol { name: String, obj_idx: usize },
+    #[error("undefined symbol `{name}` referenced in object {obj_idx}")]
+    UndefinedSymbol { name: String, obj_idx: usize },
+    #[error("entry symbol `{name}` is not defined")]
+    EntryNotFound { name: String },
+    #[error("symbol {sym_idx} in object {obj_idx} is defined in an unknown section")]
+    UnknownSymbolSection { obj_idx: usize, sym_idx: usize },
+    #[error("can't relocate object {obj_idx} => `{k}`")]
+    Relocation {
+        obj_idx: usize,
+        k: relocation::RelocationError,
+    },
+}
+
+/// A minimal static linker which creates an `ET_EXEC` from relocatable objects.
+///
+/// like-named sections(e.g. `.text.*`) are merged,
+/// and the output has a read-only/executable `PT_LOAD` with the headers and
+/// a writable `PT_LOAD` for data.
+///
+/// # Examples
+///
+/// ```
+/// use elf_utilities::{header, link, parser};
+///
+/// let start = parser::parse_elf64("src/parser/testdata/link_start.o").unwrap();
+/// let add = parser::parse_elf64("src/parser/testdata/link_add.o").unwrap();
+///
+/// let exe = link::Linker::new().object(start).object(add).link().unwrap();
+///
+/// assert_eq!(header::Type::Exec, exe.ehdr.get_type());
+/// assert_eq!(2, exe.segments.len());
+/// ```
+#[derive(Clone)]
+pub struct Linker {
+    objects: Vec<file::ELF64>,
+    entry: String,
+    base_address: Elf64Addr,
+    page_size: Elf64Xword,
+}
+
+/// where a global symbol is defined
+#[derive(Debug, Clone, Copy)]
+enum Definition {
+    Section {
+        obj_idx: usize,
+        sct_idx: usize,
+        value: Elf64Addr,
+    },
+    Absolute(Elf64Addr),
+    /// `SHN_COMMON`, `st_value` holds the alignment
+    Common {
+        size: Elf64Xword,
+        align: Elf64Xword,
+    },
+    /// allocated in an output section
+    Output {
+        out_idx: usize,
+        offset: Elf64Addr,
+    },
+}
+
+#[derive(Debug, Clone)]
+struct GlobalSymbol {
+    def: Definition,
+    weak: bool,
+    st_info: u8,
+    st_other: u8,
+    st_size: Elf64Xword,
+}
+
+#[derive(Debug, Clone)]
+struct OutputSection {
+    name: String,
+    sh_type: section::Type,
+    sh_flags: Elf64Xword,
+    sh_addralign: Elf64Xword,
+    sh_addr: Elf64Addr,
+    sh_offset: Elf64Off,
+    size: Elf64Xword,
+    data: Vec<u8>,
+}
+
+impl OutputSection {
+    /// 0: text, 1: read-only data, 2: writable data, 3: bss
+    fn rank(&self) -> usize {
+        let has = |flag: section::Flag| self.sh_flags & Elf64Xword::from(flag) != 0;
+        if has(section::Flag::ExecInstr) {
+            0
+        } else if !has(section::Flag::Write) {
+            1
+        } else if self.sh_type != section::Type::NoBits {
+            2
+        } else {
+            3
+        }
+    }
+
+    fn is_writable(&self) -> bool {
+        self.sh_flags & Elf64Xword::from(section::Flag::Write) != 0
+    }
+}
+
+impl Default for Linker {
+    fn default() -> Self {
+        Self {
+            objects: Vec::new(),
+            entry: link::DEFAULT_ENTRY.to_string(),
+            base_address: link::DEFAULT_BASE_ADDRESS,
+            page_size: link::DEFAULT_PAGE_SIZE,
+        }
+    }
+}
+
+impl Linker {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// add an input object
+    pub fn object(mut self, obj: file::ELF64) -> Self {
+        self.objects.push(obj);
+        self
+    }
+
+    /// set the entry point symbol(`_start` by default)
+    pub fn entry(mut self, name: &str) -> Self {
+        self.entry = name.to_string();
+        self
+    }
+
+    /// set the address of the first `PT_LOAD`
+    pub fn base_address(mut self, addr: Elf64Addr) -> Self {
+        self.base_address = addr;
+        self
+    }
+
+    /// set the page size used as `p_align`
+    pub fn page_size(mut self, size: Elf64Xword) -> Self {
+        self.page_size = size;
+        self
+    }
+
+    pub fn add_object(&mut self, obj: file::ELF64) {
+        self.objects.push(obj);
+    }
+
+    /// link all objects into an executable
+    pub fn link(&self) -> Result<file::ELF64, LinkError> {
+        self.check_objects()?;
+        let discarded = self.discarded_sections();
+
+        // 入力セクションを出力セクションにまとめる
+        let mut outputs = self.output_sections(&discarded);
+        let mut placements = HashMap::new();
+        for (obj_idx, obj) in self.objects.iter().enumerate() {
+            for (sct_idx, sct) in obj.sections.iter().enumerate() {
+                if !is_input_section(sct) || discarded.contains(&(obj_idx, sct_idx)) {
+                    continue;
+                }
+                let out_idx = outputs
+                    .iter()
+                    .position(|out| out.name == link::output_section_name(&sct.name))
+                    .unwrap();
+                let out = &mut outputs[out_idx];
+                let offset = align_up(out.size, sct.header.sh_addralign);
+                out.size = offset + sct.header.sh_size;
+                if out.sh_type != section::Type::NoBits {
+                    out.data.resize(offset as usize, 0x00);
+                    if sct.header.get_type() == section::Type::NoBits {
+                        out.data.resize(out.size as usize, 0x00);
+                    } else {
+                        out.data.append(&mut sct.to_bytes(obj.ehdr.get_data()));
+                    }
+                }
+                placements.insert((obj_idx, sct_idx), (out_idx, offset));
+            }
+        }
+
+        let mut globals = self.resolve_symbols(&discarded)?;
+        allocate_commons(&mut globals, &mut outputs);
+
+        let segments = self.layout(&mut outputs);
+
+        let section_address = |obj_idx: usize, sct_idx: usize| {
+            placements
+                .get(&(obj_idx, sct_idx))
+                .map(|(out_idx, offset)| outputs[*out_idx].sh_addr + offset)
+        };
+        let global_address = |sym: &GlobalSymbol| match sym.def {
+            Definition::Section {
+                obj_idx,
+                sct_idx,
+                value,
+            } => section_address(obj_idx, sct_idx).map(|addr| addr + value),
+            Definition::Absolute(value) => Some(value),
+            Definition::Output { out_idx, offset } => Some(outputs[out_idx].sh_addr + offset),
+            Definition::Common { .. } => None,
+        };
+        let addresses: HashMap<&String, Elf64Addr> = globals
+            .iter()
+            .filter_map(|(name, sym)| global_address(sym).map(|addr| (name, addr)))
+            .collect();
+
+        // 再配置の計算に必要な値を集めてから，出力セクションに書き込む
+        let mut fixups = Vec::new();
+        for (obj_idx, obj) in self.objects.iter().enumerate() {
+            for rela_sct in obj.sections.iter() {
+                let relas = match &rela_sct.contents {
+                    Contents64::RelaSymbols(relas) => relas,
+                    _ => continue,
+                };
+                let target_idx = rela_sct.header.sh_info as usize;
+                let (out_idx, base_offset) = match placements.get(&(obj_idx, target_idx)) {
+                    Some(placement) => *placement,
+                    // デバッグ情報等，出力しないセクションへの再配置
+                    None => continue,
+                };
+                let target_addr = outputs[out_idx].sh_addr + base_offset;
+                let syms = match obj
+                    .sections
+                    .get(rela_sct.header.sh_link as usize)
+                    .map(|sct| &sct.contents)
+                {
+                    Some(Contents64::Symbols(syms)) => syms,
+                    _ => {
+                        return Err(LinkError::Relocation {
+                            obj_idx,
+                            k: relocation::RelocationError::InvalidSymbol {
+                                symtab_idx: rela_sct.header.sh_link as usize,
+                                sym_idx: 0,
+                            },
+                        })
+                    }
+                };
+
+                for rela in relas.iter() {
+                    let sym_idx = rela.get_sym() as usize;
+                    let sym = syms.get(sym_idx).ok_or(LinkError::Relocation {
+                        obj_idx,
+                        k: relocation::RelocationError::InvalidSymbol {
+                            symtab_idx: rela_sct.header.sh_link as usize,
+                            sym_idx,
+                        },
+                    })?;
+
+                    let s = if sym_idx == 0 {
+                        0
+                    } else if sym.get_bind() == symbol::Bind::Local {
+                        let unknown_section =
+                            || LinkError::UnknownSymbolSection { obj_idx, sym_idx };
+                        match sym.st_shndx {
+                            section::SHN_ABS => sym.st_value,
+                            _ => {
+                                // SHN_XINDEXはSHT_SYMTAB_SHNDXから解決する
+                                let sct_idx = obj
+                                    .symbol_section_index(rela_sct.header.sh_link as usize, sym_idx)
+                                    .ok_or_else(unknown_section)?;
+                                match section_address(obj_idx, sct_idx) {
+                                    Some(addr) => addr + sym.st_value,
+                                    // 破棄されたCOMDATグループへの参照
+                                    None if discarded.contains(&(obj_idx, sct_idx)) => 0,
+                                    None => return Err(unknown_section()),
+                                }
+                            }
+                        }
+                    } else {
+                        match addresses.get(&sym.symbol_name) {
+                            Some(addr) => *addr,
+                            None if sym.get_bind() == symbol::Bind::Weak => 0,
+                            None => {
+                                return Err(LinkError::UndefinedSymbol {
+                                    name: sym.symbol_name.clone(),
+                                    obj_idx,
+                                })
+                            }
+                        }
+                    };
+
+                    fixups.push((
+                        obj_idx,
+                        out_idx,
+                        rela.kind(&obj.ehdr.get_machine()),
+                        s,
+                        rela.get_addend(),
+                        target_addr + rela.get_offset(),
+                        (base_offset + rela.get_offset()) as usize,
+                    ));
+                }
+            }
+        }
+        let data = self.objects[0].ehdr.get_data();
+        for (obj_idx, out_idx, kind, s, a, p, offset) in fixups {
+            relocation::relocate(kind, data, s, a, p, &mut outputs[out_idx].data, offset)
+                .map_err(|k| LinkError::Relocation { obj_idx, k })?;
+        }
+
+        let entry = *addresses.get(&self.entry).ok_or(LinkError::EntryNotFound {
+            name: self.entry.clone(),
+        })?;
+
+        Ok(self.build_executable(outputs, segments, &globals, &addresses, entry))
+    }
+
+    fn check_objects(&self) -> Result<(), LinkError> {
+        let machine = match self.objects.first() {
+            Some(obj) => obj.ehdr.get_machine(),
+            None => return Err(LinkError::NoInput),
+        };
+        for (obj_idx, obj) in self.objects.iter().enumerate() {
+            if obj.ehdr.get_type() != header::Type::Rel {
+                return Err(LinkError::NotRelocatable { obj_idx });
+            }
+            if obj.ehdr.get_machine() != machine {
+                return Err(LinkError::MachineMismatch { obj_idx });
+            }
+        }
+        Ok(())
+    }
+
+    /// members of COMDAT groups whose signature already appeared
+    fn discarded_sections(&self) -> HashSet<(usize, usize)> {
+        let mut signatures = HashSet::new();
+        let mut discarded = HashSet::new();
+        for (obj_idx, obj) in self.objects.iter().enumerate() {
+            for (group_idx, sct) in obj.sections.iter().enumerate() {
+                let group = match &sct.contents {
+                    Contents64::Group(group) => group,
+                    _ => continue,
+                };
+                let signature = obj.group_signature(group_idx).unwrap_or_default();
+                if signatures.insert(signature.to_string()) {
+                    continue;
+                }
+                for member in group.sections.iter() {
+                    discarded.insert((obj_idx, *member as usize));
+                }
+            }
+        }
+        discarded
+    }
+
+    /// create empty output sections sorted by their segments
+    fn output_sections(&self, discarded: &HashSet<(usize, usize)>) -> Vec<OutputSection> {
+        let mut outputs: Vec<OutputSection> = Vec::new();
+        for (obj_idx, obj) in self.objects.iter().enumerate() {
+            for (sct_idx, sct) in obj.sections.iter().enumerate() {
+                if !is_input_section(sct) || discarded.contains(&(obj_idx, sct_idx)) {
+                    continue;
+                }
+                let name = link::output_section_name(&sct.name);
+                let flags = sct.header.sh_flags & !Elf64Xword::from(section::Flag::Group);
+                match outputs.iter_mut().find(|out| out.name == name) {
+                    Some(out) => {
+                        out.sh_flags |= flags;
+                        out.sh_addralign = out.sh_addralign.max(sct.header.sh_addralign);
+                        // 一つでもファイル上の内容を持つなら，出力もPROGBITSとする
+                        if out.sh_type == section::Type::NoBits {
+                            out.sh_type = sct.header.get_type();
+                        }
+                    }
+                    None => outputs.push(OutputSection {
+                        name: name.to_string(),
+                        sh_type: sct.header.get_type(),
+                        sh_flags: flags,
+                        sh_addralign: sct.header.sh_addralign.max(1),
+                        sh_addr: 0,
+                        sh_offset: 0,
+                        size: 0,
+                        data: Vec::new(),
+                    }),
+                }
+            }
+        }
+
+        outputs.sort_by_key(|out| out.rank());
+        outputs
+    }
+
+    fn resolve_symbols(
+        &self,
+        discarded: &HashSet<(usize, usize)>,
+    ) -> Result<HashMap<String, GlobalSymbol>, LinkError> {
+        let mut globals: HashMap<String, GlobalSymbol> = HashMap::new();
+        for (obj_idx, obj) in self.objects.iter().enumerate() {
+            for (symtab_idx, symtab) in obj.sections.iter().enumerate() {
+                if symtab.header.get_type() != section::Type::SymTab {
+                    continue;
+                }
+                let syms = match &symtab.contents {
+                    Contents64::Symbols(syms) => syms,
+                    _ => continue,
+                };
+
+                for (sym_idx, sym) in syms.iter().enumerate() {
+                    let weak = match sym.get_bind() {
+                        symbol::Bind::Global => false,
+                        symbol::Bind::Weak => true,
+                        _ => continue,
+                    };
+                    let def = match sym.st_shndx {
+                        section::SHN_UNDEF => continue,
+                        section::SHN_ABS => Definition::Absolute(sym.st_value),
+                        section::SHN_COMMON => Definition::Common {
+                            size: sym.st_size,
+                            align: sym.st_value.max(1),
+                        },
+                        _ => match obj.symbol_section_index(symtab_idx, sym_idx) {
+                            // 破棄されたセクションの定義は，残った方の定義で解決される
+                            Some(sct_idx) if discarded.contains(&(obj_idx, sct_idx)) => continue,
+                            Some(sct_idx) => Definition::Section {
+                                obj_idx,
+                                sct_idx,
+                                value: sym.st_value,
+                            },
+                            None => continue,
+                        },
+                    };
+                    let new_sym = GlobalSymbol {
+                        def,
+                        weak,
+                        st_info: sym.st_info,
+                        st_other: sym.st_other,
+                        st_size: sym.st_size,
+                    };
+
+                    let existing = match globals.get_mut(&sym.symbol_name) {
+                        Some(existing) => existing,
+                        None => {
+                            globals.insert(sym.symbol_name.clone(), new_sym);
+                            continue;
+                        }
+                    };
+                    match (&mut existing.def, new_sym.def) {
+                        // COMMON同士は大きい方に合わせる
+                        (
+                            Definition::Common { size, align },
+                            Definition::Common {
+                                size: new_size,
+                                align: new_align,
+                            },
+                        ) => {
+                            *size = (*size).max(new_size);
+                            *align = (*align).max(new_align);
+                            existing.st_size = *size;
+                        }
+                        (_, Definition::Common { .. }) => {}
+                        (Definition::Common { .. }, _) if !weak => *existing = new_sym,
+                        (Definition::Common { .. }, _) => {}
+                        _ if existing.weak && !weak => *existing = new_sym,
+                        _ if existing.weak || weak => {}
+                        _ => {
+                            return Err(LinkError::DuplicateSymbol {
+                                name: sym.symbol_name.clone(),
+                                obj_idx,
+                            })
+                        }
+                    }
+                }
+            }
+        }
+        Ok(globals)
+    }
+
+    /// assign addresses and file offsets to output sections, and create `PT_LOAD`s
+    fn layout(&self, outputs: &mut [OutputSection]) -> Vec<segment::Segment64> {
+        let has_data = outputs.iter().any(|out| out.is_writable());
+        let phnum = if has_data { 2 } else { 1 };
+        let mut offset =
+            header::Ehdr64::SIZE as Elf64Off + segment::Phdr64::SIZE as Elf64Off * phnum;
+
+        // テキストセグメントはELFヘッダとPHTも含む
+        let mut segments = Vec::new();
+        for out in outputs.iter_mut().filter(|out| !out.is_writable()) {
+            offset = align_up(offset, out.sh_addralign);
+            out.sh_offset = offset;
+            out.sh_addr = self.base_address + offset;
+            offset += out.size;
+        }
+        segments.push(load_segment(
+            &[segment::Flag::R, segment::Flag::X],
+            0,
+            self.base_address,
+            offset,
+            offset,
+            self.page_size,
+        ));
+        if !has_data {
+            return segments;
+        }
+
+        // 直前のセグメントと同じページに載らないよう，1ページずらす
+        let delta = self.base_address + self.page_size;
+        let mut segment_start = None;
+        let mut file_end = offset;
+        let mut memory_end = 0;
+        for out in outputs.iter_mut().filter(|out| out.is_writable()) {
+            offset = align_up(offset, out.sh_addralign);
+            out.sh_offset = offset;
+            out.sh_addr = offset + delta;
+            segment_start.get_or_insert(offset);
+            memory_end = out.sh_addr + out.size;
+            if out.sh_type != section::Type::NoBits {
+                offset += out.size;
+                file_end = offset;
+            }
+        }
+        let p_offset = segment_start.unwrap_or(offset);
+        segments.push(load_segment(
+            &[segment::Flag::R, segment::Flag::W],
+            p_offset,
+            p_offset + delta,
+            file_end - p_offset,
+            memory_end - (p_offset + delta),
+            self.page_size,
+        ));
+        segments
+    }
+
+    fn build_executable(
+        &self,
+        outputs: Vec<OutputSection>,
+        segments: Vec<segment::Segment64>,
+        globals: &HashMap<String, GlobalSymbol>,
+        addresses: &HashMap<&String, Elf64Addr>,
+        entry: Elf64Addr,
+    ) -> file::ELF64 {
+        let mut sections = vec![Section64::new_null_section()];
+        let mut file_end = 0;
+        for out in outputs.iter() {
+            let mut sct = Section64::new(
+                out.name.clone(),
+                section::ShdrPreparation64 {
+                    sh_addralign: out.sh_addralign,
+                    ..Default::default()
+                }
+                .ty(out.sh_type),
+                Contents64::Raw(out.data.clone()),
+            );
+            sct.header.sh_flags = out.sh_flags;
+            sct.header.sh_addr = out.sh_addr;
+            sct.header.sh_offset = out.sh_offset;
+            sct.header.sh_size = out.size;
+            if out.sh_type != section::Type::NoBits {
+                file_end = file_end.max(out.sh_offset + out.size);
+            }
+            sections.push(sct);
+        }
+        let shndx_of = |out_idx: usize| (out_idx + 1) as Elf64Section;
+
+        // 定義済みのグローバルシンボルを名前順に並べる
+        let mut names: Vec<&String> = addresses.keys().copied().collect();
+        names.sort();
+        let strtab = Contents64::new_string_table(names.iter().map(|s| s.to_string()).collect());
+        let mut syms = vec![symbol::Symbol64::new_null_symbol()];
+        if let Contents64::StrTab(strs) = &strtab {
+            for (name, ent) in names.iter().zip(strs.iter()) {
+                let global = &globals[*name];
+                let st_shndx = match global.def {
+                    Definition::Section {
+                        obj_idx, sct_idx, ..
+                    } => outputs
+                        .iter()
+                        .position(|out| {
+                            out.name
+                                == link::output_section_name(
+                                    &self.objects[obj_idx].sections[sct_idx].name,
+                                )
+                        })
+                        .map_or(section::SHN_ABS, shndx_of),
+                    Definition::Output { out_idx, .. } => shndx_of(out_idx),
+                    _ => section::SHN_ABS,
+                };
+                syms.push(symbol::Symbol64 {
+                    st_name: ent.idx as Elf64Word,
+                    st_info: global.st_info,
+                    st_other: global.st_other,
+                    st_shndx,
+                    st_value: addresses[*name],
+                    st_size: global.st_size,
+                    symbol_name: name.to_string(),
+                });
+            }
+        }
+
+        let symtab_idx = sections.len();
+        let mut symtab = Section64::new(
+            ".symtab".to_string(),
+            section::ShdrPreparation64 {
+                sh_addralign: 8,
+                ..Default::default()
+            }
+            .ty(section::Type::SymTab)
+            .link(symtab_idx as Elf64Word + 1)
+            .info(1),
+            Contents64::Symbols(syms),
+        );
+        symtab.header.sh_entsize = symbol::Symbol64::SIZE as Elf64Xword;
+        sections.push(symtab);
+        sections.push(Section64::new(
+            ".strtab".to_string(),
+            section::ShdrPreparation64 {
+                sh_addralign: 1,
+                ..Default::default()
+            }
+            .ty(section::Type::StrTab),
+            strtab,
+        ));
+
+        let mut names: Vec<String> = sections.iter().skip(1).map(|s| s.name.clone()).collect();
+        names.push(".shstrtab".to_string());
+        let shstrtab = Contents64::new_string_table(names);
+        sections.push(Section64::new(
+            ".shstrtab".to_string(),
+            section::ShdrPreparation64 {
+                sh_addralign: 1,
+                ..Default::default()
+            }
+            .ty(section::Type::StrTab),
+            shstrtab,
+        ));
+        if let Contents64::StrTab(strs) = &sections.last().unwrap().contents {
+            let strs = strs.clone();
+            for (sct, ent) in sections.iter_mut().skip(1).zip(strs.iter()) {
+                sct.header.sh_name = ent.idx as Elf64Word;
+            }
+        }
+
+        // シンボルテーブル等はセグメントの後ろに配置する
+        let mut offset = file_end;
+        for sct in sections.iter_mut().skip(symtab_idx) {
+            offset = align_up(offset, sct.header.sh_addralign);
+            sct.header.sh_offset = offset;
+            sct.header.sh_size = sct.contents.size() as Elf64Xword;
+            offset += sct.header.sh_size;
+        }
+
+        let mut ehdr = self.objects[0].ehdr;
+        ehdr.set_elf_type(header::Type::Exec);
+        ehdr.e_entry = entry;
+        ehdr.e_phoff = header::Ehdr64::SIZE as Elf64Off;
+        ehdr.e_phentsize = segment::Phdr64::SIZE as Elf64Half;
+        ehdr.e_phnum = segments.len() as Elf64Half;
+        ehdr.e_shoff = align_up(offset, 8);
+        ehdr.e_shentsize = section::Shdr64::SIZE as Elf64Half;
+        ehdr.e_shnum = sections.len() as Elf64Half;
+        ehdr.e_shstrndx = (sections.len() - 1) as Elf64Half;
+
+        file::ELF64 {
+            ehdr,
+            sections,
+            segments,
+        }
+    }
+}
+
+/// sections which are loaded into memory
+fn is_input_section(sct: &Section64) -> bool {
+    sct.header.get_flags().contains(&section::Flag::Alloc)
+        && sct.header.get_type() != section::Type::Group
+}
+
+/// `SHN_COMMON` symbols are allocated at the end of `.bss`
+fn allocate_commons(globals: &mut HashMap<String, GlobalSymbol>, outputs: &mut Vec<OutputSection>) {
+    let mut names: Vec<String> = globals
+        .iter()
+        .filter(|(_, sym)| matches!(sym.def, Definition::Common { .. }))
+        .map(|(name, _)| name.clone())
+        .collect();
+    if names.is_empty() {
+        return;
+    }
+    names.sort();
+
+    let bss_idx = match outputs.iter().position(|out| out.name == ".bss") {
+        Some(idx) => idx,
+        None => {
+            outputs.push(OutputSection {
+                name: ".bss".to_string(),
+                sh_type: section::Type::NoBits,
+                sh_flags: Elf64Xword::from(section::Flag::Alloc)
+                    | Elf64Xword::from(section::Flag::Write),
+                sh_addralign: 1,
+                sh_addr: 0,
+                sh_offset: 0,
+                size: 0,
+                data: Vec::new(),
+            });
+            outputs.len() - 1
+        }
+    };
+
+    let bss = &mut outputs[bss_idx];
+    for name in names {
+        let sym = globals.get_mut(&name).unwrap();
+        if let Definition::Common { size, align } = sym.def {
+            let offset = align_up(bss.size, align);
+            bss.size = offset + size;
+            bss.sh_addralign = bss.sh_addralign.max(align);
+            if bss.sh_type != section::Type::NoBits {
+                bss.data.resize(bss.size as usize, 0x00);
+            }
+            sym.def = Definition::Output {
+                out_idx: bss_idx,
+                offset,
+            };
+        }
+    }
+}
+
+fn load_segment(
+    flags: &[segment::Flag],
+    p_offset: Elf64Off,
+    p_vaddr: Elf64Addr,
+    p_filesz: Elf64Xword,
+    p_memsz: Elf64Xword,
+    p_align: Elf64Xword,
+) -> segment::Segment64 {
+    let mut phdr = segment::Phdr64 {
+        p_offset,
+        p_vaddr,
+        p_paddr: p_vaddr,
+        p_filesz,
+        p_memsz,
+        p_align,
+        ..Default::default()
+    };
+    phdr.set_type(segment::Type::Load);
+    phdr.set_flags(flags.iter());
+    segment::Segment64 { header: phdr }
+}
+
+fn align_up(value: u64, align: u64) -> u64 {
+    let align = align.max(1);
+    value.div_ceil(align) * align
+}
+
+#[cfg(test)]
+mod linker_tests {
+    use super::*;
+
+    fn objects() -> (file::ELF64, file::ELF64) {
+        (
+            parser::parse_elf64("src/parser/testdata/link_start.o").unwrap(),
+            parser::parse_elf64("src/parser/testdata/link_add.o").unwrap(),
+        )
+    }
+
+    fn symbol_value(f: &file::ELF64, name: &str) -> Elf64Addr {
+        match &f
+            .first_section_by(|sct| sct.name == ".symtab")
+            .unwrap()
+            .contents
+        {
+            Contents64::Symbols(syms) => {
+                syms.iter()
+                    .find(|sym| sym.symbol_name == name)
+                    .unwrap()
+                    .st_value
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn link_test() {
+        let (start, add) = objects();
+        let exe = Linker::new().object(start).object(add).link().unwrap();
+
+        // 書き出したものを読み直して検証する
+        let exe = parser::parse_elf64_bytes(&exe.to_le_bytes()).unwrap();
+        assert_eq!(header::Type::Exec, exe.ehdr.get_type());
+        assert_eq!(symbol_value(&exe, "_start"), exe.ehdr.e_entry);
+
+        let names: Vec<&str> = exe.sections.iter().map(|sct| sct.name.as_str()).collect();
+        assert_eq!(
+            vec![
+                "",
+                ".text",
+                ".rodata",
+                ".data",
+                ".bss",
+                ".symtab",
+                ".strtab",
+                ".shstrtab"
+            ],
+            names
+        );
+
+        // .text.scale(強いシンボル)が先に配置される
+        let text = exe.first_section_by(|sct| sct.name == ".text").unwrap();
+        assert_eq!(text.header.sh_addr, symbol_value(&exe, "scale"));
+        assert_eq!(6 + 0x36 + 6 + 0x10, text.header.sh_size);
+
+        // COMMONシンボルは.bssに割り当てられる
+        let bss = exe.first_section_by(|sct| sct.name == ".bss").unwrap();
+        assert_eq!(bss.header.sh_addr, symbol_value(&exe, "counter"));
+        assert_eq!(4, bss.header.sh_size);
+
+        assert_eq!(2, exe.segments.len());
+        let data_sgt = &exe.segments[1].header;
+        assert_eq!(
+            data_sgt.p_offset % data_sgt.p_align,
+            data_sgt.p_vaddr % data_sgt.p_align
+        );
+        assert_eq!(4, data_sgt.p_filesz);
+        assert_eq!(bss.header.sh_addr + 4, data_sgt.p_vaddr + data_sgt.p_memsz);
+
+        // mov $msg,%esi
+        let rodata = exe.first_section_by(|sct| sct.name == ".rodata").unwrap();
+        let text_bytes = match &text.contents {
+            Contents64::Raw(bytes) => bytes.clone(),
+            _ => unreachable!(),
+        };
+        let imm = &text_bytes[6 + 0x1c..6 + 0x20];
+        assert_eq!(
+            rodata.header.sh_addr as u32,
+            u32::from_le_bytes([imm[0], imm[1], imm[2], imm[3]])
+        );
+    }
+
+    #[test]
+    fn link_local_symbol_test() {
+        let (start, add) = objects();
+        let symtab_idx = start.first_shidx_by(|sct| sct.name == ".symtab").unwrap();
+        let set_rodata_shndx = |f: &mut file::ELF64, shndx| {
+            if let Contents64::Symbols(ref mut syms) = f.sections[symtab_idx].contents {
+                syms[3].st_shndx = shndx;
+            }
+        };
+
+        // SHN_XINDEXの.rodataセクションシンボル
+        let mut extended = start.clone();
+        set_rodata_shndx(&mut extended, section::SHN_XINDEX);
+        let mut shndx = Section64::new(
+            ".symtab_shndx".to_string(),
+            section::ShdrPreparation64::default()
+                .ty(section::Type::SymTabShNdx)
+                .link(symtab_idx as Elf64Word),
+            Contents64::SymTabShNdx(vec![0, 0, 0, 7, 0, 0, 0, 0]),
+        );
+        shndx.header.sh_entsize = 4;
+        extended.sections.push(shndx);
+        let exe = Linker::new()
+            .object(extended)
+            .object(add.clone())
+            .link()
+            .unwrap();
+        let text = exe.first_section_by(|sct| sct.name == ".text").unwrap();
+        let rodata = exe.first_section_by(|sct| sct.name == ".rodata").unwrap();
+        if let Contents64::Raw(ref bytes) = text.contents {
+            let imm = &bytes[6 + 0x1c..6 + 0x20];
+            assert_eq!(
+                rodata.header.sh_addr as u32,
+                u32::from_le_bytes([imm[0], imm[1], imm[2], imm[3]])
+            );
+        }
+
+        // 存在しないセクションへの参照は0にせずエラーにする
+        let mut unknown = start;
+        set_rodata_shndx(&mut unknown, 100);
+        assert_eq!(
+            Err(LinkError::UnknownSymbolSection {
+                obj_idx: 0,
+                sym_idx: 3
+            }),
+            Linker::new().object(unknown).object(add).link().map(|_| ())
+        );
+    }
+
+    #[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+    #[test]
+    fn run_linked_executable_test() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let (start, add) = objects();
+        let exe = Linker::new().object(start).object(add).link().unwrap();
+
+        let path = std::env::temp_dir().join(format!("elf-utilities-link-{}", std::process::id()));
+        std::fs::write(&path, exe.to_le_bytes()).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let output = std::process::Command::new(&path).output().unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        // add(20) * scale() => 21 * 2
+        assert_eq!(Some(42), output.status.code());
+        assert_eq!(b"linked\n".to_vec(), output.stdout);
+    }
+
+    #[test]
+    fn link_error_test() {
+        let (start, add) = objects();
+        assert!(matches!(
+            Linker::new().object(start.clone()).link(),
+            Err(LinkError::UndefinedSymbol { name, obj_idx: 0 }) if name == "add"
+        ));
+        assert!(matches!(
+            Linker::new()
+                .object(start.clone())
+                .object(start.clone())
+                .link(),
+            Err(LinkError::DuplicateSymbol { obj_idx: 1, .. })
+        ));
+        assert_eq!(
+            Err(LinkError::EntryNotFound {
+                name: "main".to_string()
+            }),
+            Linker::new()
+                .object(start.clone())
+                .object(add)
+                .entry("main")
+                .link()
+                .map(|_| ())
+        );
+        assert_eq!(Err(LinkError::NoInput), Linker::new().link().map(|_| ()));
+
+        let exe = parser::parse_elf64("src/parser/testdata/sample").unwrap();
+        assert_eq!(
+            Err(LinkError::NotRelocatable { obj_idx: 1 }),
+            Linker::new().object(start).object(exe).link().map(|_| ())
+        );
+    }
+}
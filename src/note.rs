@@ -0,0 +1,80 @@
+//! ELF note (`SHT_NOTE` / `PT_NOTE`) utilities.
+
+mod descriptor;
+mod elf_note;
+
+pub use descriptor::*;
+pub use elf_note::*;
+
+/// owner name of GNU notes
+pub const ELF_NOTE_GNU: &str = "GNU";
+/// owner name of Go notes
+pub const ELF_NOTE_GO: &str = "Go";
+/// owner name of FreeBSD notes
+pub const ELF_NOTE_FREEBSD: &str = "FreeBSD";
+/// owner name of core dump notes
+pub const ELF_NOTE_CORE: &str = "CORE";
+/// owner name of Linux specific core dump notes
+pub const ELF_NOTE_LINUX: &str = "LINUX";
+
+/// ABI information(`.note.ABI-tag`)
+pub const NT_GNU_ABI_TAG: u32 = 1;
+/// synthetic hwcap information
+pub const NT_GNU_HWCAP: u32 = 2;
+/// unique build ID bitstring(`.note.gnu.build-id`)
+pub const NT_GNU_BUILD_ID: u32 = 3;
+/// version of gold
+pub const NT_GNU_GOLD_VERSION: u32 = 4;
+/// program property(`.note.gnu.property`)
+pub const NT_GNU_PROPERTY_TYPE_0: u32 = 5;
+
+/// build ID of Go binaries(`.note.go.buildid`)
+pub const NT_GO_BUILD_ID: u32 = 4;
+
+/// `__FreeBSD_version` the binary was built for
+pub const NT_FREEBSD_ABI_TAG: u32 = 1;
+/// no-op note to mark a binary does not need crt1's init
+pub const NT_FREEBSD_NOINIT_TAG: u32 = 2;
+/// architecture the binary was built for
+pub const NT_FREEBSD_ARCH_TAG: u32 = 3;
+/// feature control flags
+pub const NT_FREEBSD_FEATURE_CTL: u32 = 4;
+
+/// process status(`elf_prstatus`) in core dumps
+pub const NT_PRSTATUS: u32 = 1;
+/// floating point registers in core dumps
+pub const NT_PRFPREG: u32 = 2;
+/// process information(`elf_prpsinfo`) in core dumps
+pub const NT_PRPSINFO: u32 = 3;
+/// auxiliary vector in core dumps
+pub const NT_AUXV: u32 = 6;
+/// `siginfo_t` in core dumps
+pub const NT_SIGINFO: u32 = 0x53494749;
+/// mapped files in core dumps
+pub const NT_FILE: u32 = 0x46494c45;
+
+/// OS values of `NT_GNU_ABI_TAG`
+pub const ELF_NOTE_OS_LINUX: u32 = 0;
+pub const ELF_NOTE_OS_GNU: u32 = 1;
+pub const ELF_NOTE_OS_SOLARIS2: u32 = 2;
+pub const ELF_NOTE_OS_FREEBSD: u32 = 3;
+
+/// stack size property of `NT_GNU_PROPERTY_TYPE_0`
+pub const GNU_PROPERTY_STACK_SIZE: u32 = 1;
+/// no copy relocation on protected data symbol
+pub const GNU_PROPERTY_NO_COPY_ON_PROTECTED: u32 = 2;
+/// AArch64 features(BTI, PAC)
+pub const GNU_PROPERTY_AARCH64_FEATURE_1_AND: u32 = 0xc0000000;
+/// x86 ISA compatibility
+pub const GNU_PROPERTY_X86_ISA_1_USED: u32 = 0xc0010002;
+/// x86 ISA needed
+pub const GNU_PROPERTY_X86_ISA_1_NEEDED: u32 = 0xc0008002;
+/// x86 features(IBT, SHSTK)
+pub const GNU_PROPERTY_X86_FEATURE_1_AND: u32 = 0xc0000002;
+
+/// `GNU_PROPERTY_X86_FEATURE_1_AND` bits
+pub const GNU_PROPERTY_X86_FEATURE_1_IBT: u32 = 1 << 0;
+pub const GNU_PROPERTY_X86_FEATURE_1_SHSTK: u32 = 1 << 1;
+/// `GNU_PROPERTY_AARCH64_FEATURE_1_AND` bits
+pub const GNU_PROPERTY_AARCH64_FEATURE_1_BTI: u32 = 1 << 0;
+pub const GNU_PROPERTY_AARCH64_FEATURE_1_PAC: u32 = 1 << 1;
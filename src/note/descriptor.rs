@@ -0,0 +1,162 @@
+use crate::*;
+
+use super::{align_up, Note};
+
+/// Decoded descriptor of well-known notes
+#[derive(Debug, Clone, Hash, PartialOrd, Ord, PartialEq, Eq)]
+pub enum Descriptor {
+    /// `NT_GNU_ABI_TAG`: the OS(`ELF_NOTE_OS_*`) and the earliest kernel version
+    GNUABITag {
+        os: u32,
+        major: u32,
+        minor: u32,
+        patch: u32,
+    },
+    /// `NT_GNU_BUILD_ID`
+    GNUBuildID(Vec<u8>),
+    /// `NT_GNU_PROPERTY_TYPE_0`
+    GNUProperties(Vec<GNUProperty>),
+    /// `NT_GO_BUILD_ID`
+    GoBuildID(String),
+    /// `NT_FREEBSD_ABI_TAG`: `__FreeBSD_version`
+    FreeBSDABITag(u32),
+    /// `NT_FREEBSD_NOINIT_TAG`
+    FreeBSDNoInitTag(u32),
+    /// `NT_FREEBSD_ARCH_TAG`
+    FreeBSDArchTag(String),
+    /// `NT_FREEBSD_FEATURE_CTL`
+    FreeBSDFeatureCtl(u32),
+}
+
+/// An entry of the property array in `NT_GNU_PROPERTY_TYPE_0`
+#[derive(Default, Debug, Clone, Hash, PartialOrd, Ord, PartialEq, Eq)]
+pub struct GNUProperty {
+    /// property type(`GNU_PROPERTY_*`)
+    pub pr_type: u32,
+    /// raw property data
+    pub pr_data: Vec<u8>,
+}
+
+impl GNUProperty {
+    /// read the data as a 32-bit value, which most of properties(e.g. `*_FEATURE_1_AND`) have.
+    pub fn as_u32(&self, data: header::Data) -> Option<u32> {
+        read_u32(data, &self.pr_data, 0)
+    }
+}
+
+impl Descriptor {
+    /// decode the descriptor of `note`.
+    /// return `None` if the note is unknown or malformed.
+    pub fn decode(note: &Note, data: header::Data, align: usize) -> Option<Self> {
+        let desc = &note.desc;
+        match (note.name.as_str(), note.n_type) {
+            (note::ELF_NOTE_GNU, note::NT_GNU_ABI_TAG) => Some(Self::GNUABITag {
+                os: read_u32(data, desc, 0)?,
+                major: read_u32(data, desc, 4)?,
+                minor: read_u32(data, desc, 8)?,
+                patch: read_u32(data, desc, 12)?,
+            }),
+            (note::ELF_NOTE_GNU, note::NT_GNU_BUILD_ID) => Some(Self::GNUBuildID(desc.clone())),
+            (note::ELF_NOTE_GNU, note::NT_GNU_PROPERTY_TYPE_0) => {
+                decode_properties(data, align, desc).map(Self::GNUProperties)
+            }
+            (note::ELF_NOTE_GO, note::NT_GO_BUILD_ID) => read_string(desc).map(Self::GoBuildID),
+            (note::ELF_NOTE_FREEBSD, note::NT_FREEBSD_ABI_TAG) => {
+                read_u32(data, desc, 0).map(Self::FreeBSDABITag)
+            }
+            (note::ELF_NOTE_FREEBSD, note::NT_FREEBSD_NOINIT_TAG) => {
+                read_u32(data, desc, 0).map(Self::FreeBSDNoInitTag)
+            }
+            (note::ELF_NOTE_FREEBSD, note::NT_FREEBSD_ARCH_TAG) => {
+                read_string(desc).map(Self::FreeBSDArchTag)
+            }
+            (note::ELF_NOTE_FREEBSD, note::NT_FREEBSD_FEATURE_CTL) => {
+                read_u32(data, desc, 0).map(Self::FreeBSDFeatureCtl)
+            }
+            _ => None,
+        }
+    }
+
+    /// the owner name and the type of the note
+    pub fn name_and_type(&self) -> (&'static str, u32) {
+        match self {
+            Self::GNUABITag { .. } => (note::ELF_NOTE_GNU, note::NT_GNU_ABI_TAG),
+            Self::GNUBuildID(_) => (note::ELF_NOTE_GNU, note::NT_GNU_BUILD_ID),
+            Self::GNUProperties(_) => (note::ELF_NOTE_GNU, note::NT_GNU_PROPERTY_TYPE_0),
+            Self::GoBuildID(_) => (note::ELF_NOTE_GO, note::NT_GO_BUILD_ID),
+            Self::FreeBSDABITag(_) => (note::ELF_NOTE_FREEBSD, note::NT_FREEBSD_ABI_TAG),
+            Self::FreeBSDNoInitTag(_) => (note::ELF_NOTE_FREEBSD, note::NT_FREEBSD_NOINIT_TAG),
+            Self::FreeBSDArchTag(_) => (note::ELF_NOTE_FREEBSD, note::NT_FREEBSD_ARCH_TAG),
+            Self::FreeBSDFeatureCtl(_) => (note::ELF_NOTE_FREEBSD, note::NT_FREEBSD_FEATURE_CTL),
+        }
+    }
+
+    /// encode the descriptor
+    pub fn to_bytes(&self, data: header::Data, align: usize) -> Vec<u8> {
+        match self {
+            Self::GNUABITag {
+                os,
+                major,
+                minor,
+                patch,
+            } => [os, major, minor, patch]
+                .iter()
+                .flat_map(|v| data.serialize(*v))
+                .collect(),
+            Self::GNUBuildID(id) => id.clone(),
+            Self::GNUProperties(props) => {
+                let mut bytes = Vec::new();
+                for prop in props.iter() {
+                    bytes.append(&mut data.serialize(&prop.pr_type));
+                    bytes.append(&mut data.serialize(&(prop.pr_data.len() as u32)));
+                    bytes.extend_from_slice(&prop.pr_data);
+                    bytes.resize(align_up(bytes.len(), align), 0x00);
+                }
+                bytes
+            }
+            Self::GoBuildID(s) | Self::FreeBSDArchTag(s) => {
+                let mut bytes = s.as_bytes().to_vec();
+                bytes.push(0x00);
+                bytes
+            }
+            Self::FreeBSDABITag(v) | Self::FreeBSDNoInitTag(v) | Self::FreeBSDFeatureCtl(v) => {
+                data.serialize(v)
+            }
+        }
+    }
+
+    /// build a note entry from the descriptor
+    pub fn to_note(&self, data: header::Data, align: usize) -> Note {
+        let (name, n_type) = self.name_and_type();
+        Note::new(name, n_type, self.to_bytes(data, align))
+    }
+}
+
+fn decode_properties(data: header::Data, align: usize, desc: &[u8]) -> Option<Vec<GNUProperty>> {
+    let mut props = Vec::new();
+    let mut offset = 0;
+    while offset < desc.len() {
+        let pr_type = read_u32(data, desc, offset)?;
+        let pr_datasz = read_u32(data, desc, offset + 4)? as usize;
+        let start = offset + 8;
+        let pr_data = desc.get(start..start.checked_add(pr_datasz)?)?.to_vec();
+        props.push(GNUProperty { pr_type, pr_data });
+
+        // 各プロパティはELF64なら8バイト，ELF32なら4バイトに整列されている
+        offset = align_up(start + pr_datasz, align);
+    }
+    Some(props)
+}
+
+fn read_u32(data: header::Data, buf: &[u8], offset: usize) -> Option<u32> {
+    data.deserialize(buf.get(offset..offset.checked_add(4)?)?)
+        .ok()
+}
+
+fn read_string(desc: &[u8]) -> Option<String> {
+    // 終端のnull-byte以降は無視する
+    let end = desc.iter().position(|&c| c == 0x00).unwrap_or(desc.len());
+    std::str::from_utf8(&desc[..end])
+        .ok()
+        .map(|s| s.to_string())
+}
@@ -0,0 +1,198 @@
+use crate::*;
+use serde::{Deserialize, Serialize};
+
+/// Note header. the layout is same in 32-bit and 64-bit ELF.
+#[derive(
+    Default, Debug, Clone, Copy, Hash, PartialOrd, Ord, PartialEq, Eq, Serialize, Deserialize,
+)]
+#[repr(C)]
+pub struct Nhdr {
+    /// Length of the note's name(including the terminator)
+    pub n_namesz: Elf64Word,
+    /// Length of the note's descriptor
+    pub n_descsz: Elf64Word,
+    /// Type of the note
+    pub n_type: Elf64Word,
+}
+
+/// A note entry
+#[derive(Default, Debug, Clone, Hash, PartialOrd, Ord, PartialEq, Eq)]
+pub struct Note {
+    /// owner name like `GNU`, without the terminator
+    pub name: String,
+    /// note type, its meaning depends on the owner
+    pub n_type: Elf64Word,
+    /// raw descriptor
+    pub desc: Vec<u8>,
+    /// the name field in the file(`n_namesz` bytes) if it isn't `name` + the terminator,
+    /// like Go's `"Go\0\0"` or a name which is not valid UTF-8.
+    /// it is written back as it is while `name` is unchanged.
+    pub raw_name: Option<Vec<u8>>,
+}
+
+/// Note entries in a note section or a note segment.
+///
+/// # Examples
+///
+/// ```
+/// use elf_utilities::{header, note};
+///
+/// let build_id = note::Descriptor::GNUBuildID(vec![0xde, 0xad, 0xbe, 0xef]);
+/// let table = note::NoteTable::new(4).note(build_id.to_note(header::Data::LSB2, 4));
+///
+/// // Nhdr(12) + "GNU\0"(4) + build id(4)
+/// assert_eq!(20, table.size());
+/// ```
+#[derive(Default, Debug, Clone, Hash, PartialOrd, Ord, PartialEq, Eq)]
+pub struct NoteTable {
+    /// alignment of the name and the descriptor(4 or 8)
+    pub align: usize,
+    pub notes: Vec<Note>,
+}
+
+impl Nhdr {
+    pub const SIZE: usize = 12;
+
+    pub fn to_le_bytes(&self) -> Vec<u8> {
+        self.to_bytes(header::Data::LSB2)
+    }
+
+    /// Create Vec<u8> from this with the given data encoding.
+    pub fn to_bytes(&self, data: header::Data) -> Vec<u8> {
+        data.serialize(self)
+    }
+}
+
+impl Note {
+    pub fn new(name: &str, n_type: Elf64Word, desc: Vec<u8>) -> Self {
+        Self {
+            name: name.to_string(),
+            n_type,
+            desc,
+            raw_name: None,
+        }
+    }
+
+    /// create an entry from the name field in the file.
+    /// a name which is not valid UTF-8 is decoded lossily into `name`.
+    pub(crate) fn from_raw_name(raw_name: &[u8], n_type: Elf64Word, desc: Vec<u8>) -> Self {
+        let mut n = Self::new(&decode_name(raw_name), n_type, desc);
+        if n.name_bytes() != raw_name {
+            n.raw_name = Some(raw_name.to_vec());
+        }
+        n
+    }
+
+    /// the header of this entry
+    pub fn header(&self) -> Nhdr {
+        Nhdr {
+            n_namesz: self.name_size() as Elf64Word,
+            n_descsz: self.desc.len() as Elf64Word,
+            n_type: self.n_type,
+        }
+    }
+
+    /// the size of this entry in a table aligned by `align`
+    pub fn size(&self, align: usize) -> usize {
+        align_up(self.desc_offset(align) + self.desc.len(), align)
+    }
+
+    /// decode well-known descriptors.
+    /// `align` is used to decode the property array of `NT_GNU_PROPERTY_TYPE_0`.
+    pub fn descriptor(&self, data: header::Data, align: usize) -> Option<note::Descriptor> {
+        note::Descriptor::decode(self, data, align)
+    }
+
+    pub fn to_bytes(&self, data: header::Data, align: usize) -> Vec<u8> {
+        let mut bytes = self.header().to_bytes(data);
+
+        bytes.append(&mut self.name_bytes());
+        bytes.resize(self.desc_offset(align), 0x00);
+
+        bytes.extend_from_slice(&self.desc);
+        bytes.resize(self.size(align), 0x00);
+        bytes
+    }
+
+    fn desc_offset(&self, align: usize) -> usize {
+        // nameとdescの開始位置はそれぞれalignに整列される
+        align_up(Nhdr::SIZE + self.name_size(), align)
+    }
+
+    fn name_size(&self) -> usize {
+        self.name_bytes().len()
+    }
+
+    fn name_bytes(&self) -> Vec<u8> {
+        match &self.raw_name {
+            // nameが変更されていなければ，ファイル上のバイト列をそのまま使う
+            Some(raw_name) if decode_name(raw_name) == self.name => raw_name.clone(),
+            // 名前が空でなければ，終端のnull-byteを含む
+            _ if self.name.is_empty() => Vec::new(),
+            _ => {
+                let mut bytes = self.name.as_bytes().to_vec();
+                bytes.push(0x00);
+                bytes
+            }
+        }
+    }
+}
+
+/// the name before the terminator
+fn decode_name(raw_name: &[u8]) -> String {
+    let name_len = raw_name
+        .iter()
+        .position(|&c| c == 0x00)
+        .unwrap_or(raw_name.len());
+    String::from_utf8_lossy(&raw_name[..name_len]).into_owned()
+}
+
+impl NoteTable {
+    pub fn new(align: usize) -> Self {
+        Self {
+            align,
+            notes: Vec::new(),
+        }
+    }
+
+    /// append an entry
+    pub fn note(mut self, note: Note) -> Self {
+        self.notes.push(note);
+        self
+    }
+
+    /// get the first entry which has the owner name and the type
+    pub fn find(&self, name: &str, n_type: Elf64Word) -> Option<&Note> {
+        self.notes
+            .iter()
+            .find(|n| n.name == name && n.n_type == n_type)
+    }
+
+    pub fn size(&self) -> usize {
+        self.notes.iter().map(|n| n.size(self.align)).sum()
+    }
+
+    pub fn to_bytes(&self, data: header::Data) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.size());
+        for n in self.notes.iter() {
+            bytes.append(&mut n.to_bytes(data, self.align));
+        }
+        bytes
+    }
+}
+
+/// notes are aligned on 4-byte boundaries, except 8-byte aligned sections/segments
+pub(crate) fn note_alignment(sh_addralign: u64) -> usize {
+    if sh_addralign == 8 {
+        8
+    } else {
+        4
+    }
+}
+
+pub(crate) fn align_up(v: usize, align: usize) -> usize {
+    if align <= 1 {
+        return v;
+    }
+    v.div_ceil(align) * align
+}
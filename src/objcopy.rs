@@ -0,0 +1,172 @@
+//! objcopy-like conversion utilities.
+
+use std::convert::TryFrom;
+
+use thiserror::Error as TError;
+
+#[derive(TError, Debug)]
+pub enum ObjcopyError {
+    #[error("section `{name}` is not found")]
+    SectionNotFound { name: String },
+    #[error("can't dump section `{name}` => it has no contents")]
+    NoContents { name: String },
+    #[error("can't read `{file_path}` => `{k}`")]
+    CantRead {
+        file_path: String,
+        k: std::io::Error,
+    },
+    #[error("can't write `{file_path}` => `{k}`")]
+    CantWrite {
+        file_path: String,
+        k: std::io::Error,
+    },
+    #[error("PT_LOAD(p_paddr={paddr:#x}, p_offset={offset:#x}, p_filesz={filesz:#x}) overflows")]
+    LoadOverflow {
+        paddr: u64,
+        offset: u64,
+        filesz: u64,
+    },
+    #[error("flat image of {size:#x} bytes exceeds {limit:#x} bytes")]
+    ImageTooLarge { size: u64, limit: u64 },
+}
+
+/// the largest image `to_raw_binary()` creates.
+/// gaps between `PT_LOAD`s are filled with zero, so distant `p_paddr`s make a huge image.
+pub const MAX_RAW_BINARY_SIZE: u64 = 1 << 30;
+
+/// class-independent fields of a `PT_LOAD` used for flattening
+pub(crate) struct LoadImage {
+    pub paddr: u64,
+    pub offset: u64,
+    pub filesz: u64,
+}
+
+/// place the file image of each `PT_LOAD` at `p_paddr` relative to the lowest one.
+/// gaps are filled with zero, and segments without file bytes are ignored.
+pub(crate) fn flatten_loads(
+    file_binary: &[u8],
+    loads: &[LoadImage],
+) -> Result<Vec<u8>, ObjcopyError> {
+    let loads: Vec<&LoadImage> = loads.iter().filter(|load| load.filesz != 0).collect();
+    let base = match loads.iter().map(|load| load.paddr).min() {
+        Some(base) => base,
+        None => return Ok(Vec::new()),
+    };
+    let overflow = |load: &LoadImage| ObjcopyError::LoadOverflow {
+        paddr: load.paddr,
+        offset: load.offset,
+        filesz: load.filesz,
+    };
+
+    // baseは最小のp_paddrなので，引き算は溢れない
+    let mut len = 0;
+    for load in loads.iter() {
+        let end = (load.paddr - base)
+            .checked_add(load.filesz)
+            .ok_or_else(|| overflow(load))?;
+        len = len.max(end);
+    }
+    if len > MAX_RAW_BINARY_SIZE {
+        return Err(ObjcopyError::ImageTooLarge {
+            size: len,
+            limit: MAX_RAW_BINARY_SIZE,
+        });
+    }
+
+    let mut image = vec![0x00; len as usize];
+    for load in loads {
+        let start = (load.paddr - base) as usize;
+        let file_end = load
+            .offset
+            .checked_add(load.filesz)
+            .ok_or_else(|| overflow(load))?;
+        // ファイル末尾を超える部分は0のまま
+        let end = file_binary
+            .len()
+            .min(usize::try_from(file_end).unwrap_or(usize::MAX));
+        let offset = usize::try_from(load.offset).unwrap_or(usize::MAX);
+        if offset < end {
+            image[start..start + (end - offset)].copy_from_slice(&file_binary[offset..end]);
+        }
+    }
+    Ok(image)
+}
+
+pub(crate) fn write_file(file_path: &str, bytes: &[u8]) -> Result<(), ObjcopyError> {
+    std::fs::write(file_path, bytes).map_err(|k| ObjcopyError::CantWrite {
+        file_path: file_path.to_string(),
+        k,
+    })
+}
+
+pub(crate) fn read_file(file_path: &str) -> Result<Vec<u8>, ObjcopyError> {
+    std::fs::read(file_path).map_err(|k| ObjcopyError::CantRead {
+        file_path: file_path.to_string(),
+        k,
+    })
+}
+
+#[cfg(test)]
+mod objcopy_tests {
+    use super::*;
+
+    #[test]
+    fn flatten_loads_test() {
+        let file_binary: Vec<u8> = (0..0x20).collect();
+        let loads = vec![
+            LoadImage {
+                paddr: 0x0800_0010,
+                offset: 0x18,
+                filesz: 4,
+            },
+            LoadImage {
+                paddr: 0x0800_0000,
+                offset: 0x8,
+                filesz: 8,
+            },
+            // .bssのみのセグメント
+            LoadImage {
+                paddr: 0x2000_0000,
+                offset: 0x20,
+                filesz: 0,
+            },
+        ];
+
+        let image = flatten_loads(&file_binary, &loads).unwrap();
+        assert_eq!(0x14, image.len());
+        assert_eq!(vec![8, 9, 10, 11, 12, 13, 14, 15], image[..8]);
+        assert_eq!(vec![0; 8], image[8..0x10]);
+        assert_eq!(vec![0x18, 0x19, 0x1a, 0x1b], image[0x10..]);
+        assert!(flatten_loads(&file_binary, &[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn flatten_broken_loads_test() {
+        let file_binary: Vec<u8> = (0..0x20).collect();
+        let load = |paddr, offset, filesz| LoadImage {
+            paddr,
+            offset,
+            filesz,
+        };
+
+        // p_paddr + p_filesz, p_offset + p_fileszが溢れる
+        assert!(matches!(
+            flatten_loads(&file_binary, &[load(0, 0, 8), load(u64::MAX, 0, 8)]),
+            Err(ObjcopyError::LoadOverflow { .. })
+        ));
+        assert!(matches!(
+            flatten_loads(&file_binary, &[load(0, u64::MAX, 8)]),
+            Err(ObjcopyError::LoadOverflow { .. })
+        ));
+        // 離れたPT_LOADで巨大なイメージを確保しない
+        assert!(matches!(
+            flatten_loads(&file_binary, &[load(0, 0, 8), load(1 << 40, 8, 8)]),
+            Err(ObjcopyError::ImageTooLarge { .. })
+        ));
+        // ファイル末尾を超える部分は0で埋める
+        assert_eq!(
+            vec![0x1e, 0x1f, 0, 0],
+            flatten_loads(&file_binary, &[load(0, 0x1e, 4)]).unwrap()
+        );
+    }
+}
@@ -1,2 +1,4 @@
+mod lazy;
 mod parse;
+pub use lazy::*;
 pub use parse::*;
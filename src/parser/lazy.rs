@@ -0,0 +1,44 @@
+pub use elf32::*;
+pub use elf64::*;
+
+mod elf32;
+mod elf64;
+
+use std::io::{Read, Seek, SeekFrom};
+
+use super::ReadELFError;
+
+/// エラーメッセージでファイルパスの代わりに用いる
+const READER_SOURCE: &str = "<reader>";
+
+fn read_error(k: std::io::Error) -> ReadELFError {
+    ReadELFError::CantRead {
+        file_path: READER_SOURCE.to_string(),
+        k,
+    }
+}
+
+/// 入力全体の長さを調べる
+/// 壊れたsh_size等で巨大な領域を確保しないよう，読み込み前の範囲検査に用いる
+fn stream_len<R: Read + Seek>(reader: &mut R) -> Result<u64, ReadELFError> {
+    reader.seek(SeekFrom::End(0)).map_err(read_error)
+}
+
+/// `offset` から `size` バイトを読み込む
+/// 範囲外の場合はNoneを返す
+fn read_range<R: Read + Seek>(
+    reader: &mut R,
+    stream_len: u64,
+    offset: u64,
+    size: u64,
+) -> Result<Option<Vec<u8>>, ReadELFError> {
+    match offset.checked_add(size) {
+        Some(end) if end <= stream_len => {}
+        _ => return Ok(None),
+    }
+
+    let mut buf = vec![0x00; size as usize];
+    reader.seek(SeekFrom::Start(offset)).map_err(read_error)?;
+    reader.read_exact(&mut buf).map_err(read_error)?;
+    Ok(Some(buf))
+}
@@ -0,0 +1,283 @@
+use std::io::{Read, Seek};
+
+use crate::parser::{self, ReadELFError};
+use crate::*;
+
+/// A reader which loads section contents of a 64-bit ELF on demand.
+///
+/// the ELF header, the section header table and the program header table are parsed up front,
+/// and any other bytes are read from `R` only when they are requested.
+///
+/// # Examples
+///
+/// ```
+/// use elf_utilities::{parser, section};
+///
+/// let f = std::fs::File::open("src/parser/testdata/sample").unwrap();
+/// let mut reader = parser::ELF64Reader::new(f).unwrap();
+///
+/// let dynamic_idx = reader.section_by_name(".dynamic").unwrap();
+/// let dynamic = reader.section(dynamic_idx).unwrap();
+/// assert!(matches!(dynamic.contents, section::Contents64::Dynamics(_)));
+/// ```
+pub struct ELF64Reader<R: Read + Seek> {
+    reader: R,
+    stream_len: u64,
+    pub ehdr: header::Ehdr64,
+    pub section_headers: Vec<section::Shdr64>,
+    pub program_headers: Vec<segment::Phdr64>,
+    shstrndx: usize,
+    section_names: Vec<String>,
+}
+
+impl<R: Read + Seek> ELF64Reader<R> {
+    pub fn new(mut reader: R) -> Result<Self, ReadELFError> {
+        let stream_len = super::stream_len(&mut reader)?;
+
+        let header_size = header::Ehdr64::SIZE as u64;
+        let ehdr_bytes =
+            super::read_range(&mut reader, stream_len, 0, header_size.min(stream_len))?
+                .unwrap_or_default();
+        let (class, data) = parser::check_elf_ident(super::READER_SOURCE, &ehdr_bytes)?;
+        if class != header::Class::Bit64 {
+            return Err(ReadELFError::ClassMismatch { expected: 64 });
+        }
+        let ehdr: header::Ehdr64 =
+            data.deserialize(&ehdr_bytes)
+                .map_err(|_| ReadELFError::TruncatedHeader {
+                    size: header_size as usize,
+                    len: stream_len as usize,
+                })?;
+
+        // e_shnum等に収まらない値はNULLセクションに格納されている
+        let (e_shnum, e_shstrndx, e_phnum) = (
+            ehdr.e_shnum as usize,
+            ehdr.e_shstrndx as usize,
+            ehdr.e_phnum as usize,
+        );
+        let null_shdr = if ehdr.e_shoff != 0
+            && parser::HeaderNumbers::is_extended(e_shnum, e_shstrndx, e_phnum)
+        {
+            let sh0: section::Shdr64 = super::read_range(
+                &mut reader,
+                stream_len,
+                ehdr.e_shoff,
+                section::Shdr64::SIZE as u64,
+            )?
+            .and_then(|bytes| data.deserialize(&bytes).ok())
+            .ok_or(ReadELFError::SectionHeaderTableOutOfBounds {
+                offset: ehdr.e_shoff as usize,
+                number: 1,
+            })?;
+            Some((
+                sh0.sh_size as usize,
+                sh0.sh_link as usize,
+                sh0.sh_info as usize,
+            ))
+        } else {
+            None
+        };
+        let numbers = parser::HeaderNumbers::resolve(e_shnum, e_shstrndx, e_phnum, null_shdr);
+
+        let sht_out_of_bounds = || ReadELFError::SectionHeaderTableOutOfBounds {
+            offset: ehdr.e_shoff as usize,
+            number: numbers.shnum,
+        };
+        let sht_size = section::Shdr64::SIZE
+            .checked_mul(numbers.shnum)
+            .ok_or_else(sht_out_of_bounds)?;
+        let sht = super::read_range(&mut reader, stream_len, ehdr.e_shoff, sht_size as u64)?
+            .ok_or_else(sht_out_of_bounds)?;
+        let section_headers = sht
+            .chunks_exact(section::Shdr64::SIZE)
+            .map(|entry| data.deserialize(entry).map_err(|_| sht_out_of_bounds()))
+            .collect::<Result<_, _>>()?;
+
+        let pht_out_of_bounds = || ReadELFError::ProgramHeaderTableOutOfBounds {
+            offset: ehdr.e_phoff as usize,
+            number: numbers.phnum,
+        };
+        let pht_size = segment::Phdr64::SIZE
+            .checked_mul(numbers.phnum)
+            .ok_or_else(pht_out_of_bounds)?;
+        let pht = super::read_range(&mut reader, stream_len, ehdr.e_phoff, pht_size as u64)?
+            .ok_or_else(pht_out_of_bounds)?;
+        let program_headers = pht
+            .chunks_exact(segment::Phdr64::SIZE)
+            .map(|entry| data.deserialize(entry).map_err(|_| pht_out_of_bounds()))
+            .collect::<Result<_, _>>()?;
+
+        let mut elf_reader = Self {
+            reader,
+            stream_len,
+            ehdr,
+            section_headers,
+            program_headers,
+            shstrndx: numbers.shstrndx,
+            section_names: Vec::new(),
+        };
+        elf_reader.section_names = elf_reader.read_section_names()?;
+
+        Ok(elf_reader)
+    }
+
+    /// the index of `.shstrtab`, resolved from section 0 when `e_shstrndx` is `SHN_XINDEX`.
+    pub fn shstrndx(&self) -> usize {
+        self.shstrndx
+    }
+
+    pub fn section_name(&self, idx: usize) -> Option<&str> {
+        self.section_names.get(idx).map(|s| s.as_str())
+    }
+
+    /// get the first section index which has the name
+    pub fn section_by_name(&self, name: &str) -> Option<usize> {
+        self.section_names.iter().position(|s| s == name)
+    }
+
+    /// read the raw section contents.
+    /// `NoBits` sections have no bytes in the file, so an empty vector is returned.
+    pub fn section_data(&mut self, idx: usize) -> Result<Vec<u8>, ReadELFError> {
+        let shdr = *self
+            .section_headers
+            .get(idx)
+            .ok_or(ReadELFError::InvalidSectionIndex {
+                index: idx,
+                number: self.section_headers.len(),
+            })?;
+        if shdr.get_type() == section::Type::NoBits {
+            return Ok(Vec::new());
+        }
+
+        super::read_range(
+            &mut self.reader,
+            self.stream_len,
+            shdr.sh_offset,
+            shdr.sh_size,
+        )?
+        .ok_or(ReadELFError::SectionOutOfBounds {
+            index: idx,
+            offset: shdr.sh_offset as usize,
+            size: shdr.sh_size as usize,
+        })
+    }
+
+    /// read a section and decode its contents like `parser::parse_elf64()`.
+    /// symbol tables also load the linked string table to name each symbol.
+    pub fn section(&mut self, idx: usize) -> Result<section::Section64, ReadELFError> {
+        let class = header::Class::Bit64;
+        let data = self.ehdr.get_data();
+
+        let raw = self.section_data(idx)?;
+        let mut sct = section::Section::new(section::Shdr::Shdr64(self.section_headers[idx]));
+        if sct.ty() != section::Type::NoBits {
+            sct.contents = parser::parse_section_contents(class, data, idx, &sct, &raw)?;
+        }
+
+        if sct.contents.has_linked_names() {
+            let strtab_idx = sct.link();
+            let strtab_raw = self.section_data(strtab_idx)?;
+            let mut strtab =
+                section::Section::new(section::Shdr::Shdr64(self.section_headers[strtab_idx]));
+            strtab.contents =
+                parser::parse_section_contents(class, data, strtab_idx, &strtab, &strtab_raw)?;
+            let strtab = parser::string_table_contents(&strtab, strtab_idx)?;
+            parser::assign_linked_names(&mut sct.contents, &strtab, strtab_idx)?;
+        }
+
+        sct.name = self.section_names[idx].clone();
+        Ok(sct.as_64bit())
+    }
+
+    /// read the segment contents in the file(`p_filesz` bytes from `p_offset`).
+    pub fn segment_data(&mut self, idx: usize) -> Result<Vec<u8>, ReadELFError> {
+        let phdr =
+            *self
+                .program_headers
+                .get(idx)
+                .ok_or(ReadELFError::ProgramHeaderTableOutOfBounds {
+                    offset: self.ehdr.e_phoff as usize,
+                    number: self.program_headers.len(),
+                })?;
+
+        super::read_range(
+            &mut self.reader,
+            self.stream_len,
+            phdr.p_offset,
+            phdr.p_filesz,
+        )?
+        .ok_or(ReadELFError::SegmentOutOfBounds {
+            index: idx,
+            offset: phdr.p_offset as usize,
+            size: phdr.p_filesz as usize,
+        })
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// .shstrtabを読み込んで各セクション名を取り出す
+    fn read_section_names(&mut self) -> Result<Vec<String>, ReadELFError> {
+        let shstrndx = self.shstrndx;
+        if self.section_headers.is_empty() || shstrndx == section::SHN_UNDEF as usize {
+            return Ok(vec![String::new(); self.section_headers.len()]);
+        }
+
+        let shstrtab = self.section_data(shstrndx)?;
+        self.section_headers
+            .iter()
+            .map(|shdr| {
+                if shdr.sh_name == 0 {
+                    return Ok(String::new());
+                }
+                parser::string_in_table(&shstrtab, shstrndx, shdr.sh_name as usize)
+                    .map(|s| s.to_string())
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod elf64_tests {
+    use super::*;
+
+    #[test]
+    fn lazy_reader_matches_parsed_elf64_test() {
+        let f = parser::parse_elf64("src/parser/testdata/sample").unwrap();
+        let file = std::fs::File::open("src/parser/testdata/sample").unwrap();
+        let mut reader = ELF64Reader::new(file).unwrap();
+
+        assert_eq!(f.ehdr, reader.ehdr);
+        assert_eq!(f.segments.len(), reader.program_headers.len());
+        for (idx, sct) in f.sections.iter().enumerate() {
+            assert_eq!(sct.header, reader.section_headers[idx]);
+            assert_eq!(Some(sct.name.as_str()), reader.section_name(idx));
+            assert!(*sct == reader.section(idx).unwrap());
+        }
+
+        let interp = reader.segment_data(1).unwrap();
+        assert_eq!(b"/lib64/ld-linux-x86-64.so.2\0", interp.as_slice());
+
+        assert!(reader.section(29).is_err());
+        assert!(reader.segment_data(13).is_err());
+
+        let truncated = std::io::Cursor::new(
+            std::fs::read("src/parser/testdata/sample").unwrap()[..0x3000].to_vec(),
+        );
+        assert!(matches!(
+            ELF64Reader::new(truncated),
+            Err(ReadELFError::SectionHeaderTableOutOfBounds { .. })
+        ));
+
+        // NULLセクションのsh_sizeが巨大でもパニックしない
+        let mut huge = std::fs::read("src/parser/testdata/sample").unwrap();
+        let shoff = f.ehdr.e_shoff as usize;
+        huge[0x3c..0x3e].fill(0);
+        huge[shoff + 0x20..shoff + 0x28].fill(0xff);
+        assert!(matches!(
+            ELF64Reader::new(std::io::Cursor::new(huge)),
+            Err(ReadELFError::SectionHeaderTableOutOfBounds { .. })
+        ));
+    }
+}
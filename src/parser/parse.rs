@@ -5,58 +5,200 @@ use std::io::Read;
 use serde::Deserialize;
 use thiserror::Error as TError;
 
+/// errors while parsing an ELF file.
+///
+/// broken headers make the parse fail: the ELF header, SHT/PHT, section bounds,
+/// `sh_entsize`/`sh_link` and the names of sections and symbols.
+/// a section whose contents can't be decoded as its `sh_type`
+/// (e.g. a non UTF-8 string table or a broken hash/version/note table) is kept as `Raw` instead.
 #[derive(TError, Debug)]
 pub enum ReadELFError {
+    #[error("can't read `{file_path}` => `{k}`")]
+    CantRead {
+        file_path: String,
+        k: std::io::Error,
+    },
     #[error("input file `{file_path}` is not an ELF file")]
     NotELF { file_path: String },
-    #[error("can't parse elf header => `{k}`")]
-    CantParseELFHeader { k: Box<dyn std::error::Error> },
-    #[error("can't parse section header => `{k}`")]
-    CantParseSectionHeader { k: Box<dyn std::error::Error> },
-    #[error("can't parse program header => `{k}`")]
-    CantParseProgramHeader { k: Box<dyn std::error::Error> },
-    #[error("can't parse symbol => `{k}`")]
-    CantParseSymbol { k: Box<dyn std::error::Error> },
+    #[error("unsupported ELF class `{class}`")]
+    UnsupportedClass { class: u8 },
+    #[error("expected {expected}-bit ELF, but the input is not")]
+    ClassMismatch { expected: usize },
+    #[error("ELF header is truncated => needs {size} bytes, but the input has only {len} bytes")]
+    TruncatedHeader { size: usize, len: usize },
+    #[error("section header table is out of bounds => offset {offset:#x}, {number} entries")]
+    SectionHeaderTableOutOfBounds { offset: usize, number: usize },
+    #[error("program header table is out of bounds => offset {offset:#x}, {number} entries")]
+    ProgramHeaderTableOutOfBounds { offset: usize, number: usize },
+    #[error("section[{index}] is out of bounds => offset {offset:#x}, size {size:#x}")]
+    SectionOutOfBounds {
+        index: usize,
+        offset: usize,
+        size: usize,
+    },
+    #[error("segment[{index}] is out of bounds => offset {offset:#x}, size {size:#x}")]
+    SegmentOutOfBounds {
+        index: usize,
+        offset: usize,
+        size: usize,
+    },
+    #[error("section[{index}] has an invalid entry size {entsize:#x} at offset {offset:#x}")]
+    InvalidEntrySize {
+        index: usize,
+        offset: usize,
+        entsize: usize,
+    },
+    #[error("section[{index}] contains a non UTF-8 string at offset {offset:#x}")]
+    InvalidString { index: usize, offset: usize },
+    #[error("string index {name_idx:#x} is out of the string table section[{index}]")]
+    InvalidStringIndex { index: usize, name_idx: usize },
+    #[error("section index {index} is out of range => the file has {number} sections")]
+    InvalidSectionIndex { index: usize, number: usize },
+    #[error("section[{index}] is not a string table")]
+    NotStringTable { index: usize },
+    #[error("section[{index}] has a broken version chain")]
+    InvalidVersionTable { index: usize },
+    #[error("note entry at offset {offset:#x} in section/segment[{index}] is truncated")]
+    InvalidNote { index: usize, offset: usize },
+    #[error("virtual address {addr:#x} (size {size:#x}) is not backed by any PT_LOAD segment")]
+    UnmappedAddress { addr: usize, size: usize },
+    #[error("input file is not a core dump")]
+    NotCoreDump,
+    #[error("core dump note of type {n_type:#x} is truncated")]
+    InvalidCoreNote { n_type: u32 },
 }
 
 /// parse 64bit ELF
-pub fn parse_elf64(file_path: &str) -> Result<file::ELF64, Box<dyn std::error::Error>> {
-    Ok(parse_elf(file_path)?.as_64bit())
+pub fn parse_elf64(file_path: &str) -> Result<file::ELF64, ReadELFError> {
+    expect_64bit(parse_elf(file_path)?)
 }
 
 /// parse 32bit ELF
-pub fn parse_elf32(file_path: &str) -> Result<file::ELF32, Box<dyn std::error::Error>> {
-    Ok(parse_elf(file_path)?.as_32bit())
+pub fn parse_elf32(file_path: &str) -> Result<file::ELF32, ReadELFError> {
+    expect_32bit(parse_elf(file_path)?)
 }
 
 /// parse ELF and construct `file::ELF`
-pub fn parse_elf(file_path: &str) -> Result<file::ELF, Box<dyn std::error::Error>> {
-    let mut f = File::open(file_path)?;
+pub fn parse_elf(file_path: &str) -> Result<file::ELF, ReadELFError> {
+    let read_error = |k| ReadELFError::CantRead {
+        file_path: file_path.to_string(),
+        k,
+    };
+    let mut f = File::open(file_path).map_err(read_error)?;
     let mut buf = Vec::new();
-    let _ = f.read_to_end(&mut buf);
+    f.read_to_end(&mut buf).map_err(read_error)?;
 
-    let _ = check_elf_magic(file_path, &buf[..4])?;
+    parse_elf_buffer(file_path, &buf)
+}
 
-    // 32bit/64bitでパース処理を共通化するため，classを取っておく
-    let elf_class = header::Class::from(buf[header::Class::INDEX]);
+/// parse 64bit ELF from an in-memory image
+pub fn parse_elf64_bytes(buf: &[u8]) -> Result<file::ELF64, ReadELFError> {
+    expect_64bit(parse_elf_bytes(buf)?)
+}
+
+/// parse 32bit ELF from an in-memory image
+pub fn parse_elf32_bytes(buf: &[u8]) -> Result<file::ELF32, ReadELFError> {
+    expect_32bit(parse_elf_bytes(buf)?)
+}
+
+/// parse ELF from an in-memory image and construct `file::ELF`
+///
+/// # Examples
+///
+/// ```
+/// use elf_utilities::{file, parser};
+///
+/// let bytes = std::fs::read("src/parser/testdata/sample").unwrap();
+/// let f = parser::parse_elf_bytes(&bytes).unwrap();
+///
+/// assert!(matches!(f, file::ELF::ELF64(_)));
+/// ```
+pub fn parse_elf_bytes(buf: &[u8]) -> Result<file::ELF, ReadELFError> {
+    parse_elf_buffer(IN_MEMORY_SOURCE, buf)
+}
+
+/// parse ELF from any `Read` implementor(a socket, a archive member, etc.)
+pub fn parse_elf_from_reader<R: Read>(mut reader: R) -> Result<file::ELF, ReadELFError> {
+    let mut buf = Vec::new();
+    reader
+        .read_to_end(&mut buf)
+        .map_err(|k| ReadELFError::CantRead {
+            file_path: IN_MEMORY_SOURCE.to_string(),
+            k,
+        })?;
+
+    parse_elf_buffer(IN_MEMORY_SOURCE, &buf)
+}
+
+/// エラーメッセージでファイルパスの代わりに用いる
+pub(crate) const IN_MEMORY_SOURCE: &str = "<memory>";
 
-    let elf_header = parse_elf_header(elf_class, &buf)?;
-    let phdr_table_exists = elf_header.pht_exists();
+fn expect_64bit(f: file::ELF) -> Result<file::ELF64, ReadELFError> {
+    match f {
+        file::ELF::ELF64(f) => Ok(f),
+        _ => Err(ReadELFError::ClassMismatch { expected: 64 }),
+    }
+}
 
-    let mut sections = read_sht(elf_class, elf_header.shnum(), elf_header.sht_start(), &buf)?;
+fn expect_32bit(f: file::ELF) -> Result<file::ELF32, ReadELFError> {
+    match f {
+        file::ELF::ELF32(f) => Ok(f),
+        _ => Err(ReadELFError::ClassMismatch { expected: 32 }),
+    }
+}
+
+/// バッファからELFをパースする
+/// パス/バイト列/Readerの各APIはすべてここに集約される
+fn parse_elf_buffer(source: &str, buf: &[u8]) -> Result<file::ELF, ReadELFError> {
+    // 32bit/64bitでパース処理を共通化するため，classを取っておく
+    let (elf_class, elf_data) = check_elf_ident(source, buf)?;
+
+    let elf_header = parse_elf_header(elf_class, elf_data, buf)?;
+
+    // e_shnum等に収まらない値はNULLセクションに格納されている
+    let null_shdr = if HeaderNumbers::is_extended(
+        elf_header.shnum(),
+        elf_header.shstrndx(),
+        elf_header.phnum(),
+    ) {
+        read_null_shdr(elf_class, elf_data, elf_header.sht_start(), buf)?
+    } else {
+        None
+    };
+    let numbers = HeaderNumbers::resolve(
+        elf_header.shnum(),
+        elf_header.shstrndx(),
+        elf_header.phnum(),
+        null_shdr,
+    );
+
+    let mut sections = read_sht(
+        elf_class,
+        elf_data,
+        numbers.shnum,
+        elf_header.sht_start(),
+        buf,
+    )?;
     let mut segments = Vec::new();
 
-    if phdr_table_exists {
-        segments = read_pht(elf_class, elf_header.phnum(), elf_header.pht_start(), &buf)?;
+    // e_phnumがPN_XNUMでも，NULLセクションのsh_infoが0ならPHTは空
+    if elf_header.pht_exists() && numbers.phnum != 0 {
+        segments = read_pht(
+            elf_class,
+            elf_data,
+            numbers.phnum,
+            elf_header.pht_start(),
+            buf,
+        )?;
     }
 
     // セクション名の設定
     // .shstrtabセクションは大抵SHTの末尾にあるため，read_sht() 後に行う必要がある
-    naming_sections_from_shstrtab(elf_header.shstrndx(), &mut sections);
+    naming_sections_from_shstrtab(numbers.shstrndx, &mut sections)?;
 
     // シンボル名の設定
     // これもセクション名の設定と同様，SHTパース後に実行する必要があるため切り離している
-    naming_symbols(&mut sections);
+    naming_symbols(&mut sections)?;
 
     match elf_class {
         header::Class::Bit64 => Ok(file::ELF::ELF64(file::ELF64 {
@@ -64,40 +206,137 @@ pub fn parse_elf(file_path: &str) -> Result<file::ELF, Box<dyn std::error::Error
             sections: sections.iter().map(|sct| sct.as_64bit()).collect(),
             segments: segments.iter().map(|sgt| sgt.as_64bit()).collect(),
         })),
-        header::Class::Bit32 => Ok(file::ELF::ELF32(file::ELF32 {
+        _ => Ok(file::ELF::ELF32(file::ELF32 {
             ehdr: elf_header.as_32bit(),
             sections: sections.iter().map(|sct| sct.as_32bit()).collect(),
             segments: segments.iter().map(|sgt| sgt.as_32bit()).collect(),
         })),
-        _ => todo!(),
     }
 }
 
+/// マジックナンバーを検査し，e_identからclassとdataを取り出す
+pub(crate) fn check_elf_ident(
+    file_path: &str,
+    buf: &[u8],
+) -> Result<(header::Class, header::Data), ReadELFError> {
+    check_elf_magic(file_path, buf)?;
+
+    // e_identを読み切れない場合もここで弾いておく
+    if buf.len() < header::EI_NIDENT {
+        return Err(ReadELFError::TruncatedHeader {
+            size: header::EI_NIDENT,
+            len: buf.len(),
+        });
+    }
+    let class = header::Class::from(buf[header::Class::INDEX]);
+    if !matches!(class, header::Class::Bit32 | header::Class::Bit64) {
+        return Err(ReadELFError::UnsupportedClass {
+            class: class.to_identifier(),
+        });
+    }
+
+    // 各構造体のエンディアンはe_ident[EI_DATA]に従う
+    Ok((class, header::Data::from(buf[header::Data::INDEX])))
+}
+
+/// 拡張セクション番号を解決した，実際のセクション数等
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct HeaderNumbers {
+    pub shnum: usize,
+    pub shstrndx: usize,
+    pub phnum: usize,
+}
+
+impl HeaderNumbers {
+    /// 実際の値がNULLセクションに格納されているかチェック
+    pub(crate) fn is_extended(e_shnum: usize, e_shstrndx: usize, e_phnum: usize) -> bool {
+        e_shnum == 0
+            || e_shstrndx == section::SHN_XINDEX as usize
+            || e_phnum == segment::PN_XNUM as usize
+    }
+
+    /// `e_shnum` / `e_shstrndx` / `e_phnum` と，NULLセクションの `(sh_size, sh_link, sh_info)` から求める
+    pub(crate) fn resolve(
+        e_shnum: usize,
+        e_shstrndx: usize,
+        e_phnum: usize,
+        null_shdr: Option<(usize, usize, usize)>,
+    ) -> Self {
+        let (size, link, info) = null_shdr.unwrap_or_default();
+        Self {
+            shnum: if e_shnum == 0 { size } else { e_shnum },
+            shstrndx: if e_shstrndx == section::SHN_XINDEX as usize {
+                link
+            } else {
+                e_shstrndx
+            },
+            phnum: if e_phnum == segment::PN_XNUM as usize {
+                info
+            } else {
+                e_phnum
+            },
+        }
+    }
+}
+
+/// SHTの先頭にあるNULLセクションの `(sh_size, sh_link, sh_info)` を読む
+/// SHTが存在しなければNoneを返す
+fn read_null_shdr(
+    class: header::Class,
+    data: header::Data,
+    sht_offset: usize,
+    buf: &[u8],
+) -> Result<Option<(usize, usize, usize)>, ReadELFError> {
+    if sht_offset == 0 {
+        return Ok(None);
+    }
+
+    let sct = read_sht(class, data, 1, sht_offset, buf)?;
+    let sct = &sct[0];
+    Ok(Some((sct.size(), sct.link(), sct.info())))
+}
+
+/// `buf[offset..offset + size]` を，範囲外やオーバーフローの場合はNoneとして取り出す
+pub(crate) fn checked_slice(buf: &[u8], offset: usize, size: usize) -> Option<&[u8]> {
+    let end = offset.checked_add(size)?;
+    buf.get(offset..end)
+}
+
 /// セクションヘッダテーブルのパース
 fn read_sht(
     class: header::Class,
-
+    data: header::Data,
     section_number: usize,
     sht_offset: usize,
     buf: &[u8],
-) -> Result<Vec<section::Section>, Box<dyn std::error::Error>> {
-    let mut sections = Vec::with_capacity(50);
+) -> Result<Vec<section::Section>, ReadELFError> {
     let shdr_size = match class {
         header::Class::Bit32 => section::Shdr32::SIZE,
-        header::Class::Bit64 => section::Shdr64::SIZE,
-        _ => todo!(),
+        _ => section::Shdr64::SIZE,
+    };
+    let sht_out_of_bounds = || ReadELFError::SectionHeaderTableOutOfBounds {
+        offset: sht_offset,
+        number: section_number,
     };
 
+    // テーブル全体がバッファに収まるか先に検査しておく
+    let sht_size = shdr_size
+        .checked_mul(section_number)
+        .ok_or_else(sht_out_of_bounds)?;
+    let sht = checked_slice(buf, sht_offset, sht_size).ok_or_else(sht_out_of_bounds)?;
+
+    let mut sections = Vec::with_capacity(section_number);
     for sct_idx in 0..section_number {
-        let header_start = sht_offset + shdr_size * sct_idx;
+        let header_start = shdr_size * sct_idx;
         let shdr = match class {
-            header::Class::Bit32 => {
-                section::Shdr::Shdr32(bincode::deserialize(&buf[header_start..])?)
-            }
-            header::Class::Bit64 => {
-                section::Shdr::Shdr64(bincode::deserialize(&buf[header_start..])?)
-            }
-            _ => todo!(),
+            header::Class::Bit32 => section::Shdr::Shdr32(
+                data.deserialize(&sht[header_start..])
+                    .map_err(|_| sht_out_of_bounds())?,
+            ),
+            _ => section::Shdr::Shdr64(
+                data.deserialize(&sht[header_start..])
+                    .map_err(|_| sht_out_of_bounds())?,
+            ),
         };
 
         let mut sct = section::Section::new(shdr);
@@ -105,28 +344,16 @@ fn read_sht(
 
         if section_type != section::Type::NoBits {
             let section_offset = sct.offset();
-            let section_raw_contents =
-                buf[section_offset..section_offset + sct.size() as usize].to_vec();
-
-            sct.contents = match section_type {
-                section::Type::StrTab => parse_string_table(class, &section_raw_contents),
-                section::Type::SymTab | section::Type::DynSym => {
-                    parse_symbol_table(class, &sct, &section_raw_contents)
-                }
-                section::Type::Rela => parse_rela_symbol_table(class, &sct, &section_raw_contents),
-                section::Type::Dynamic => {
-                    parse_dynamic_information(class, &sct, &section_raw_contents)
-                }
-                _ => match class {
-                    header::Class::Bit32 => section::Contents::Contents32(
-                        section::Contents32::Raw(section_raw_contents),
-                    ),
-                    header::Class::Bit64 => section::Contents::Contents64(
-                        section::Contents64::Raw(section_raw_contents),
-                    ),
-                    _ => todo!(),
+            let section_raw_contents = checked_slice(buf, section_offset, sct.size()).ok_or(
+                ReadELFError::SectionOutOfBounds {
+                    index: sct_idx,
+                    offset: section_offset,
+                    size: sct.size(),
                 },
-            }
+            )?;
+
+            sct.contents =
+                parse_section_contents(class, data, sct_idx, &sct, section_raw_contents)?;
         }
 
         sections.push(sct);
@@ -135,7 +362,139 @@ fn read_sht(
     Ok(sections)
 }
 
-fn parse_string_table(class: header::Class, section_raw_contents: &Vec<u8>) -> section::Contents {
+/// セクションタイプに応じて中身をデコードする
+/// 中身が壊れているだけならパース全体を失敗させず，バイト列のまま保持する
+pub(crate) fn parse_section_contents(
+    class: header::Class,
+    data: header::Data,
+    sct_idx: usize,
+    sct: &section::Section,
+    section_raw_contents: &[u8],
+) -> Result<section::Contents, ReadELFError> {
+    match decode_section_contents(class, data, sct_idx, sct, section_raw_contents) {
+        Err(ReadELFError::InvalidString { .. })
+        | Err(ReadELFError::InvalidVersionTable { .. })
+        | Err(ReadELFError::InvalidNote { .. }) => Ok(raw_contents(class, section_raw_contents)),
+        result => result,
+    }
+}
+
+fn decode_section_contents(
+    class: header::Class,
+    data: header::Data,
+    sct_idx: usize,
+    sct: &section::Section,
+    section_raw_contents: &[u8],
+) -> Result<section::Contents, ReadELFError> {
+    match sct.ty() {
+        section::Type::StrTab => parse_string_table(class, sct_idx, section_raw_contents),
+        section::Type::SymTab | section::Type::DynSym => {
+            parse_symbol_table(class, data, sct_idx, sct, section_raw_contents)
+        }
+        section::Type::Rela => {
+            parse_rela_symbol_table(class, data, sct_idx, sct, section_raw_contents)
+        }
+        section::Type::Rel => {
+            parse_rel_symbol_table(class, data, sct_idx, sct, section_raw_contents)
+        }
+        section::Type::Dynamic => {
+            parse_dynamic_information(class, data, sct_idx, sct, section_raw_contents)
+        }
+        section::Type::SymTabShNdx => {
+            parse_symtab_shndx(class, data, sct_idx, sct, section_raw_contents)
+        }
+        section::Type::Group => {
+            let group = parse_group(data, sct_idx, sct, section_raw_contents)?;
+            match class {
+                header::Class::Bit32 => Ok(section::Contents::Contents32(
+                    section::Contents32::Group(group),
+                )),
+                _ => Ok(section::Contents::Contents64(section::Contents64::Group(
+                    group,
+                ))),
+            }
+        }
+        section::Type::Hash => match section::SysvHash::parse(data, section_raw_contents) {
+            Some(table) => match class {
+                header::Class::Bit32 => Ok(section::Contents::Contents32(
+                    section::Contents32::SysvHash(table),
+                )),
+                _ => Ok(section::Contents::Contents64(
+                    section::Contents64::SysvHash(table),
+                )),
+            },
+            None => Ok(raw_contents(class, section_raw_contents)),
+        },
+        section::Type::GNUHash => {
+            match section::GnuHash::parse(data, class, section_raw_contents) {
+                Some(table) => match class {
+                    header::Class::Bit32 => Ok(section::Contents::Contents32(
+                        section::Contents32::GnuHash(table),
+                    )),
+                    _ => Ok(section::Contents::Contents64(section::Contents64::GnuHash(
+                        table,
+                    ))),
+                },
+                None => Ok(raw_contents(class, section_raw_contents)),
+            }
+        }
+        section::Type::GNUVerSym => {
+            parse_version_symbols(class, data, sct_idx, sct, section_raw_contents)
+        }
+        section::Type::GNUVerDef => {
+            let defs = version::VersionDefinition::parse_table(data, section_raw_contents)
+                .ok_or(ReadELFError::InvalidVersionTable { index: sct_idx })?;
+            match class {
+                header::Class::Bit32 => Ok(section::Contents::Contents32(
+                    section::Contents32::VersionDefinitions(defs),
+                )),
+                _ => Ok(section::Contents::Contents64(
+                    section::Contents64::VersionDefinitions(defs),
+                )),
+            }
+        }
+        section::Type::GNUVerNeed => {
+            let reqs = version::VersionRequirement::parse_table(data, section_raw_contents)
+                .ok_or(ReadELFError::InvalidVersionTable { index: sct_idx })?;
+            match class {
+                header::Class::Bit32 => Ok(section::Contents::Contents32(
+                    section::Contents32::VersionRequirements(reqs),
+                )),
+                _ => Ok(section::Contents::Contents64(
+                    section::Contents64::VersionRequirements(reqs),
+                )),
+            }
+        }
+        section::Type::Note => {
+            let align = note::note_alignment(sct.addralign());
+            let table = parse_note_table(data, align, sct_idx, section_raw_contents)?;
+            match class {
+                header::Class::Bit32 => Ok(section::Contents::Contents32(
+                    section::Contents32::Notes(table),
+                )),
+                _ => Ok(section::Contents::Contents64(section::Contents64::Notes(
+                    table,
+                ))),
+            }
+        }
+        _ => Ok(raw_contents(class, section_raw_contents)),
+    }
+}
+
+fn raw_contents(class: header::Class, section_raw_contents: &[u8]) -> section::Contents {
+    match class {
+        header::Class::Bit32 => {
+            section::Contents::Contents32(section::Contents32::Raw(section_raw_contents.to_vec()))
+        }
+        _ => section::Contents::Contents64(section::Contents64::Raw(section_raw_contents.to_vec())),
+    }
+}
+
+fn parse_string_table(
+    class: header::Class,
+    sct_idx: usize,
+    section_raw_contents: &[u8],
+) -> Result<section::Contents, ReadELFError> {
     let mut strs: Vec<section::StrTabEntry> = Default::default();
     let mut name_idx = 0;
     loop {
@@ -151,9 +510,12 @@ fn parse_string_table(class: header::Class, section_raw_contents: &Vec<u8>) -> s
         let nul_range_end = section_raw_contents[name_idx..]
             .iter()
             .position(|&c| c == b'\0')
-            .unwrap_or(section_raw_contents.len());
+            .unwrap_or(section_raw_contents.len() - name_idx);
         let s = std::str::from_utf8(&section_raw_contents[name_idx..name_idx + nul_range_end])
-            .unwrap()
+            .map_err(|_| ReadELFError::InvalidString {
+                index: sct_idx,
+                offset: name_idx,
+            })?
             .to_string();
 
         let idx = name_idx;
@@ -162,105 +524,276 @@ fn parse_string_table(class: header::Class, section_raw_contents: &Vec<u8>) -> s
     }
 
     match class {
-        header::Class::Bit32 => section::Contents::Contents32(section::Contents32::StrTab(strs)),
-        header::Class::Bit64 => section::Contents::Contents64(section::Contents64::StrTab(strs)),
-        _ => todo!(),
+        header::Class::Bit32 => Ok(section::Contents::Contents32(section::Contents32::StrTab(
+            strs,
+        ))),
+        _ => Ok(section::Contents::Contents64(section::Contents64::StrTab(
+            strs,
+        ))),
     }
 }
 
 fn parse_rela_symbol_table(
     class: header::Class,
+    data: header::Data,
+    sct_idx: usize,
     sct: &section::Section,
-    raw_symtab: &Vec<u8>,
-) -> section::Contents {
-    let entry_size = sct.entry_size();
-    let entry_number = sct.size() / entry_size;
+    raw_symtab: &[u8],
+) -> Result<section::Contents, ReadELFError> {
     match class {
-        header::Class::Bit32 => section::Contents::Contents32(section::Contents32::RelaSymbols(
-            parse_table(entry_size, entry_number, raw_symtab),
+        header::Class::Bit32 => Ok(section::Contents::Contents32(
+            section::Contents32::RelaSymbols(parse_table(
+                data,
+                sct_idx,
+                sct,
+                relocation::Rela32::SIZE as usize,
+                raw_symtab,
+            )?),
         )),
-        header::Class::Bit64 => section::Contents::Contents64(section::Contents64::RelaSymbols(
-            parse_table(entry_size, entry_number, raw_symtab),
+        _ => Ok(section::Contents::Contents64(
+            section::Contents64::RelaSymbols(parse_table(
+                data,
+                sct_idx,
+                sct,
+                relocation::Rela64::SIZE as usize,
+                raw_symtab,
+            )?),
+        )),
+    }
+}
+
+fn parse_rel_symbol_table(
+    class: header::Class,
+    data: header::Data,
+    sct_idx: usize,
+    sct: &section::Section,
+    raw_symtab: &[u8],
+) -> Result<section::Contents, ReadELFError> {
+    match class {
+        header::Class::Bit32 => Ok(section::Contents::Contents32(
+            section::Contents32::RelSymbols(parse_table(
+                data,
+                sct_idx,
+                sct,
+                relocation::Rel32::SIZE as usize,
+                raw_symtab,
+            )?),
+        )),
+        _ => Ok(section::Contents::Contents64(
+            section::Contents64::RelSymbols(parse_table(
+                data,
+                sct_idx,
+                sct,
+                relocation::Rel64::SIZE as usize,
+                raw_symtab,
+            )?),
         )),
-        _ => todo!(),
     }
 }
 
 fn parse_dynamic_information(
     class: header::Class,
+    data: header::Data,
+    sct_idx: usize,
     sct: &section::Section,
-    raw_symtab: &Vec<u8>,
-) -> section::Contents {
-    let entry_size = sct.entry_size();
-    let entry_number = sct.size() / entry_size;
+    raw_symtab: &[u8],
+) -> Result<section::Contents, ReadELFError> {
     match class {
-        header::Class::Bit32 => section::Contents::Contents32(section::Contents32::Dynamics(
-            parse_table(entry_size, entry_number, raw_symtab),
+        header::Class::Bit32 => Ok(section::Contents::Contents32(
+            section::Contents32::Dynamics(parse_table(
+                data,
+                sct_idx,
+                sct,
+                dynamic::Dyn32::SIZE,
+                raw_symtab,
+            )?),
         )),
-        header::Class::Bit64 => section::Contents::Contents64(section::Contents64::Dynamics(
-            parse_table(entry_size, entry_number, raw_symtab),
+        _ => Ok(section::Contents::Contents64(
+            section::Contents64::Dynamics(parse_table(
+                data,
+                sct_idx,
+                sct,
+                dynamic::Dyn64::SIZE,
+                raw_symtab,
+            )?),
         )),
-        _ => todo!(),
     }
 }
 
-fn parse_symbol_table(
+/// Elf_Nhdrと，後続するname/descを順に読み出す
+/// `index` はエラー報告用のセクション/セグメント番号
+pub(crate) fn parse_note_table(
+    data: header::Data,
+    align: usize,
+    index: usize,
+    buf: &[u8],
+) -> Result<note::NoteTable, ReadELFError> {
+    let mut table = note::NoteTable::new(align);
+    let mut offset = 0;
+    while offset < buf.len() {
+        let invalid_note = || ReadELFError::InvalidNote { index, offset };
+
+        let nhdr: note::Nhdr = checked_slice(buf, offset, note::Nhdr::SIZE)
+            .and_then(|b| data.deserialize(b).ok())
+            .ok_or_else(invalid_note)?;
+        let name_start = offset + note::Nhdr::SIZE;
+        // nameとdescの開始位置はそれぞれalignに整列されている
+        let desc_start = note::align_up(name_start + nhdr.n_namesz as usize, align);
+
+        let name =
+            checked_slice(buf, name_start, nhdr.n_namesz as usize).ok_or_else(invalid_note)?;
+        let desc =
+            checked_slice(buf, desc_start, nhdr.n_descsz as usize).ok_or_else(invalid_note)?;
+
+        // 名前は終端のnull-byteやパディングを含むことがあるので，書き戻せるように保持する
+        table
+            .notes
+            .push(note::Note::from_raw_name(name, nhdr.n_type, desc.to_vec()));
+
+        offset = note::align_up(desc_start + nhdr.n_descsz as usize, align);
+    }
+
+    Ok(table)
+}
+
+/// 先頭のエントリはフラグ，以降はメンバのセクション番号
+fn parse_group(
+    data: header::Data,
+    sct_idx: usize,
+    sct: &section::Section,
+    raw_group: &[u8],
+) -> Result<section::Group, ReadELFError> {
+    let mut entries: Vec<Elf64Word> =
+        parse_table(data, sct_idx, sct, section::Group::ENTRY_SIZE, raw_group)?;
+    if entries.is_empty() {
+        return Err(ReadELFError::InvalidEntrySize {
+            index: sct_idx,
+            offset: sct.offset(),
+            entsize: sct.entry_size(),
+        });
+    }
+
+    let flags = entries.remove(0);
+    Ok(section::Group::new(flags, entries))
+}
+
+fn parse_version_symbols(
     class: header::Class,
+    data: header::Data,
+    sct_idx: usize,
     sct: &section::Section,
-    raw_symtab: &Vec<u8>,
-) -> section::Contents {
-    let entry_size = sct.entry_size();
-    let entry_number = sct.size() / entry_size;
+    raw_versyms: &[u8],
+) -> Result<section::Contents, ReadELFError> {
+    // 各エントリはElf32Versym/Elf64Versym(どちらも2バイト)
+    match class {
+        header::Class::Bit32 => Ok(section::Contents::Contents32(
+            section::Contents32::VersionSymbols(parse_table(data, sct_idx, sct, 2, raw_versyms)?),
+        )),
+        _ => Ok(section::Contents::Contents64(
+            section::Contents64::VersionSymbols(parse_table(data, sct_idx, sct, 2, raw_versyms)?),
+        )),
+    }
+}
+
+fn parse_symtab_shndx(
+    class: header::Class,
+    data: header::Data,
+    sct_idx: usize,
+    sct: &section::Section,
+    raw_indices: &[u8],
+) -> Result<section::Contents, ReadELFError> {
+    // 各エントリはElf32Word/Elf64Word(どちらも4バイト)
     match class {
-        header::Class::Bit32 => section::Contents::Contents32(section::Contents32::Symbols(
-            parse_table(entry_size, entry_number, raw_symtab),
+        header::Class::Bit32 => Ok(section::Contents::Contents32(
+            section::Contents32::SymTabShNdx(parse_table(data, sct_idx, sct, 4, raw_indices)?),
         )),
-        header::Class::Bit64 => section::Contents::Contents64(section::Contents64::Symbols(
-            parse_table(entry_size, entry_number, raw_symtab),
+        _ => Ok(section::Contents::Contents64(
+            section::Contents64::SymTabShNdx(parse_table(data, sct_idx, sct, 4, raw_indices)?),
         )),
-        _ => todo!(),
     }
 }
 
+fn parse_symbol_table(
+    class: header::Class,
+    data: header::Data,
+    sct_idx: usize,
+    sct: &section::Section,
+    raw_symtab: &[u8],
+) -> Result<section::Contents, ReadELFError> {
+    match class {
+        header::Class::Bit32 => Ok(section::Contents::Contents32(section::Contents32::Symbols(
+            parse_table(data, sct_idx, sct, symbol::Symbol32::SIZE, raw_symtab)?,
+        ))),
+        _ => Ok(section::Contents::Contents64(section::Contents64::Symbols(
+            parse_table(data, sct_idx, sct, symbol::Symbol64::SIZE, raw_symtab)?,
+        ))),
+    }
+}
+
+/// sh_entsize毎にエントリをデコードする
+/// sh_entsizeが0だったり，構造体のサイズより小さい場合はエラーとする
 fn parse_table<'a, T: Deserialize<'a>>(
-    entry_size: usize,
-    entry_number: usize,
+    data: header::Data,
+    sct_idx: usize,
+    sct: &section::Section,
+    min_entry_size: usize,
     buf: &'a [u8],
-) -> Vec<T> {
-    let mut table = Vec::new();
+) -> Result<Vec<T>, ReadELFError> {
+    let entry_size = sct.entry_size();
+    let invalid_entry_size = || ReadELFError::InvalidEntrySize {
+        index: sct_idx,
+        offset: sct.offset(),
+        entsize: entry_size,
+    };
+    if entry_size == 0 || entry_size < min_entry_size {
+        return Err(invalid_entry_size());
+    }
+
+    let entry_number = buf.len() / entry_size;
+    let mut table = Vec::with_capacity(entry_number);
     for idx in 0..entry_number {
         let start = idx * entry_size;
         let end = (idx + 1) * entry_size;
-        let entry = bincode::deserialize(&buf[start..end]).unwrap();
+        let entry = data
+            .deserialize(&buf[start..end])
+            .map_err(|_| invalid_entry_size())?;
         table.push(entry);
     }
-    table
+    Ok(table)
 }
 
 /// プログラムヘッダテーブルのパース
 fn read_pht(
     class: header::Class,
+    data: header::Data,
     phnum: usize,
     pht_start: usize,
     buf: &[u8],
-) -> Result<Vec<segment::Segment>, Box<dyn std::error::Error>> {
-    let mut segments = Vec::with_capacity(10);
+) -> Result<Vec<segment::Segment>, ReadELFError> {
     let phdr_size = match class {
         header::Class::Bit32 => segment::Phdr32::SIZE,
-        header::Class::Bit64 => segment::Phdr64::SIZE,
-        _ => todo!(),
+        _ => segment::Phdr64::SIZE,
+    };
+    let pht_out_of_bounds = || ReadELFError::ProgramHeaderTableOutOfBounds {
+        offset: pht_start,
+        number: phnum,
     };
 
+    let pht_size = phdr_size.checked_mul(phnum).ok_or_else(pht_out_of_bounds)?;
+    let pht = checked_slice(buf, pht_start, pht_size).ok_or_else(pht_out_of_bounds)?;
+
+    let mut segments = Vec::with_capacity(phnum);
     for seg_idx in 0..phnum {
-        let header_start = pht_start as usize + phdr_size * seg_idx;
+        let header_start = phdr_size * seg_idx;
         let phdr = match class {
-            header::Class::Bit32 => {
-                segment::Phdr::Phdr32(segment::Phdr32::deserialize(buf, header_start)?)
-            }
-            header::Class::Bit64 => {
-                segment::Phdr::Phdr64(segment::Phdr64::deserialize(buf, header_start)?)
-            }
-            _ => todo!(),
+            header::Class::Bit32 => segment::Phdr::Phdr32(
+                data.deserialize(&pht[header_start..])
+                    .map_err(|_| pht_out_of_bounds())?,
+            ),
+            _ => segment::Phdr::Phdr64(
+                data.deserialize(&pht[header_start..])
+                    .map_err(|_| pht_out_of_bounds())?,
+            ),
         };
 
         let seg = segment::Segment { phdr };
@@ -270,10 +803,121 @@ fn read_pht(
     Ok(segments)
 }
 
+/// 文字列テーブルから `name_idx` が指す文字列を探す
+/// 文字列の途中を指すインデックス(末尾の共有)にも対応する
+fn find_string(
+    strtab: &[section::StrTabEntry],
+    strtab_idx: usize,
+    name_idx: usize,
+) -> Result<String, ReadELFError> {
+    let s = strtab
+        .iter()
+        .find(|s| s.idx <= name_idx && name_idx <= s.idx + s.v.len())
+        .ok_or(ReadELFError::InvalidStringIndex {
+            index: strtab_idx,
+            name_idx,
+        })?;
+
+    // 文字境界でない位置を指していた場合も不正なインデックスとして扱う
+    match s.v.get(name_idx - s.idx..) {
+        Some(name) => Ok(name.to_string()),
+        None => Err(ReadELFError::InvalidStringIndex {
+            index: strtab_idx,
+            name_idx,
+        }),
+    }
+}
+
+/// 文字列テーブルのバイト列から，`name_idx` が指すNUL終端文字列を借用する
+pub(crate) fn string_in_table(
+    table: &[u8],
+    strtab_idx: usize,
+    name_idx: usize,
+) -> Result<&str, ReadELFError> {
+    let bytes = table
+        .get(name_idx..)
+        .ok_or(ReadELFError::InvalidStringIndex {
+            index: strtab_idx,
+            name_idx,
+        })?;
+    let len = bytes
+        .iter()
+        .position(|&c| c == b'\0')
+        .ok_or(ReadELFError::InvalidStringIndex {
+            index: strtab_idx,
+            name_idx,
+        })?;
+
+    std::str::from_utf8(&bytes[..len]).map_err(|_| ReadELFError::InvalidString {
+        index: strtab_idx,
+        offset: name_idx,
+    })
+}
+
+/// `sections[idx]` を文字列テーブルとして取り出す
+fn string_table_at(
+    sections: &[section::Section],
+    idx: usize,
+) -> Result<section::Contents, ReadELFError> {
+    let sct = sections.get(idx).ok_or(ReadELFError::InvalidSectionIndex {
+        index: idx,
+        number: sections.len(),
+    })?;
+
+    string_table_contents(sct, idx)
+}
+
+/// `sct` の中身を名前の解決に使う文字列テーブルとして取り出す
+/// UTF-8でない文字列を含むため，バイト列のまま保持されたSHT_STRTABも受け付ける
+pub(crate) fn string_table_contents(
+    sct: &section::Section,
+    idx: usize,
+) -> Result<section::Contents, ReadELFError> {
+    match &sct.contents {
+        section::Contents::Contents32(section::Contents32::StrTab(_))
+        | section::Contents::Contents64(section::Contents64::StrTab(_)) => Ok(sct.contents.clone()),
+        section::Contents::Contents32(section::Contents32::Raw(_))
+        | section::Contents::Contents64(section::Contents64::Raw(_))
+            if sct.ty() == section::Type::StrTab =>
+        {
+            Ok(sct.contents.clone())
+        }
+        _ => Err(ReadELFError::NotStringTable { index: idx }),
+    }
+}
+
+/// `string_table_contents()` で取り出した文字列テーブルから名前を探す
+/// バイト列のまま保持されたテーブルでは，UTF-8でない名前を指していればエラーとなる
+fn find_name(
+    strtab: &section::Contents,
+    strtab_idx: usize,
+    name_idx: usize,
+) -> Result<String, ReadELFError> {
+    match strtab {
+        section::Contents::Contents32(section::Contents32::StrTab(strs))
+        | section::Contents::Contents64(section::Contents64::StrTab(strs)) => {
+            find_string(strs, strtab_idx, name_idx)
+        }
+        section::Contents::Contents32(section::Contents32::Raw(bytes))
+        | section::Contents::Contents64(section::Contents64::Raw(bytes)) => {
+            string_in_table(bytes, strtab_idx, name_idx).map(|s| s.to_string())
+        }
+        _ => Err(ReadELFError::NotStringTable { index: strtab_idx }),
+    }
+}
+
 /// セクション名を.shstrtabから探して，Section構造体に書き込む
 /// このようにしているのは，SHTのパースがすべて終わってからでないとshstrtabを使用できない為
-fn naming_sections_from_shstrtab(shstrndx: usize, sections: &mut Vec<section::Section>) {
-    let shstrtab = sections[shstrndx].contents.as_strtab();
+fn naming_sections_from_shstrtab(
+    shstrndx: usize,
+    sections: &mut [section::Section],
+) -> Result<(), ReadELFError> {
+    // セクション名テーブルが存在しない
+    if sections.is_empty() || shstrndx == section::SHN_UNDEF as usize {
+        return Ok(());
+    }
+
+    let shstrtab = string_table_at(sections, shstrndx)?;
 
     for sct in sections.iter_mut() {
         let name_idx = sct.name_idx();
@@ -281,77 +925,86 @@ fn naming_sections_from_shstrtab(shstrndx: usize, sections: &mut Vec<section::Se
             continue;
         }
 
-        let s = shstrtab
-            .iter()
-            .find(|&s| s.idx <= name_idx && name_idx <= s.idx + s.v.len())
-            .unwrap();
-
-        let (_, name) = s.v.split_at(name_idx - s.idx);
-        sct.name = name.to_string();
+        sct.name = find_name(&shstrtab, shstrndx, name_idx)?;
     }
+
+    Ok(())
 }
 
 /// シンボル名をsh_linkが指す文字列テーブルから探して割り当てる
 /// このようにしているのは，SHTのパースがすべて終わってからでないとshstrtabを使用できない為
-fn naming_symbols(sections: &mut Vec<section::Section>) {
+fn naming_symbols(sections: &mut [section::Section]) -> Result<(), ReadELFError> {
     let section_number = sections.len();
     for sct_idx in 0..section_number {
         let sct = &sections[sct_idx];
-        if sct.ty() != section::Type::SymTab && sct.ty() != section::Type::DynSym {
+        // NoBitsなどで中身がパースされていない場合は無視する
+        if !sct.contents.has_linked_names() {
             continue;
         }
 
-        let strtab = sections[sct.link()].contents.as_strtab();
+        let strtab_idx = sct.link();
+        let strtab = string_table_at(sections, strtab_idx)?;
 
-        match &mut sections[sct_idx].contents {
-            section::Contents::Contents32(c) => {
-                if let section::Contents32::Symbols(ref mut symbols) = c {
-                    for sym in symbols.iter_mut() {
-                        let name_idx = sym.st_name as usize;
-                        if name_idx == 0 {
-                            continue;
-                        }
+        assign_linked_names(&mut sections[sct_idx].contents, &strtab, strtab_idx)?;
+    }
 
-                        let s = strtab
-                            .iter()
-                            .find(|s| s.idx <= name_idx && name_idx <= s.idx + s.v.len())
-                            .unwrap();
-                        let (_, name) = s.v.split_at(name_idx - s.idx);
+    Ok(())
+}
 
-                        sym.symbol_name = name.to_string();
-                    }
-                }
-            }
+/// シンボルやバージョン情報に，リンクされた文字列テーブル `strtab` から名前を割り当てる
+pub(crate) fn assign_linked_names(
+    contents: &mut section::Contents,
+    strtab: &section::Contents,
+    strtab_idx: usize,
+) -> Result<(), ReadELFError> {
+    let name_of = |name_idx: usize| -> Result<String, ReadELFError> {
+        if name_idx == 0 {
+            return Ok(String::new());
+        }
+        find_name(strtab, strtab_idx, name_idx)
+    };
+    let naming_entry = |ent: &mut section::StrTabEntry| -> Result<(), ReadELFError> {
+        ent.v = name_of(ent.idx)?;
+        Ok(())
+    };
 
-            section::Contents::Contents64(c) => {
-                if let section::Contents64::Symbols(ref mut symbols) = c {
-                    for sym in symbols.iter_mut() {
-                        let name_idx = sym.st_name as usize;
-                        if name_idx == 0 {
-                            continue;
-                        }
-
-                        let s = strtab
-                            .iter()
-                            .find(|s| s.idx <= name_idx && name_idx <= s.idx + s.v.len())
-                            .unwrap();
-                        let (_, name) = s.v.split_at(name_idx - s.idx);
-
-                        sym.symbol_name = name.to_string();
-                    }
+    match contents {
+        section::Contents::Contents32(section::Contents32::Symbols(ref mut symbols)) => {
+            for sym in symbols.iter_mut() {
+                sym.symbol_name = name_of(sym.st_name as usize)?;
+            }
+        }
+        section::Contents::Contents64(section::Contents64::Symbols(ref mut symbols)) => {
+            for sym in symbols.iter_mut() {
+                sym.symbol_name = name_of(sym.st_name as usize)?;
+            }
+        }
+        section::Contents::Contents32(section::Contents32::VersionDefinitions(ref mut defs))
+        | section::Contents::Contents64(section::Contents64::VersionDefinitions(ref mut defs)) => {
+            for def in defs.iter_mut() {
+                def.names.iter_mut().try_for_each(naming_entry)?;
+            }
+        }
+        section::Contents::Contents32(section::Contents32::VersionRequirements(ref mut reqs))
+        | section::Contents::Contents64(section::Contents64::VersionRequirements(ref mut reqs)) => {
+            for req in reqs.iter_mut() {
+                naming_entry(&mut req.file)?;
+                for entry in req.entries.iter_mut() {
+                    naming_entry(&mut entry.name)?;
                 }
             }
         }
+        _ => {}
     }
-}
 
-fn check_elf_magic(file_path: &str, buf: &[u8]) -> Result<(), Box<dyn std::error::Error>> {
-    assert_eq!(buf.len(), 4);
+    Ok(())
+}
 
-    if buf[0] != 0x7f || buf[1] != 0x45 || buf[2] != 0x4c || buf[3] != 0x46 {
-        return Err(Box::new(ReadELFError::NotELF {
+fn check_elf_magic(file_path: &str, buf: &[u8]) -> Result<(), ReadELFError> {
+    if buf.len() < 4 || buf[0] != 0x7f || buf[1] != 0x45 || buf[2] != 0x4c || buf[3] != 0x46 {
+        return Err(ReadELFError::NotELF {
             file_path: file_path.to_string(),
-        }));
+        });
     }
 
     Ok(())
@@ -359,18 +1012,35 @@ fn check_elf_magic(file_path: &str, buf: &[u8]) -> Result<(), Box<dyn std::error
 
 fn parse_elf_header(
     class: header::Class,
+    data: header::Data,
     buf: &[u8],
-) -> Result<header::Ehdr, Box<dyn std::error::Error>> {
+) -> Result<header::Ehdr, ReadELFError> {
+    let header_size = match class {
+        header::Class::Bit32 => header::Ehdr32::SIZE as usize,
+        _ => header::Ehdr64::SIZE as usize,
+    };
+    let truncated = || ReadELFError::TruncatedHeader {
+        size: header_size,
+        len: buf.len(),
+    };
+    if buf.len() < header_size {
+        return Err(truncated());
+    }
+
     match class {
-        header::Class::Bit32 => Ok(header::Ehdr::Ehdr32(bincode::deserialize(buf)?)),
-        header::Class::Bit64 => Ok(header::Ehdr::Ehdr64(bincode::deserialize(buf)?)),
-        _ => todo!(),
+        header::Class::Bit32 => Ok(header::Ehdr::Ehdr32(
+            data.deserialize(buf).map_err(|_| truncated())?,
+        )),
+        _ => Ok(header::Ehdr::Ehdr64(
+            data.deserialize(buf).map_err(|_| truncated())?,
+        )),
     }
 }
 
 #[cfg(test)]
 mod parse_tests {
     use crate::section::Contents64;
+    use std::collections::HashSet;
 
     use super::*;
 
@@ -390,7 +1060,8 @@ mod parse_tests {
             0x57, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x40, 0x00, 0x38, 0x00,
             0x0c, 0x00, 0x40, 0x00, 0x2c, 0x00, 0x2b, 0x00,
         ];
-        let hdr_result = parse_elf_header(header::Class::Bit64, &header_bytes).unwrap();
+        let hdr_result =
+            parse_elf_header(header::Class::Bit64, header::Data::LSB2, &header_bytes).unwrap();
         assert!(matches!(hdr_result, header::Ehdr::Ehdr64(_)));
 
         if let header::Ehdr::Ehdr64(ehdr) = hdr_result {
@@ -409,7 +1080,8 @@ mod parse_tests {
             0x20, 0x00, 0x0c, 0x00, 0x28, 0x00, 0x1f, 0x00, 0x1e, 0x00, 0x06, 0x00, 0x34, 0x00,
             0x00, 0x00, 0x40, 0x00, 0x2c, 0x00, 0x2b, 0x00,
         ];
-        let hdr_result = parse_elf_header(header::Class::Bit32, &header_bytes).unwrap();
+        let hdr_result =
+            parse_elf_header(header::Class::Bit32, header::Data::LSB2, &header_bytes).unwrap();
         assert!(matches!(hdr_result, header::Ehdr::Ehdr32(_)));
 
         if let header::Ehdr::Ehdr32(ehdr) = hdr_result {
@@ -450,14 +1122,47 @@ mod parse_tests {
             assert_eq!(f.sections[2].header.get_type(), section::Type::Note);
             assert_eq!(f.sections[2].header.sh_addr, 0x338);
             assert!(
-                matches!(&f.sections[2].contents, Contents64::Raw(x) if x.len() == f.sections[2].header.sh_size as usize )
+                matches!(&f.sections[2].contents, Contents64::Notes(x) if x.size() == f.sections[2].header.sh_size as usize )
             );
+            if let Contents64::Notes(table) = &f.sections[2].contents {
+                assert_eq!(8, table.align);
+                let property = table
+                    .find(note::ELF_NOTE_GNU, note::NT_GNU_PROPERTY_TYPE_0)
+                    .unwrap();
+                assert_eq!(
+                    Some(note::Descriptor::GNUProperties(vec![note::GNUProperty {
+                        pr_type: note::GNU_PROPERTY_X86_FEATURE_1_AND,
+                        pr_data: vec![0x03, 0x00, 0x00, 0x00],
+                    }])),
+                    property.descriptor(header::Data::LSB2, table.align)
+                );
+            }
+            if let Contents64::Notes(table) = &f.sections[4].contents {
+                assert_eq!(
+                    Some(note::Descriptor::GNUABITag {
+                        os: note::ELF_NOTE_OS_LINUX,
+                        major: 3,
+                        minor: 2,
+                        patch: 0,
+                    }),
+                    table.notes[0].descriptor(header::Data::LSB2, table.align)
+                );
+                assert_eq!(
+                    f.sections[4].header.sh_size as usize,
+                    f.sections[4].to_le_bytes().len()
+                );
+            }
 
             assert_eq!(f.sections[10].header.get_type(), section::Type::Rela);
             assert!(matches!(
                 f.sections[10].contents,
                 Contents64::RelaSymbols(_)
             ));
+            if let Contents64::RelaSymbols(relas) = &f.sections[10].contents {
+                let machine = f.ehdr.get_machine();
+                assert_eq!("R_X86_64_RELATIVE", relas[0].kind(&machine).name());
+                assert_eq!("R_X86_64_GLOB_DAT", relas[3].kind(&machine).name());
+            }
             assert_eq!(f.sections[26].header.get_type(), section::Type::SymTab);
             assert!(matches!(
                 &f.sections[26].contents,
@@ -492,6 +1197,642 @@ mod parse_tests {
         }
     }
 
+    #[test]
+    fn read_elf64_from_bytes_test() {
+        let buf = std::fs::read("src/parser/testdata/sample").unwrap();
+
+        let from_bytes = parse_elf64_bytes(&buf).unwrap();
+        let from_reader =
+            expect_64bit(parse_elf_from_reader(std::io::Cursor::new(&buf)).unwrap()).unwrap();
+        let from_path = parse_elf64("src/parser/testdata/sample").unwrap();
+
+        assert!(from_bytes == from_path);
+        assert!(from_reader == from_path);
+
+        assert!(parse_elf_bytes(&buf[..3]).is_err());
+        assert!(parse_elf_bytes(&[0x00; 64]).is_err());
+    }
+
+    #[test]
+    fn read_elf64_big_endian_test() {
+        let mut f = parse_elf64("src/parser/testdata/sample").unwrap();
+        f.ehdr.set_data(header::Data::MSB2);
+
+        // 元ファイルのレイアウトを保ったまま，各構造体をビッグエンディアンで書き戻す
+        let mut buf = std::fs::read("src/parser/testdata/sample").unwrap();
+        let mut overwrite = |start: usize, bytes: Vec<u8>| {
+            buf[start..start + bytes.len()].copy_from_slice(&bytes);
+        };
+        overwrite(0, f.ehdr.to_bytes(header::Data::MSB2));
+        for (i, seg) in f.segments.iter().enumerate() {
+            let start = f.ehdr.e_phoff as usize + segment::Phdr64::SIZE * i;
+            overwrite(start, seg.header.to_bytes(header::Data::MSB2));
+        }
+        for (i, sct) in f.sections.iter().enumerate() {
+            let start = f.ehdr.e_shoff as usize + section::Shdr64::SIZE * i;
+            overwrite(start, sct.header.to_bytes(header::Data::MSB2));
+            if sct.header.get_type() != section::Type::NoBits {
+                overwrite(
+                    sct.header.sh_offset as usize,
+                    sct.to_bytes(header::Data::MSB2),
+                );
+            }
+        }
+
+        let be = parse_elf64_bytes(&buf).unwrap();
+        assert!(be == f);
+
+        // to_be_bytes()はEI_DATAもELFDATA2MSBにする
+        let le = parse_elf64("src/parser/testdata/sample").unwrap();
+        let be_bytes = le.to_be_bytes();
+        assert_eq!(header::Data::MSB2.to_identifier(), be_bytes[5]);
+        assert!(parse_elf64_bytes(&be_bytes).unwrap() == f);
+        assert_eq!(header::Data::LSB2.to_identifier(), f.to_le_bytes()[5]);
+        assert!(matches!(
+            &be.sections[26].contents,
+            Contents64::Symbols(x) if x[45].symbol_name == "_ITM_deregisterTMCloneTable"
+        ));
+    }
+
+    #[test]
+    fn read_elf64_extended_numbers_test() {
+        let sample = std::fs::read("src/parser/testdata/sample").unwrap();
+        let f = parse_elf64_bytes(&sample).unwrap();
+
+        // e_shnum/e_shstrndx/e_phnumの実際の値をNULLセクションに移す
+        let mut buf = sample.clone();
+        let mut overwrite = |start: usize, bytes: &[u8]| {
+            buf[start..start + bytes.len()].copy_from_slice(bytes);
+        };
+        overwrite(0x38, &segment::PN_XNUM.to_le_bytes());
+        overwrite(0x3c, &0u16.to_le_bytes());
+        overwrite(0x3e, &section::SHN_XINDEX.to_le_bytes());
+        let mut null_shdr = f.sections[0].header;
+        null_shdr.sh_size = 29;
+        null_shdr.sh_link = 28;
+        null_shdr.sh_info = 13;
+        overwrite(f.ehdr.e_shoff as usize, &null_shdr.to_le_bytes());
+
+        let extended = parse_elf64_bytes(&buf).unwrap();
+        assert!(f.ehdr.pht_exists());
+        assert!(extended.ehdr.pht_exists());
+        assert_eq!(f.sections.len(), extended.sections.len());
+        assert_eq!(f.segments.len(), extended.segments.len());
+        assert_eq!(28, extended.shstrndx());
+        assert_eq!(null_shdr, extended.sections[0].header);
+        assert!(f.sections[1..] == extended.sections[1..]);
+        assert!(f.segments == extended.segments);
+
+        let view = view::ElfView64::new(&buf).unwrap();
+        assert_eq!(29, view.section_number());
+        assert_eq!(13, view.segment_number());
+        assert_eq!(".shstrtab", view.section_name(28).unwrap());
+    }
+
+    #[test]
+    fn parse_symtab_shndx_test() {
+        let mut sct = section::Section::new(section::Shdr::Shdr64(section::Shdr64 {
+            sh_type: section::Type::SymTabShNdx.into(),
+            sh_entsize: 4,
+            ..Default::default()
+        }));
+        let raw = [0x00, 0x00, 0x00, 0x00, 0x00, 0xff, 0x00, 0x00];
+        sct.contents =
+            parse_section_contents(header::Class::Bit64, header::Data::LSB2, 1, &sct, &raw)
+                .unwrap();
+        assert!(sct.as_64bit().contents == Contents64::SymTabShNdx(vec![0, 0xff00]));
+    }
+
+    #[test]
+    fn parse_note_table_round_trip_test() {
+        let mut raw = Vec::new();
+        // Goのビルドノートはn_namesz = 4で"Go\0\0"となっている
+        raw.extend_from_slice(&[4, 0, 0, 0, 4, 0, 0, 0, 4, 0, 0, 0]);
+        raw.extend_from_slice(b"Go\0\0");
+        raw.extend_from_slice(b"abcd");
+        // UTF-8でない名前
+        raw.extend_from_slice(&[3, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0]);
+        raw.extend_from_slice(&[0xff, 0xfe, 0x00, 0x00]);
+        raw.extend_from_slice(&[4, 0, 0, 0, 4, 0, 0, 0, 3, 0, 0, 0]);
+        raw.extend_from_slice(b"GNU\0");
+        raw.extend_from_slice(&[0xde, 0xad, 0xbe, 0xef]);
+
+        let table = parse_note_table(header::Data::LSB2, 4, 1, &raw).unwrap();
+        assert_eq!(3, table.notes.len());
+        assert_eq!(raw, table.to_bytes(header::Data::LSB2));
+        assert_eq!(raw.len(), table.size());
+
+        assert_eq!("Go", table.notes[0].name);
+        assert_eq!(Some(b"Go\0\0".to_vec()), table.notes[0].raw_name);
+        assert_eq!("\u{fffd}\u{fffd}", table.notes[1].name);
+        // 正規の形式の名前はraw_nameを持たない
+        assert!(
+            table.notes[2] == note::Note::new(note::ELF_NOTE_GNU, 3, vec![0xde, 0xad, 0xbe, 0xef])
+        );
+
+        // 名前を変更するとraw_nameは使われない
+        let mut renamed = table.notes[0].clone();
+        renamed.name = "Gopher".to_string();
+        assert_eq!(7, renamed.header().n_namesz);
+    }
+
+    #[test]
+    fn read_elf64_comdat_group_test() {
+        // g++ -c で生成した，inline関数のCOMDATグループを持つオブジェクト
+        let f = parse_elf64("src/parser/testdata/comdat.o").unwrap();
+
+        assert_eq!(".group", f.sections[1].name);
+        assert!(matches!(
+            &f.sections[1].contents,
+            Contents64::Group(group) if group.is_comdat() && group.sections == vec![6]
+        ));
+        assert_eq!(Some("_Z1fi"), f.group_signature(1));
+        assert_eq!(Some(1), f.group_of(6));
+        assert_eq!(None, f.group_of(2));
+        assert!(f.sections[6]
+            .header
+            .get_flags()
+            .contains(&section::Flag::Group));
+        assert_eq!(
+            f.sections[1].header.sh_size as usize,
+            f.sections[1].to_le_bytes().len()
+        );
+    }
+
+    #[test]
+    fn lookup_dynamic_symbol_test() {
+        // --hash-style=both でリンクした共有ライブラリ
+        let mut f = parse_elf64("src/parser/testdata/libhash.so").unwrap();
+        assert!(matches!(f.sections[2].contents, Contents64::SysvHash(_)));
+        assert!(matches!(f.sections[3].contents, Contents64::GnuHash(_)));
+        for idx in [2, 3] {
+            assert_eq!(
+                f.sections[idx].header.sh_size as usize,
+                f.sections[idx].to_le_bytes().len()
+            );
+        }
+
+        let expected = [("mul", 0x1120), ("add", 0x1100), ("counter", 0x4008)];
+        let lookup_all = |f: &file::ELF64| {
+            for (name, value) in expected.iter() {
+                let sym = f.lookup_dynamic_symbol(name).unwrap();
+                assert_eq!(*value, sym.st_value);
+            }
+            // 未定義シンボルや存在しないシンボルは見つからない
+            assert!(f.lookup_dynamic_symbol("__cxa_finalize").is_none());
+            assert!(f.lookup_dynamic_symbol("hidden").is_none());
+        };
+
+        // .gnu.hash => .hash => 線形探索の順に使われる
+        lookup_all(&f);
+        f.sections[3].contents = Contents64::Raw(Vec::new());
+        lookup_all(&f);
+        f.sections[2].contents = Contents64::Raw(Vec::new());
+        lookup_all(&f);
+
+        let f = parse_elf32("src/parser/testdata/32bit").unwrap();
+        assert!(f.lookup_dynamic_symbol("printf").is_none());
+    }
+
+    #[test]
+    fn broken_hash_table_test() {
+        let raw = std::fs::read("src/parser/testdata/libhash.so").unwrap();
+        let f = parse_elf64_bytes(&raw).unwrap();
+        let hash_offset = f.sections[2].header.sh_offset as usize;
+        let gnu_hash_offset = f.sections[3].header.sh_offset as usize;
+
+        // nbucketが大きすぎる.hashと，bloom_shiftが64以上の.gnu.hash
+        let mut broken = raw.clone();
+        broken[hash_offset..hash_offset + 4].fill(0xff);
+        broken[gnu_hash_offset + 12..gnu_hash_offset + 16].copy_from_slice(&64u32.to_le_bytes());
+        let broken_f = parse_elf64_bytes(&broken).unwrap();
+        for idx in [2, 3] {
+            let sct = &broken_f.sections[idx];
+            assert!(matches!(sct.contents, Contents64::Raw(_)));
+            let start = sct.header.sh_offset as usize;
+            assert_eq!(
+                &broken[start..start + sct.header.sh_size as usize],
+                sct.to_le_bytes().as_slice()
+            );
+        }
+        // 線形探索で見つかる
+        assert_eq!(
+            0x1120,
+            broken_f.lookup_dynamic_symbol("mul").unwrap().st_value
+        );
+
+        if let Contents64::GnuHash(mut table) = f.sections[3].contents.clone() {
+            table.bloom_shift = 64;
+            assert_eq!(None, table.lookup(header::Class::Bit64, "mul", |_| true));
+        }
+    }
+
+    #[test]
+    fn address_translation_test() {
+        let raw = std::fs::read("src/parser/testdata/sample").unwrap();
+        let f = parse_elf64_bytes(&raw).unwrap();
+
+        assert_eq!(Some(0x1040), f.vaddr_to_offset(0x1040));
+        assert_eq!(Some(0x3000), f.vaddr_to_offset(0x4000));
+        assert_eq!(Some(0x4000), f.offset_to_vaddr(0x3000));
+        // .bssはファイル上に存在しない
+        assert_eq!(None, f.vaddr_to_offset(0x4010));
+        assert_eq!(Some(5), f.segment_containing_addr(0x4010));
+        assert_eq!(Some(24), f.section_containing_addr(0x4010));
+        assert_eq!(Some(14), f.section_containing_addr(0x1100));
+        assert_eq!(None, f.segment_containing_addr(0x4018));
+
+        // ファイル上にある部分は元のバイト列と一致する
+        for seg in f.segments.iter() {
+            let phdr = &seg.header;
+            if phdr.get_type() != segment::Type::Load {
+                continue;
+            }
+            let start = phdr.p_offset as usize;
+            assert_eq!(
+                &raw[start..start + phdr.p_filesz as usize],
+                f.read_memory(phdr.p_vaddr, phdr.p_filesz as usize)
+                    .unwrap()
+                    .as_slice()
+            );
+        }
+
+        // .dataの末尾から.bssにまたがる読み出し
+        let memory = f.read_memory(0x4008, 0x10).unwrap();
+        assert_eq!(&raw[0x3008..0x3010], &memory[..8]);
+        assert_eq!(vec![0; 8], memory[8..]);
+        assert!(f.read_memory(0x4010, 0x9).is_none());
+
+        // p_vaddr + p_memszが溢れてもパニックしない
+        let mut broken = f.clone();
+        broken.segments[5].header.p_memsz = u64::MAX;
+        assert!(broken.read_memory(0x4008, 0x10).is_none());
+
+        // セクションが無いと，ファイル上の内容は分からない
+        let mut no_sections = f.clone();
+        no_sections.sections.clear();
+        assert!(no_sections.read_memory(0x1040, 4).is_none());
+        // .bssの部分はファイル上に存在しないので0になる
+        assert_eq!(vec![0; 8], no_sections.read_memory(0x4010, 8).unwrap());
+
+        let raw = std::fs::read("src/parser/testdata/32bit").unwrap();
+        let f = parse_elf32_bytes(&raw).unwrap();
+        assert_eq!(Some(0x2ed8), f.vaddr_to_offset(0x3ed8));
+        assert_eq!(Some(0x3ed8), f.offset_to_vaddr(0x2ed8));
+        assert_eq!(&raw[0x1000..0x1324], f.read_memory(0x1000, 0x324).unwrap());
+        assert_eq!(vec![0; 4], f.read_memory(0x4008, 4).unwrap());
+    }
+
+    #[test]
+    fn section_segment_mapping_test() {
+        let names = |f: &file::ELF64, indices: &Vec<usize>| -> Vec<String> {
+            indices
+                .iter()
+                .map(|idx| f.sections[*idx].name.clone())
+                .collect()
+        };
+
+        // readelf -l の "Section to Segment mapping" と一致する
+        let f = parse_elf64("src/parser/testdata/sample").unwrap();
+        let mapping = f.segment_sections();
+        assert_eq!(13, mapping.len());
+        assert!(mapping[0].is_empty());
+        assert_eq!(vec![".interp"], names(&f, &mapping[1]));
+        assert_eq!(
+            vec![".init", ".plt", ".plt.got", ".text", ".fini"],
+            names(&f, &mapping[3])
+        );
+        assert_eq!(
+            vec![
+                ".init_array",
+                ".fini_array",
+                ".dynamic",
+                ".got",
+                ".data",
+                ".bss"
+            ],
+            names(&f, &mapping[5])
+        );
+        assert_eq!(vec![".dynamic"], names(&f, &mapping[6]));
+        assert_eq!(
+            vec![".note.gnu.build-id", ".note.ABI-tag"],
+            names(&f, &mapping[8])
+        );
+        assert!(mapping[11].is_empty());
+        assert_eq!(
+            vec![".init_array", ".fini_array", ".dynamic", ".got"],
+            names(&f, &mapping[12])
+        );
+
+        let inverse = f.section_segments();
+        assert_eq!(vec![3], inverse[14]);
+        let dynamic_idx = f.first_shidx_by(|sct| sct.name == ".dynamic").unwrap();
+        assert_eq!(vec![5, 6, 12], inverse[dynamic_idx]);
+        assert!(inverse[0].is_empty());
+
+        // .tbssはPT_TLSにのみ含まれる
+        let f = parse_elf64("src/parser/testdata/libtls.so").unwrap();
+        let mapping = f.segment_sections();
+        assert_eq!(
+            vec![
+                ".tdata",
+                ".init_array",
+                ".fini_array",
+                ".dynamic",
+                ".got",
+                ".got.plt",
+                ".data",
+                ".bss"
+            ],
+            names(&f, &mapping[3])
+        );
+        assert_eq!(vec![".tdata", ".tbss"], names(&f, &mapping[6]));
+        assert_eq!(
+            vec![".tdata", ".init_array", ".fini_array", ".dynamic", ".got"],
+            names(&f, &mapping[9])
+        );
+        assert_eq!(vec![6], f.section_segments()[17]);
+
+        let f = parse_elf32("src/parser/testdata/32bit").unwrap();
+        let text_idx = f
+            .sections
+            .iter()
+            .position(|sct| sct.name == ".text")
+            .unwrap();
+        let text_segments = &f.section_segments()[text_idx];
+        assert_eq!(1, text_segments.len());
+        assert_eq!(
+            segment::Type::Load,
+            f.segments[text_segments[0]].header.get_type()
+        );
+    }
+
+    #[test]
+    fn dynamic_info_test() {
+        let mut f = parse_elf64("src/parser/testdata/libdyn.so").unwrap();
+        let info = f.dynamic_info().unwrap();
+        assert_eq!(vec!["libm.so.6", "libc.so.6"], info.needed);
+        assert_eq!(Some("libdyn.so.1".to_string()), info.soname);
+        assert_eq!(vec!["$ORIGIN/lib", "/opt/dyn"], info.runpath);
+        assert!(info.rpath.is_empty());
+        assert_eq!(
+            HashSet::from([dynamic::Flag::Origin, dynamic::Flag::BindNow]),
+            info.flags
+        );
+        assert_eq!(
+            HashSet::from([
+                dynamic::Flag::Now1,
+                dynamic::Flag::NoDelete1,
+                dynamic::Flag::Origin1
+            ]),
+            info.flags_1
+        );
+
+        // DT_STRTABが指すセクションが無ければsh_linkを辿る
+        let dynstr_idx = f.first_shidx_by(|sct| sct.name == ".dynstr").unwrap();
+        f.sections[dynstr_idx].header.sh_addr = 0;
+        if let Contents64::Dynamics(dynamics) = &mut f.sections[19].contents {
+            let runpath = dynamics
+                .iter_mut()
+                .find(|d| d.get_type() == dynamic::EntryType::RunPath)
+                .unwrap();
+            runpath.d_tag = 15;
+        }
+        let info = f.dynamic_info().unwrap();
+        assert_eq!(Some("libdyn.so.1".to_string()), info.soname);
+        assert_eq!(vec!["$ORIGIN/lib", "/opt/dyn"], info.rpath);
+        assert!(info.runpath.is_empty());
+
+        let f = parse_elf32("src/parser/testdata/32bit").unwrap();
+        let info = f.dynamic_info().unwrap();
+        assert_eq!(vec!["libc.so.6"], info.needed);
+        assert_eq!(None, info.soname);
+        assert!(info.flags.contains(&dynamic::Flag::BindNow));
+        assert!(info.flags_1.contains(&dynamic::Flag::PIE1));
+    }
+
+    #[test]
+    fn read_elf64_symbol_versions_test() {
+        let f = parse_elf64("src/parser/testdata/sample").unwrap();
+        assert!(matches!(
+            f.sections[8].contents,
+            Contents64::VersionSymbols(_)
+        ));
+        assert!(matches!(
+            f.sections[9].contents,
+            Contents64::VersionRequirements(_)
+        ));
+        for idx in [8, 9] {
+            assert_eq!(
+                f.sections[idx].header.sh_size as usize,
+                f.sections[idx].to_le_bytes().len()
+            );
+        }
+
+        assert!(f.requires_version("GLIBC_2.2.5"));
+        assert!(!f.requires_version("GLIBC_2.34"));
+        assert_eq!(
+            Some("__cxa_finalize@GLIBC_2.2.5".to_string()),
+            f.versioned_dynamic_symbol_name(5)
+        );
+        let ver = f.symbol_version(2).unwrap();
+        assert_eq!("GLIBC_2.2.5", ver.name);
+        assert_eq!(Some("libc.so.6".to_string()), ver.file);
+        // バージョンを持たないシンボル
+        assert!(f.symbol_version(1).is_none());
+
+        // --version-script でバージョンを定義した共有ライブラリ
+        let raw = std::fs::read("src/parser/testdata/libver.so").unwrap();
+        let f = parse_elf64_bytes(&raw).unwrap();
+        let defs = match &f.sections[6].contents {
+            Contents64::VersionDefinitions(defs) => defs,
+            _ => unreachable!(),
+        };
+        let names: Vec<Vec<&str>> = defs
+            .iter()
+            .map(|d| d.names.iter().map(|n| n.v.as_str()).collect())
+            .collect();
+        assert_eq!(
+            vec![vec!["libver.so"], vec!["VER_1"], vec!["VER_2", "VER_1"]],
+            names
+        );
+        assert_eq!(version::VER_FLG_BASE, defs[0].flags);
+        assert_eq!(version::version_hash("VER_2"), defs[2].hash);
+
+        let shdr = &f.sections[6].header;
+        assert_eq!(
+            &raw[shdr.sh_offset as usize..(shdr.sh_offset + shdr.sh_size) as usize],
+            f.sections[6].to_le_bytes().as_slice()
+        );
+
+        assert_eq!(
+            Some("api@@VER_2".to_string()),
+            f.versioned_dynamic_symbol_name(5)
+        );
+        assert_eq!(
+            Some("api@VER_1".to_string()),
+            f.versioned_dynamic_symbol_name(7)
+        );
+        assert!(f.symbol_version(7).unwrap().hidden);
+    }
+
+    #[test]
+    fn malformed_elf_test() {
+        let sample = std::fs::read("src/parser/testdata/sample").unwrap();
+        let f = parse_elf64_bytes(&sample).unwrap();
+        let shdr_start = |idx: usize| f.ehdr.e_shoff as usize + section::Shdr64::SIZE * idx;
+        let patched = |offset: usize, bytes: &[u8]| {
+            let mut buf = sample.clone();
+            buf[offset..offset + bytes.len()].copy_from_slice(bytes);
+            buf
+        };
+
+        assert!(matches!(
+            parse_elf_bytes(&sample[..0x30]),
+            Err(ReadELFError::TruncatedHeader { size: 0x40, .. })
+        ));
+        assert!(matches!(
+            parse_elf_bytes(&patched(header::Class::INDEX, &[0x05])),
+            Err(ReadELFError::UnsupportedClass { class: 5 })
+        ));
+        assert!(matches!(
+            parse_elf32_bytes(&sample),
+            Err(ReadELFError::ClassMismatch { expected: 32 })
+        ));
+
+        // e_shoff
+        assert!(matches!(
+            parse_elf_bytes(&patched(0x28, &u64::MAX.to_le_bytes())),
+            Err(ReadELFError::SectionHeaderTableOutOfBounds { .. })
+        ));
+        // e_phoff
+        assert!(matches!(
+            parse_elf_bytes(&patched(0x20, &0x4000u64.to_le_bytes())),
+            Err(ReadELFError::ProgramHeaderTableOutOfBounds { offset: 0x4000, .. })
+        ));
+        // .interp's sh_size
+        assert!(matches!(
+            parse_elf_bytes(&patched(shdr_start(1) + 0x20, &u64::MAX.to_le_bytes())),
+            Err(ReadELFError::SectionOutOfBounds {
+                index: 1,
+                offset: 0x318,
+                ..
+            })
+        ));
+        // .symtab's sh_entsize
+        assert!(matches!(
+            parse_elf_bytes(&patched(shdr_start(26) + 0x38, &0u64.to_le_bytes())),
+            Err(ReadELFError::InvalidEntrySize {
+                index: 26,
+                entsize: 0,
+                ..
+            })
+        ));
+        // .interp's sh_name
+        assert!(matches!(
+            parse_elf_bytes(&patched(shdr_start(1), &0xffffu32.to_le_bytes())),
+            Err(ReadELFError::InvalidStringIndex {
+                index: 28,
+                name_idx: 0xffff
+            })
+        ));
+        // .symtab's sh_link
+        assert!(matches!(
+            parse_elf_bytes(&patched(shdr_start(26) + 0x28, &100u32.to_le_bytes())),
+            Err(ReadELFError::InvalidSectionIndex {
+                index: 100,
+                number: 29
+            })
+        ));
+        // e_shstrndx => .interp
+        assert!(matches!(
+            parse_elf_bytes(&patched(0x3e, &1u16.to_le_bytes())),
+            Err(ReadELFError::NotStringTable { index: 1 })
+        ));
+    }
+
+    #[test]
+    fn malformed_section_contents_test() {
+        let sample = std::fs::read("src/parser/testdata/sample").unwrap();
+        let f = parse_elf64_bytes(&sample).unwrap();
+        let patched = |buf: &[u8], offset: usize, bytes: &[u8]| {
+            let mut buf = buf.to_vec();
+            buf[offset..offset + bytes.len()].copy_from_slice(bytes);
+            buf
+        };
+        let raw_of = |buf: &[u8], sct: &section::Section64| {
+            let start = sct.header.sh_offset as usize;
+            Contents64::Raw(buf[start..start + sct.header.sh_size as usize].to_vec())
+        };
+
+        // .note.gnu.build-idのn_nameszがセクションを超える
+        let buf = patched(&sample, 0x358, &0xffffu32.to_le_bytes());
+        let broken = parse_elf64_bytes(&buf).unwrap();
+        assert!(broken.sections[3].contents == raw_of(&buf, &broken.sections[3]));
+        assert!(f.sections[4..] == broken.sections[4..]);
+
+        // .gnu.version_rのvn_auxがセクションを超える
+        let buf = patched(&sample, 0x4e8 + 8, &0xffffu32.to_le_bytes());
+        let broken = parse_elf64_bytes(&buf).unwrap();
+        assert!(broken.sections[9].contents == raw_of(&buf, &broken.sections[9]));
+        assert_eq!(".gnu.version_r", broken.sections[9].name);
+
+        // シンボル名がUTF-8でない場合，名前を解決できないのでエラーとする
+        let name_idx = match &f.sections[26].contents {
+            Contents64::Symbols(syms) => syms[45].st_name as usize,
+            _ => unreachable!(),
+        };
+        assert!(matches!(
+            parse_elf_bytes(&patched(&sample, 0x3608 + name_idx, &[0xff])),
+            Err(ReadELFError::InvalidString { index: 27, offset }) if offset == name_idx
+        ));
+
+        // DT_SONAMEからしか参照されない文字列がUTF-8でなくても，.dynstr以外には影響しない
+        let libdyn = std::fs::read("src/parser/testdata/libdyn.so").unwrap();
+        let f = parse_elf64_bytes(&libdyn).unwrap();
+        let dynstr = f.first_section_by(|sct| sct.name == ".dynstr").unwrap();
+        let soname = f
+            .sections
+            .iter()
+            .find_map(|sct| match &sct.contents {
+                Contents64::Dynamics(dyns) => dyns
+                    .iter()
+                    .find(|d| d.get_type() == dynamic::EntryType::SOName),
+                _ => None,
+            })
+            .unwrap();
+        let buf = patched(
+            &libdyn,
+            (dynstr.header.sh_offset + soname.d_un) as usize,
+            &[0xff],
+        );
+        let broken = parse_elf64_bytes(&buf).unwrap();
+        assert!(broken.sections[4].contents == raw_of(&buf, &broken.sections[4]));
+        assert!(f.sections[3] == broken.sections[3]);
+        assert!(f.sections[6] == broken.sections[6]);
+    }
+
+    #[test]
+    fn corrupted_elf_does_not_panic_test() {
+        let sample = std::fs::read("src/parser/testdata/sample").unwrap();
+
+        for len in (0..sample.len()).step_by(13) {
+            let _ = parse_elf_bytes(&sample[..len]);
+        }
+
+        // ELFヘッダとSHTの各バイトを壊してもpanicしないこと
+        let f = parse_elf64_bytes(&sample).unwrap();
+        let sht_start = f.ehdr.e_shoff as usize;
+        let sht_end = sht_start + section::Shdr64::SIZE * f.sections.len();
+        for offset in (0..header::Ehdr64::SIZE as usize).chain(sht_start..sht_end) {
+            for byte in [0x00, 0x7f, 0xff] {
+                let mut buf = sample.clone();
+                buf[offset] = byte;
+                let _ = parse_elf_bytes(&buf);
+            }
+        }
+    }
+
     #[test]
     fn read_elf32_test() {
         let f_result = parse_elf("src/parser/testdata/32bit");
@@ -513,6 +1854,25 @@ mod parse_tests {
 
             assert_eq!(".note.ABI-tag", f.sections[4].name);
             assert_eq!(0x208, f.sections[4].header.sh_addr);
+
+            assert_eq!(".rel.dyn", f.sections[10].name);
+            match &f.sections[10].contents {
+                section::Contents32::RelSymbols(rels) => {
+                    assert_eq!(8, rels.len());
+                    assert_eq!(0x3fec, rels[4].get_offset());
+                    assert_eq!(1, rels[4].get_sym());
+                    assert_eq!(6, rels[4].get_type());
+                    assert_eq!(
+                        relocation::Kind::I386(relocation::I386Type::GlobDat),
+                        rels[4].kind(&f.ehdr.get_machine())
+                    );
+                }
+                _ => unreachable!(),
+            }
+            assert_eq!(
+                f.sections[10].header.sh_size as usize,
+                f.sections[10].to_le_bytes().len()
+            );
         }
     }
 }
@@ -5,6 +5,27 @@ mod elf64;
 pub use elf32::*;
 
 mod elf32;
+pub use apply::*;
+
+mod apply;
+pub use kind::*;
+
+mod kind;
+pub use x86_64::*;
+
+mod x86_64;
+pub use i386::*;
+
+mod i386;
+pub use aarch64::*;
+
+mod aarch64;
+pub use arm::*;
+
+mod arm;
+pub use riscv::*;
+
+mod riscv;
 
 pub const R_X86_64_PC32: Elf64Xword = 2;
 pub const R_X86_64_PLT32: Elf64Xword = 4;
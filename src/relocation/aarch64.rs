@@ -0,0 +1,284 @@
+use crate::relocation::Calculation;
+
+/// Relocation types for AArch64(`R_AARCH64_*`)
+#[derive(Debug, Clone, Copy, Hash, Eq, Ord, PartialEq, PartialOrd)]
+pub enum AArch64Type {
+    /// No relocation
+    None,
+    /// Direct 64 bit
+    Abs64,
+    /// Direct 32 bit
+    Abs32,
+    /// Direct 16 bit
+    Abs16,
+    /// PC-relative 64 bit
+    Prel64,
+    /// PC-relative 32 bit
+    Prel32,
+    /// PC-relative 16 bit
+    Prel16,
+    /// Dir. MOVZ imm. from bits 15:0
+    MovwUabsG0,
+    /// Likewise for MOVK; no check
+    MovwUabsG0Nc,
+    /// Dir. MOVZ imm. from bits 31:16
+    MovwUabsG1,
+    /// Likewise for MOVK; no check
+    MovwUabsG1Nc,
+    /// Dir. MOVZ imm. from bits 47:32
+    MovwUabsG2,
+    /// Likewise for MOVK; no check
+    MovwUabsG2Nc,
+    /// Dir. MOV{K,Z} imm. from 63:48
+    MovwUabsG3,
+    /// PC-rel. LD imm. from bits 20:2
+    LdPrelLo19,
+    /// PC-rel. ADR imm. from bits 20:0
+    AdrPrelLo21,
+    /// Page-rel. ADRP imm. from 32:12
+    AdrPrelPgHi21,
+    /// Likewise; no overflow check
+    AdrPrelPgHi21Nc,
+    /// Dir. ADD imm. from bits 11:0
+    AddAbsLo12Nc,
+    /// Likewise for LD/ST; no check
+    Ldst8AbsLo12Nc,
+    /// PC-rel. TBZ/TBNZ imm. from 15:2
+    Tstbr14,
+    /// PC-rel. cond. br. imm. from 20:2
+    Condbr19,
+    /// PC-rel. B imm. from bits 27:2
+    Jump26,
+    /// Likewise for CALL
+    Call26,
+    /// Dir. ADD imm. from bits 11:1
+    Ldst16AbsLo12Nc,
+    /// Likewise for bits 11:2
+    Ldst32AbsLo12Nc,
+    /// Likewise for bits 11:3
+    Ldst64AbsLo12Nc,
+    /// Likewise for bits 11:4
+    Ldst128AbsLo12Nc,
+    /// P-page-rel. GOT off. ADRP 32:12
+    AdrGotPage,
+    /// Dir. GOT off. LD/ST imm. 11:3
+    Ld64GotLo12Nc,
+    /// Copy symbol at runtime
+    Copy,
+    /// Create GOT entry
+    GlobDat,
+    /// Create PLT entry
+    JumpSlot,
+    /// Adjust by program base
+    Relative,
+    /// Module number, 64 bit
+    TlsDtpMod,
+    /// Module-relative offset, 64 bit
+    TlsDtpRel,
+    /// TP-relative offset, 64 bit
+    TlsTpRel,
+    /// TLS Descriptor
+    TlsDesc,
+    /// STT_GNU_IFUNC relocation
+    IRelative,
+    /// User defined value
+    Any(u32),
+}
+
+impl AArch64Type {
+    pub fn to_bytes(&self) -> u32 {
+        match self {
+            Self::None => 0,
+            Self::Abs64 => 257,
+            Self::Abs32 => 258,
+            Self::Abs16 => 259,
+            Self::Prel64 => 260,
+            Self::Prel32 => 261,
+            Self::Prel16 => 262,
+            Self::MovwUabsG0 => 263,
+            Self::MovwUabsG0Nc => 264,
+            Self::MovwUabsG1 => 265,
+            Self::MovwUabsG1Nc => 266,
+            Self::MovwUabsG2 => 267,
+            Self::MovwUabsG2Nc => 268,
+            Self::MovwUabsG3 => 269,
+            Self::LdPrelLo19 => 273,
+            Self::AdrPrelLo21 => 274,
+            Self::AdrPrelPgHi21 => 275,
+            Self::AdrPrelPgHi21Nc => 276,
+            Self::AddAbsLo12Nc => 277,
+            Self::Ldst8AbsLo12Nc => 278,
+            Self::Tstbr14 => 279,
+            Self::Condbr19 => 280,
+            Self::Jump26 => 282,
+            Self::Call26 => 283,
+            Self::Ldst16AbsLo12Nc => 284,
+            Self::Ldst32AbsLo12Nc => 285,
+            Self::Ldst64AbsLo12Nc => 286,
+            Self::Ldst128AbsLo12Nc => 299,
+            Self::AdrGotPage => 311,
+            Self::Ld64GotLo12Nc => 312,
+            Self::Copy => 1024,
+            Self::GlobDat => 1025,
+            Self::JumpSlot => 1026,
+            Self::Relative => 1027,
+            Self::TlsDtpMod => 1028,
+            Self::TlsDtpRel => 1029,
+            Self::TlsTpRel => 1030,
+            Self::TlsDesc => 1031,
+            Self::IRelative => 1032,
+            Self::Any(c) => *c,
+        }
+    }
+
+    /// the name defined in the psABI like `R_AARCH64_CALL26`
+    pub fn name(&self) -> Option<&'static str> {
+        Some(match self {
+            Self::None => "R_AARCH64_NONE",
+            Self::Abs64 => "R_AARCH64_ABS64",
+            Self::Abs32 => "R_AARCH64_ABS32",
+            Self::Abs16 => "R_AARCH64_ABS16",
+            Self::Prel64 => "R_AARCH64_PREL64",
+            Self::Prel32 => "R_AARCH64_PREL32",
+            Self::Prel16 => "R_AARCH64_PREL16",
+            Self::MovwUabsG0 => "R_AARCH64_MOVW_UABS_G0",
+            Self::MovwUabsG0Nc => "R_AARCH64_MOVW_UABS_G0_NC",
+            Self::MovwUabsG1 => "R_AARCH64_MOVW_UABS_G1",
+            Self::MovwUabsG1Nc => "R_AARCH64_MOVW_UABS_G1_NC",
+            Self::MovwUabsG2 => "R_AARCH64_MOVW_UABS_G2",
+            Self::MovwUabsG2Nc => "R_AARCH64_MOVW_UABS_G2_NC",
+            Self::MovwUabsG3 => "R_AARCH64_MOVW_UABS_G3",
+            Self::LdPrelLo19 => "R_AARCH64_LD_PREL_LO19",
+            Self::AdrPrelLo21 => "R_AARCH64_ADR_PREL_LO21",
+            Self::AdrPrelPgHi21 => "R_AARCH64_ADR_PREL_PG_HI21",
+            Self::AdrPrelPgHi21Nc => "R_AARCH64_ADR_PREL_PG_HI21_NC",
+            Self::AddAbsLo12Nc => "R_AARCH64_ADD_ABS_LO12_NC",
+            Self::Ldst8AbsLo12Nc => "R_AARCH64_LDST8_ABS_LO12_NC",
+            Self::Tstbr14 => "R_AARCH64_TSTBR14",
+            Self::Condbr19 => "R_AARCH64_CONDBR19",
+            Self::Jump26 => "R_AARCH64_JUMP26",
+            Self::Call26 => "R_AARCH64_CALL26",
+            Self::Ldst16AbsLo12Nc => "R_AARCH64_LDST16_ABS_LO12_NC",
+            Self::Ldst32AbsLo12Nc => "R_AARCH64_LDST32_ABS_LO12_NC",
+            Self::Ldst64AbsLo12Nc => "R_AARCH64_LDST64_ABS_LO12_NC",
+            Self::Ldst128AbsLo12Nc => "R_AARCH64_LDST128_ABS_LO12_NC",
+            Self::AdrGotPage => "R_AARCH64_ADR_GOT_PAGE",
+            Self::Ld64GotLo12Nc => "R_AARCH64_LD64_GOT_LO12_NC",
+            Self::Copy => "R_AARCH64_COPY",
+            Self::GlobDat => "R_AARCH64_GLOB_DAT",
+            Self::JumpSlot => "R_AARCH64_JUMP_SLOT",
+            Self::Relative => "R_AARCH64_RELATIVE",
+            Self::TlsDtpMod => "R_AARCH64_TLS_DTPMOD",
+            Self::TlsDtpRel => "R_AARCH64_TLS_DTPREL",
+            Self::TlsTpRel => "R_AARCH64_TLS_TPREL",
+            Self::TlsDesc => "R_AARCH64_TLSDESC",
+            Self::IRelative => "R_AARCH64_IRELATIVE",
+            Self::Any(_) => return None,
+        })
+    }
+
+    /// the number of bytes to be modified at `r_offset`
+    pub fn size(&self) -> Option<usize> {
+        match self {
+            Self::None | Self::Copy | Self::Any(_) => None,
+            Self::Abs16 | Self::Prel16 => Some(2),
+            Self::Abs64
+            | Self::Prel64
+            | Self::GlobDat
+            | Self::JumpSlot
+            | Self::Relative
+            | Self::TlsDtpMod
+            | Self::TlsDtpRel
+            | Self::TlsTpRel
+            | Self::IRelative => Some(8),
+            // ディスクリプタは2ワード分
+            Self::TlsDesc => Some(16),
+            // 残りは全て命令(4バイト)の即値フィールド
+            _ => Some(4),
+        }
+    }
+
+    pub fn calculation(&self) -> Calculation {
+        match self {
+            Self::Abs64
+            | Self::Abs32
+            | Self::Abs16
+            | Self::MovwUabsG0
+            | Self::MovwUabsG0Nc
+            | Self::MovwUabsG1
+            | Self::MovwUabsG1Nc
+            | Self::MovwUabsG2
+            | Self::MovwUabsG2Nc
+            | Self::MovwUabsG3
+            | Self::AddAbsLo12Nc
+            | Self::Ldst8AbsLo12Nc
+            | Self::Ldst16AbsLo12Nc
+            | Self::Ldst32AbsLo12Nc
+            | Self::Ldst64AbsLo12Nc
+            | Self::Ldst128AbsLo12Nc
+            | Self::GlobDat
+            | Self::JumpSlot => Calculation::Absolute,
+            Self::Prel64
+            | Self::Prel32
+            | Self::Prel16
+            | Self::LdPrelLo19
+            | Self::AdrPrelLo21
+            | Self::AdrPrelPgHi21
+            | Self::AdrPrelPgHi21Nc
+            | Self::Tstbr14
+            | Self::Condbr19
+            | Self::Jump26
+            | Self::Call26
+            | Self::AdrGotPage => Calculation::PcRelative,
+            _ => Calculation::Other,
+        }
+    }
+}
+
+impl From<u32> for AArch64Type {
+    fn from(bytes: u32) -> Self {
+        match bytes {
+            // R_AARCH64_NONEは256も許容される
+            0 | 256 => Self::None,
+            257 => Self::Abs64,
+            258 => Self::Abs32,
+            259 => Self::Abs16,
+            260 => Self::Prel64,
+            261 => Self::Prel32,
+            262 => Self::Prel16,
+            263 => Self::MovwUabsG0,
+            264 => Self::MovwUabsG0Nc,
+            265 => Self::MovwUabsG1,
+            266 => Self::MovwUabsG1Nc,
+            267 => Self::MovwUabsG2,
+            268 => Self::MovwUabsG2Nc,
+            269 => Self::MovwUabsG3,
+            273 => Self::LdPrelLo19,
+            274 => Self::AdrPrelLo21,
+            275 => Self::AdrPrelPgHi21,
+            276 => Self::AdrPrelPgHi21Nc,
+            277 => Self::AddAbsLo12Nc,
+            278 => Self::Ldst8AbsLo12Nc,
+            279 => Self::Tstbr14,
+            280 => Self::Condbr19,
+            282 => Self::Jump26,
+            283 => Self::Call26,
+            284 => Self::Ldst16AbsLo12Nc,
+            285 => Self::Ldst32AbsLo12Nc,
+            286 => Self::Ldst64AbsLo12Nc,
+            299 => Self::Ldst128AbsLo12Nc,
+            311 => Self::AdrGotPage,
+            312 => Self::Ld64GotLo12Nc,
+            1024 => Self::Copy,
+            1025 => Self::GlobDat,
+            1026 => Self::JumpSlot,
+            1027 => Self::Relative,
+            1028 => Self::TlsDtpMod,
+            1029 => Self::TlsDtpRel,
+            1030 => Self::TlsTpRel,
+            1031 => Self::TlsDesc,
+            1032 => Self::IRelative,
+            _ => Self::Any(bytes),
+        }
+    }
+}
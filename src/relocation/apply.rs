@@ -0,0 +1,502 @@
+use thiserror::Error as TError;
+
+use crate::relocation::{AArch64Type, Kind, X8664Type};
+use crate::*;
+
+#[derive(TError, Debug, PartialEq, Eq)]
+pub enum RelocationError {
+    #[error("the file is not a relocatable object")]
+    NotRelocatable,
+    #[error("{name} is not supported")]
+    Unsupported { name: String },
+    #[error("{name} at offset {offset:#x} overflowed => {value:#x}")]
+    Overflow {
+        name: String,
+        offset: usize,
+        value: i64,
+    },
+    #[error("{name} at offset {offset:#x} is not aligned => {value:#x}")]
+    Misaligned {
+        name: String,
+        offset: usize,
+        value: i64,
+    },
+    #[error("{name} at offset {offset:#x} is out of the section")]
+    OutOfSection { name: String, offset: usize },
+    #[error("undefined symbol `{name}`")]
+    UndefinedSymbol { name: String },
+    #[error("symbol {sym_idx} is not in the symbol table {symtab_idx}")]
+    InvalidSymbol { symtab_idx: usize, sym_idx: usize },
+    #[error("section {sct_idx} can't be relocated")]
+    InvalidTarget { sct_idx: usize },
+}
+
+/// apply a relocation to `buf` which is the contents of the target section.
+///
+/// - `s` ... the address of the symbol
+/// - `a` ... the addend
+/// - `p` ... the address of the place(`buf[offset]`)
+pub fn relocate(
+    kind: Kind,
+    data: header::Data,
+    s: u64,
+    a: i64,
+    p: u64,
+    buf: &mut [u8],
+    offset: usize,
+) -> Result<(), RelocationError> {
+    let mut place = Place {
+        kind,
+        data,
+        buf,
+        offset,
+    };
+    match kind {
+        Kind::X8664(t) => relocate_x86_64(t, s, a, p, &mut place),
+        Kind::AArch64(t) => relocate_aarch64(t, s, a, p, &mut place),
+        _ => Err(place.unsupported()),
+    }
+}
+
+fn relocate_x86_64(
+    t: X8664Type,
+    s: u64,
+    a: i64,
+    p: u64,
+    place: &mut Place,
+) -> Result<(), RelocationError> {
+    let abs = s.wrapping_add(a as u64) as i64;
+    let pcrel = s.wrapping_add(a as u64).wrapping_sub(p) as i64;
+
+    match t {
+        X8664Type::None => Ok(()),
+        X8664Type::Abs64 => place.write(8, abs as u64),
+        X8664Type::Pc64 => place.write(8, pcrel as u64),
+        X8664Type::Abs32 => {
+            place.check_unsigned(abs, 32)?;
+            place.write(4, abs as u64)
+        }
+        X8664Type::Abs32S => {
+            place.check_signed(abs, 32)?;
+            place.write(4, abs as u64)
+        }
+        // PLTを作らないので，シンボルを直接呼び出す
+        X8664Type::Pc32 | X8664Type::Plt32 => {
+            place.check_signed(pcrel, 32)?;
+            place.write(4, pcrel as u64)
+        }
+        X8664Type::Abs16 => {
+            place.check_bitfield(abs, 16)?;
+            place.write(2, abs as u64)
+        }
+        X8664Type::Pc16 => {
+            place.check_signed(pcrel, 16)?;
+            place.write(2, pcrel as u64)
+        }
+        X8664Type::Abs8 => {
+            place.check_bitfield(abs, 8)?;
+            place.write(1, abs as u64)
+        }
+        X8664Type::Pc8 => {
+            place.check_signed(pcrel, 8)?;
+            place.write(1, pcrel as u64)
+        }
+        _ => Err(place.unsupported()),
+    }
+}
+
+fn relocate_aarch64(
+    t: AArch64Type,
+    s: u64,
+    a: i64,
+    p: u64,
+    place: &mut Place,
+) -> Result<(), RelocationError> {
+    let abs = s.wrapping_add(a as u64) as i64;
+    let pcrel = s.wrapping_add(a as u64).wrapping_sub(p) as i64;
+    let page = |addr: u64| addr & !0xfff;
+    let pgrel = page(s.wrapping_add(a as u64)).wrapping_sub(page(p)) as i64;
+
+    match t {
+        AArch64Type::None => Ok(()),
+        AArch64Type::Abs64 => place.write(8, abs as u64),
+        AArch64Type::Prel64 => place.write(8, pcrel as u64),
+        AArch64Type::Abs32 => {
+            place.check_bitfield(abs, 32)?;
+            place.write(4, abs as u64)
+        }
+        AArch64Type::Prel32 => {
+            place.check_bitfield(pcrel, 32)?;
+            place.write(4, pcrel as u64)
+        }
+        AArch64Type::Abs16 => {
+            place.check_bitfield(abs, 16)?;
+            place.write(2, abs as u64)
+        }
+        AArch64Type::Prel16 => {
+            place.check_bitfield(pcrel, 16)?;
+            place.write(2, pcrel as u64)
+        }
+        // PLTを作らないので，シンボルを直接呼び出す
+        AArch64Type::Call26 | AArch64Type::Jump26 => {
+            place.check_signed(pcrel, 28)?;
+            place.check_aligned(pcrel, 4)?;
+            place.patch_insn(pcrel as u64 >> 2, 0, 26)
+        }
+        AArch64Type::Condbr19 | AArch64Type::LdPrelLo19 => {
+            place.check_signed(pcrel, 21)?;
+            place.check_aligned(pcrel, 4)?;
+            place.patch_insn(pcrel as u64 >> 2, 5, 19)
+        }
+        AArch64Type::Tstbr14 => {
+            place.check_signed(pcrel, 16)?;
+            place.check_aligned(pcrel, 4)?;
+            place.patch_insn(pcrel as u64 >> 2, 5, 14)
+        }
+        AArch64Type::AdrPrelLo21 => {
+            place.check_signed(pcrel, 21)?;
+            place.patch_adr(pcrel as u64)
+        }
+        AArch64Type::AdrPrelPgHi21 => {
+            place.check_signed(pgrel, 33)?;
+            place.patch_adr(pgrel as u64 >> 12)
+        }
+        AArch64Type::AdrPrelPgHi21Nc => place.patch_adr(pgrel as u64 >> 12),
+        AArch64Type::AddAbsLo12Nc | AArch64Type::Ldst8AbsLo12Nc => {
+            place.patch_insn(abs as u64 & 0xfff, 10, 12)
+        }
+        AArch64Type::Ldst16AbsLo12Nc => place.patch_insn((abs as u64 & 0xfff) >> 1, 10, 12),
+        AArch64Type::Ldst32AbsLo12Nc => place.patch_insn((abs as u64 & 0xfff) >> 2, 10, 12),
+        AArch64Type::Ldst64AbsLo12Nc => place.patch_insn((abs as u64 & 0xfff) >> 3, 10, 12),
+        AArch64Type::Ldst128AbsLo12Nc => place.patch_insn((abs as u64 & 0xfff) >> 4, 10, 12),
+        AArch64Type::MovwUabsG0
+        | AArch64Type::MovwUabsG0Nc
+        | AArch64Type::MovwUabsG1
+        | AArch64Type::MovwUabsG1Nc
+        | AArch64Type::MovwUabsG2
+        | AArch64Type::MovwUabsG2Nc
+        | AArch64Type::MovwUabsG3 => {
+            let (group, check) = match t {
+                AArch64Type::MovwUabsG0 => (0, true),
+                AArch64Type::MovwUabsG0Nc => (0, false),
+                AArch64Type::MovwUabsG1 => (1, true),
+                AArch64Type::MovwUabsG1Nc => (1, false),
+                AArch64Type::MovwUabsG2 => (2, true),
+                AArch64Type::MovwUabsG2Nc => (2, false),
+                _ => (3, false),
+            };
+            if check {
+                place.check_unsigned(abs, 16 * (group + 1))?;
+            }
+            place.patch_insn((abs as u64 >> (16 * group)) & 0xffff, 5, 16)
+        }
+        _ => Err(place.unsupported()),
+    }
+}
+
+/// the place to be relocated
+struct Place<'a> {
+    kind: Kind,
+    data: header::Data,
+    buf: &'a mut [u8],
+    offset: usize,
+}
+
+impl<'a> Place<'a> {
+    fn unsupported(&self) -> RelocationError {
+        RelocationError::Unsupported {
+            name: self.kind.name(),
+        }
+    }
+
+    fn overflow(&self, value: i64) -> RelocationError {
+        RelocationError::Overflow {
+            name: self.kind.name(),
+            offset: self.offset,
+            value,
+        }
+    }
+
+    fn check_signed(&self, value: i64, bits: u32) -> Result<(), RelocationError> {
+        let min = -(1i64 << (bits - 1));
+        let max = (1i64 << (bits - 1)) - 1;
+        if value < min || max < value {
+            return Err(self.overflow(value));
+        }
+        Ok(())
+    }
+
+    fn check_unsigned(&self, value: i64, bits: u32) -> Result<(), RelocationError> {
+        if bits < 64 && (value as u64) >> bits != 0 {
+            return Err(self.overflow(value));
+        }
+        Ok(())
+    }
+
+    /// accept both signed and unsigned values(binutilsの`complain_overflow_bitfield`)
+    fn check_bitfield(&self, value: i64, bits: u32) -> Result<(), RelocationError> {
+        let min = -(1i64 << (bits - 1));
+        let max = (1i64 << bits) - 1;
+        if value < min || max < value {
+            return Err(self.overflow(value));
+        }
+        Ok(())
+    }
+
+    fn check_aligned(&self, value: i64, align: i64) -> Result<(), RelocationError> {
+        if value % align != 0 {
+            return Err(RelocationError::Misaligned {
+                name: self.kind.name(),
+                offset: self.offset,
+                value,
+            });
+        }
+        Ok(())
+    }
+
+    fn field(&mut self, size: usize) -> Result<&mut [u8], RelocationError> {
+        let name = self.kind.name();
+        let offset = self.offset;
+        offset
+            .checked_add(size)
+            .and_then(move |end| self.buf.get_mut(offset..end))
+            .ok_or(RelocationError::OutOfSection { name, offset })
+    }
+
+    fn write(&mut self, size: usize, value: u64) -> Result<(), RelocationError> {
+        let big_endian = self.data.is_big_endian();
+        let field = self.field(size)?;
+        for (i, b) in field.iter_mut().enumerate() {
+            let shift = if big_endian { size - 1 - i } else { i } * 8;
+            *b = (value >> shift) as u8;
+        }
+        Ok(())
+    }
+
+    /// replace `bits` bits from `lsb` of an instruction with `value`
+    fn patch_insn(&mut self, value: u64, lsb: u32, bits: u32) -> Result<(), RelocationError> {
+        // AArch64の命令はデータのエンディアンによらず常にリトルエンディアン
+        let field = self.field(4)?;
+        let insn = u32::from_le_bytes([field[0], field[1], field[2], field[3]]);
+        let mask = ((1u64 << bits) - 1) as u32;
+        let insn = (insn & !(mask << lsb)) | (((value as u32) & mask) << lsb);
+        field.copy_from_slice(&insn.to_le_bytes());
+        Ok(())
+    }
+
+    /// immhi:immlo of ADR/ADRP
+    fn patch_adr(&mut self, imm: u64) -> Result<(), RelocationError> {
+        self.patch_insn(imm & 0x3, 29, 2)?;
+        self.patch_insn((imm >> 2) & 0x7ffff, 5, 19)
+    }
+}
+
+#[cfg(test)]
+mod apply_tests {
+    use super::*;
+
+    fn relocate_insn(t: AArch64Type, insn: u32, s: u64, p: u64) -> Result<u32, RelocationError> {
+        let mut buf = insn.to_le_bytes().to_vec();
+        relocate(Kind::AArch64(t), header::Data::LSB2, s, 0, p, &mut buf, 0)?;
+        Ok(u32::from_le_bytes([buf[0], buf[1], buf[2], buf[3]]))
+    }
+
+    #[test]
+    fn relocate_aarch64_test() {
+        // bl #0x1000
+        assert_eq!(
+            Ok(0x94000400),
+            relocate_insn(AArch64Type::Call26, 0x94000000, 0x2000, 0x1000)
+        );
+        // bl #-4
+        assert_eq!(
+            Ok(0x97ffffff),
+            relocate_insn(AArch64Type::Call26, 0x94000000, 0x1000, 0x1004)
+        );
+        // adrp x0, 0x12345000
+        assert_eq!(
+            Ok(0x90091a20),
+            relocate_insn(AArch64Type::AdrPrelPgHi21, 0x90000000, 0x12345678, 0x1000)
+        );
+        // add x0, x0, #0x678
+        assert_eq!(
+            Ok(0x9119e000),
+            relocate_insn(AArch64Type::AddAbsLo12Nc, 0x91000000, 0x12345678, 0x1000)
+        );
+        // ldr x1, [x0, #0x678]
+        assert_eq!(
+            Ok(0xf9433c01),
+            relocate_insn(AArch64Type::Ldst64AbsLo12Nc, 0xf9400001, 0x12345678, 0x1000)
+        );
+    }
+
+    #[test]
+    fn relocation_error_test() {
+        assert!(matches!(
+            relocate_insn(AArch64Type::Call26, 0x94000000, 0x1000 + (1 << 27), 0x1000),
+            Err(RelocationError::Overflow { .. })
+        ));
+        assert!(matches!(
+            relocate_insn(AArch64Type::Call26, 0x94000000, 0x1002, 0x1000),
+            Err(RelocationError::Misaligned { .. })
+        ));
+        assert_eq!(
+            Err(RelocationError::Unsupported {
+                name: "R_AARCH64_ADR_GOT_PAGE".to_string()
+            }),
+            relocate_insn(AArch64Type::AdrGotPage, 0x90000000, 0, 0)
+        );
+
+        let mut buf = vec![0; 4];
+        assert_eq!(
+            Err(RelocationError::Overflow {
+                name: "R_X86_64_PC32".to_string(),
+                offset: 0,
+                value: 0x1_0000_0000,
+            }),
+            relocate(
+                Kind::X8664(X8664Type::Pc32),
+                header::Data::LSB2,
+                0x1_0000_0000,
+                0,
+                0,
+                &mut buf,
+                0
+            )
+        );
+        assert!(matches!(
+            relocate(
+                Kind::X8664(X8664Type::Abs64),
+                header::Data::LSB2,
+                0,
+                0,
+                0,
+                &mut buf,
+                0
+            ),
+            Err(RelocationError::OutOfSection { .. })
+        ));
+    }
+
+    fn raw_contents(f: &file::ELF64, name: &str) -> Vec<u8> {
+        match &f.first_section_by(|sct| sct.name == name).unwrap().contents {
+            section::Contents64::Raw(bytes) => bytes.clone(),
+            _ => unreachable!(),
+        }
+    }
+
+    fn word(bytes: &[u8], offset: usize) -> u32 {
+        u32::from_le_bytes([
+            bytes[offset],
+            bytes[offset + 1],
+            bytes[offset + 2],
+            bytes[offset + 3],
+        ])
+    }
+
+    #[test]
+    fn apply_relocations_x86_64_test() {
+        let mut f = parser::parse_elf64("src/parser/testdata/reloc.o").unwrap();
+        assert_eq!(0x40002c, f.assign_section_addresses(0x400000));
+        assert_eq!(
+            0x400020,
+            f.first_section_by(|sct| sct.name == ".data")
+                .unwrap()
+                .header
+                .sh_addr
+        );
+
+        f.apply_relocations(|name| match name {
+            "ext" => Some(0x500000),
+            _ => None,
+        })
+        .unwrap();
+
+        let text = raw_contents(&f, ".text");
+        // mov g(%rip),%edi
+        assert_eq!(0x400028 - 4 - 0x400006, word(&text, 0x6));
+        // call ext
+        assert_eq!(0x500000 - 4 - 0x40000b, word(&text, 0xb));
+        // mov $g,%eax
+        assert_eq!(0x400028, word(&text, 0x15));
+        // int *pg = &g;
+        assert_eq!(
+            0x400028u64.to_le_bytes().to_vec(),
+            raw_contents(&f, ".data")[..8].to_vec()
+        );
+    }
+
+    #[test]
+    fn apply_relocations_x86_64_error_test() {
+        let mut f = parser::parse_elf64("src/parser/testdata/reloc.o").unwrap();
+        f.assign_section_addresses(0x400000);
+        assert_eq!(
+            Err(RelocationError::UndefinedSymbol {
+                name: "ext".to_string()
+            }),
+            f.apply_relocations(|_| None)
+        );
+
+        // R_X86_64_32は4GiBを超えるアドレスを表現できない
+        let mut f = parser::parse_elf64("src/parser/testdata/reloc.o").unwrap();
+        f.assign_section_addresses(0x1_0000_0000);
+        assert!(matches!(
+            f.apply_relocations(|_| Some(0x1_0000_1000)),
+            Err(RelocationError::Overflow { name, offset: 0x15, .. }) if name == "R_X86_64_32"
+        ));
+
+        // SHT_RELは飛ばさずにエラーとする
+        let mut f = parser::parse_elf64("src/parser/testdata/reloc.o").unwrap();
+        let rel_text = f
+            .sections
+            .iter_mut()
+            .find(|sct| sct.name == ".rela.text")
+            .unwrap();
+        rel_text.name = ".rel.text".to_string();
+        rel_text.contents = section::Contents64::RelSymbols(Vec::new());
+        assert_eq!(
+            Err(RelocationError::Unsupported {
+                name: "SHT_REL section .rel.text".to_string()
+            }),
+            f.apply_relocations(|_| Some(0x500000))
+        );
+
+        let mut f = parser::parse_elf64("src/parser/testdata/sample").unwrap();
+        assert_eq!(
+            Err(RelocationError::NotRelocatable),
+            f.apply_relocations(|_| None)
+        );
+    }
+
+    #[test]
+    fn apply_relocations_aarch64_test() {
+        let mut f = parser::parse_elf64("src/parser/testdata/reloc_aarch64.o").unwrap();
+        f.assign_section_addresses(0x400ff0);
+        f.apply_relocations(|name| match name {
+            "ext" => Some(0x410000),
+            _ => None,
+        })
+        .unwrap();
+
+        let text = raw_contents(&f, ".text");
+        // adrp x0, g
+        assert_eq!(0xb0000000, word(&text, 0));
+        // add x0, x0, :lo12:g
+        assert_eq!(0x91004000, word(&text, 4));
+        // ldr x1, [x0, :lo12:g]
+        assert_eq!(0xf9400801, word(&text, 8));
+        // bl ext
+        assert_eq!(0x94003c01, word(&text, 12));
+        // b f
+        assert_eq!(0x17fffffc, word(&text, 16));
+        // movz x2, #:abs_g1:g
+        assert_eq!(0xd2a00802, word(&text, 20));
+        // movk x2, #:abs_g0_nc:g
+        assert_eq!(0xf2820202, word(&text, 24));
+
+        assert_eq!(
+            0x401010u64.to_le_bytes().to_vec(),
+            raw_contents(&f, ".data")[..8].to_vec()
+        );
+    }
+}
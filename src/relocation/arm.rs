@@ -0,0 +1,218 @@
+use crate::relocation::Calculation;
+
+/// Relocation types for ARM(`R_ARM_*`)
+#[derive(Debug, Clone, Copy, Hash, Eq, Ord, PartialEq, PartialOrd)]
+pub enum ArmType {
+    /// No reloc
+    None,
+    /// Deprecated PC relative 26 bit branch
+    Pc24,
+    /// Direct 32 bit
+    Abs32,
+    /// PC relative 32 bit
+    Rel32,
+    /// Direct 16 bit
+    Abs16,
+    /// Direct 8 bit
+    Abs8,
+    /// PC relative 24 bit (Thumb32 BL)
+    ThmCall,
+    /// ID of module containing symbol
+    TlsDtpMod32,
+    /// Offset in TLS block
+    TlsDtpOff32,
+    /// Offset in static TLS block
+    TlsTpOff32,
+    /// Copy symbol at runtime
+    Copy,
+    /// Create GOT entry
+    GlobDat,
+    /// Create PLT entry
+    JumpSlot,
+    /// Adjust by program base
+    Relative,
+    /// 32 bit offset to GOT
+    GotOff32,
+    /// 32 bit PC relative offset to GOT
+    BasePrel,
+    /// 32 bit GOT entry
+    GotBrel,
+    /// Deprecated, 32 bit PLT address
+    Plt32,
+    /// PC relative 24 bit (BL, BLX)
+    Call,
+    /// PC relative 24 bit (B, BL<cond>)
+    Jump24,
+    /// PC relative 24 bit (Thumb32 B.W)
+    ThmJump24,
+    /// Marker for ARMv4 BX
+    V4bx,
+    /// 31 bit PC relative offset for exception tables
+    Prel31,
+    /// Direct 16-bit (MOVW)
+    MovwAbsNc,
+    /// Direct high 16-bit (MOVT)
+    MovtAbs,
+    /// PC relative 16-bit (MOVW)
+    MovwPrelNc,
+    /// PC relative high 16-bit (MOVT)
+    MovtPrel,
+    /// Direct 16 bit (Thumb32 MOVW)
+    ThmMovwAbsNc,
+    /// Direct high 16 bit (Thumb32 MOVT)
+    ThmMovtAbs,
+    /// Adjust indirectly by program base
+    IRelative,
+    /// User defined value
+    Any(u32),
+}
+
+impl ArmType {
+    pub fn to_bytes(&self) -> u32 {
+        match self {
+            Self::None => 0,
+            Self::Pc24 => 1,
+            Self::Abs32 => 2,
+            Self::Rel32 => 3,
+            Self::Abs16 => 5,
+            Self::Abs8 => 8,
+            Self::ThmCall => 10,
+            Self::TlsDtpMod32 => 17,
+            Self::TlsDtpOff32 => 18,
+            Self::TlsTpOff32 => 19,
+            Self::Copy => 20,
+            Self::GlobDat => 21,
+            Self::JumpSlot => 22,
+            Self::Relative => 23,
+            Self::GotOff32 => 24,
+            Self::BasePrel => 25,
+            Self::GotBrel => 26,
+            Self::Plt32 => 27,
+            Self::Call => 28,
+            Self::Jump24 => 29,
+            Self::ThmJump24 => 30,
+            Self::V4bx => 40,
+            Self::Prel31 => 42,
+            Self::MovwAbsNc => 43,
+            Self::MovtAbs => 44,
+            Self::MovwPrelNc => 45,
+            Self::MovtPrel => 46,
+            Self::ThmMovwAbsNc => 47,
+            Self::ThmMovtAbs => 48,
+            Self::IRelative => 160,
+            Self::Any(c) => *c,
+        }
+    }
+
+    /// the name defined in the psABI like `R_ARM_CALL`
+    pub fn name(&self) -> Option<&'static str> {
+        Some(match self {
+            Self::None => "R_ARM_NONE",
+            Self::Pc24 => "R_ARM_PC24",
+            Self::Abs32 => "R_ARM_ABS32",
+            Self::Rel32 => "R_ARM_REL32",
+            Self::Abs16 => "R_ARM_ABS16",
+            Self::Abs8 => "R_ARM_ABS8",
+            Self::ThmCall => "R_ARM_THM_CALL",
+            Self::TlsDtpMod32 => "R_ARM_TLS_DTPMOD32",
+            Self::TlsDtpOff32 => "R_ARM_TLS_DTPOFF32",
+            Self::TlsTpOff32 => "R_ARM_TLS_TPOFF32",
+            Self::Copy => "R_ARM_COPY",
+            Self::GlobDat => "R_ARM_GLOB_DAT",
+            Self::JumpSlot => "R_ARM_JUMP_SLOT",
+            Self::Relative => "R_ARM_RELATIVE",
+            Self::GotOff32 => "R_ARM_GOTOFF32",
+            Self::BasePrel => "R_ARM_BASE_PREL",
+            Self::GotBrel => "R_ARM_GOT_BREL",
+            Self::Plt32 => "R_ARM_PLT32",
+            Self::Call => "R_ARM_CALL",
+            Self::Jump24 => "R_ARM_JUMP24",
+            Self::ThmJump24 => "R_ARM_THM_JUMP24",
+            Self::V4bx => "R_ARM_V4BX",
+            Self::Prel31 => "R_ARM_PREL31",
+            Self::MovwAbsNc => "R_ARM_MOVW_ABS_NC",
+            Self::MovtAbs => "R_ARM_MOVT_ABS",
+            Self::MovwPrelNc => "R_ARM_MOVW_PREL_NC",
+            Self::MovtPrel => "R_ARM_MOVT_PREL",
+            Self::ThmMovwAbsNc => "R_ARM_THM_MOVW_ABS_NC",
+            Self::ThmMovtAbs => "R_ARM_THM_MOVT_ABS",
+            Self::IRelative => "R_ARM_IRELATIVE",
+            Self::Any(_) => return None,
+        })
+    }
+
+    /// the number of bytes to be modified at `r_offset`
+    pub fn size(&self) -> Option<usize> {
+        match self {
+            Self::None | Self::Copy | Self::V4bx | Self::Any(_) => None,
+            Self::Abs8 => Some(1),
+            Self::Abs16 => Some(2),
+            // Thumb32命令も2つのハーフワードで4バイト
+            _ => Some(4),
+        }
+    }
+
+    pub fn calculation(&self) -> Calculation {
+        match self {
+            Self::Abs32
+            | Self::Abs16
+            | Self::Abs8
+            | Self::GlobDat
+            | Self::JumpSlot
+            | Self::MovwAbsNc
+            | Self::MovtAbs
+            | Self::ThmMovwAbsNc
+            | Self::ThmMovtAbs => Calculation::Absolute,
+            Self::Pc24
+            | Self::Rel32
+            | Self::ThmCall
+            | Self::BasePrel
+            | Self::Plt32
+            | Self::Call
+            | Self::Jump24
+            | Self::ThmJump24
+            | Self::Prel31
+            | Self::MovwPrelNc
+            | Self::MovtPrel => Calculation::PcRelative,
+            _ => Calculation::Other,
+        }
+    }
+}
+
+impl From<u32> for ArmType {
+    fn from(bytes: u32) -> Self {
+        match bytes {
+            0 => Self::None,
+            1 => Self::Pc24,
+            2 => Self::Abs32,
+            3 => Self::Rel32,
+            5 => Self::Abs16,
+            8 => Self::Abs8,
+            10 => Self::ThmCall,
+            17 => Self::TlsDtpMod32,
+            18 => Self::TlsDtpOff32,
+            19 => Self::TlsTpOff32,
+            20 => Self::Copy,
+            21 => Self::GlobDat,
+            22 => Self::JumpSlot,
+            23 => Self::Relative,
+            24 => Self::GotOff32,
+            25 => Self::BasePrel,
+            26 => Self::GotBrel,
+            27 => Self::Plt32,
+            28 => Self::Call,
+            29 => Self::Jump24,
+            30 => Self::ThmJump24,
+            40 => Self::V4bx,
+            42 => Self::Prel31,
+            43 => Self::MovwAbsNc,
+            44 => Self::MovtAbs,
+            45 => Self::MovwPrelNc,
+            46 => Self::MovtPrel,
+            47 => Self::ThmMovwAbsNc,
+            48 => Self::ThmMovtAbs,
+            160 => Self::IRelative,
+            _ => Self::Any(bytes),
+        }
+    }
+}
@@ -24,6 +24,11 @@ impl Rela32 {
         self.r_info & 0xff
     }
 
+    /// the relocation type interpreted with `e_machine`
+    pub fn kind(&self, machine: &header::Machine) -> relocation::Kind {
+        relocation::Kind::new(machine, self.get_type())
+    }
+
     pub fn get_offset(&self) -> Elf32Addr {
         self.r_offset
     }
@@ -55,7 +60,80 @@ impl Rela32 {
     /// assert_eq!([0].repeat(Rela32::SIZE as usize), null_rel.to_le_bytes());
     /// ```
     pub fn to_le_bytes(&self) -> Vec<u8> {
-        bincode::serialize(self).unwrap()
+        self.to_bytes(header::Data::LSB2)
+    }
+
+    /// Create Vec<u8> from this with the given data encoding.
+    pub fn to_bytes(&self, data: header::Data) -> Vec<u8> {
+        data.serialize(self)
+    }
+
+    pub fn deserialize(buf: &[u8], start: usize) -> Result<Self, Box<dyn std::error::Error>> {
+        // bincode::ErrorKindをトレイトオブジェクトとするため,この冗長な書き方が必要
+        match bincode::deserialize(&buf[start..]) {
+            Ok(header) => Ok(header),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[derive(
+    Default, Debug, Clone, Copy, Hash, PartialOrd, Ord, PartialEq, Eq, Serialize, Deserialize,
+)]
+#[repr(C)]
+pub struct Rel32 {
+    /// Location at which to apply the action
+    r_offset: Elf32Addr,
+    /// index and type of relocation
+    r_info: Elf32Word,
+}
+
+#[allow(dead_code)]
+impl Rel32 {
+    pub const SIZE: Elf32Xword = 8;
+    pub fn get_sym(&self) -> Elf32Word {
+        self.r_info >> 8
+    }
+    pub fn get_type(&self) -> Elf32Word {
+        self.r_info & 0xff
+    }
+
+    /// the relocation type interpreted with `e_machine`
+    pub fn kind(&self, machine: &header::Machine) -> relocation::Kind {
+        relocation::Kind::new(machine, self.get_type())
+    }
+
+    pub fn get_offset(&self) -> Elf32Addr {
+        self.r_offset
+    }
+    pub fn get_info(&self) -> Elf32Word {
+        self.r_info
+    }
+
+    pub fn set_offset(&mut self, offset: Elf32Addr) {
+        self.r_offset = offset;
+    }
+    pub fn set_info(&mut self, info: Elf32Word) {
+        self.r_info = info;
+    }
+
+    /// Create Vec<u8> from this.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use elf_utilities::relocation::Rel32;
+    /// let null_rel : Rel32 = Default::default();
+    ///
+    /// assert_eq!([0].repeat(Rel32::SIZE as usize), null_rel.to_le_bytes());
+    /// ```
+    pub fn to_le_bytes(&self) -> Vec<u8> {
+        self.to_bytes(header::Data::LSB2)
+    }
+
+    /// Create Vec<u8> from this with the given data encoding.
+    pub fn to_bytes(&self, data: header::Data) -> Vec<u8> {
+        data.serialize(self)
     }
 
     pub fn deserialize(buf: &[u8], start: usize) -> Result<Self, Box<dyn std::error::Error>> {
@@ -24,6 +24,11 @@ impl Rela64 {
         self.r_info & 0xffffffff
     }
 
+    /// the relocation type interpreted with `e_machine`
+    pub fn kind(&self, machine: &header::Machine) -> relocation::Kind {
+        relocation::Kind::new(machine, self.get_type() as u32)
+    }
+
     pub fn get_offset(&self) -> Elf64Addr {
         self.r_offset
     }
@@ -55,7 +60,80 @@ impl Rela64 {
     /// assert_eq!([0].repeat(Rela64::SIZE as usize), null_rel.to_le_bytes());
     /// ```
     pub fn to_le_bytes(&self) -> Vec<u8> {
-        bincode::serialize(self).unwrap()
+        self.to_bytes(header::Data::LSB2)
+    }
+
+    /// Create Vec<u8> from this with the given data encoding.
+    pub fn to_bytes(&self, data: header::Data) -> Vec<u8> {
+        data.serialize(self)
+    }
+
+    pub fn deserialize(buf: &[u8], start: usize) -> Result<Self, Box<dyn std::error::Error>> {
+        // bincode::ErrorKindをトレイトオブジェクトとするため,この冗長な書き方が必要
+        match bincode::deserialize(&buf[start..]) {
+            Ok(header) => Ok(header),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[derive(
+    Default, Debug, Clone, Copy, Hash, PartialOrd, Ord, PartialEq, Eq, Serialize, Deserialize,
+)]
+#[repr(C)]
+pub struct Rel64 {
+    /// Location at which to apply the action
+    r_offset: Elf64Addr,
+    /// index and type of relocation
+    r_info: Elf64Xword,
+}
+
+#[allow(dead_code)]
+impl Rel64 {
+    pub const SIZE: Elf64Xword = 16;
+    pub fn get_sym(&self) -> Elf64Xword {
+        self.r_info >> 32
+    }
+    pub fn get_type(&self) -> Elf64Xword {
+        self.r_info & 0xffffffff
+    }
+
+    /// the relocation type interpreted with `e_machine`
+    pub fn kind(&self, machine: &header::Machine) -> relocation::Kind {
+        relocation::Kind::new(machine, self.get_type() as u32)
+    }
+
+    pub fn get_offset(&self) -> Elf64Addr {
+        self.r_offset
+    }
+    pub fn get_info(&self) -> Elf64Xword {
+        self.r_info
+    }
+
+    pub fn set_offset(&mut self, offset: Elf64Addr) {
+        self.r_offset = offset;
+    }
+    pub fn set_info(&mut self, info: Elf64Xword) {
+        self.r_info = info;
+    }
+
+    /// Create Vec<u8> from this.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use elf_utilities::relocation::Rel64;
+    /// let null_rel : Rel64 = Default::default();
+    ///
+    /// assert_eq!([0].repeat(Rel64::SIZE as usize), null_rel.to_le_bytes());
+    /// ```
+    pub fn to_le_bytes(&self) -> Vec<u8> {
+        self.to_bytes(header::Data::LSB2)
+    }
+
+    /// Create Vec<u8> from this with the given data encoding.
+    pub fn to_bytes(&self, data: header::Data) -> Vec<u8> {
+        data.serialize(self)
     }
 
     pub fn deserialize(buf: &[u8], start: usize) -> Result<Self, Box<dyn std::error::Error>> {
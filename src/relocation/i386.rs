@@ -0,0 +1,158 @@
+use crate::relocation::Calculation;
+
+/// Relocation types for i386(`R_386_*`)
+#[derive(Debug, Clone, Copy, Hash, Eq, Ord, PartialEq, PartialOrd)]
+pub enum I386Type {
+    /// No reloc
+    None,
+    /// Direct 32 bit
+    Abs32,
+    /// PC relative 32 bit
+    Pc32,
+    /// 32 bit GOT entry
+    Got32,
+    /// 32 bit PLT address
+    Plt32,
+    /// Copy symbol at runtime
+    Copy,
+    /// Create GOT entry
+    GlobDat,
+    /// Create PLT entry
+    JmpSlot,
+    /// Adjust by program base
+    Relative,
+    /// 32 bit offset to GOT
+    GotOff,
+    /// 32 bit PC relative offset to GOT
+    GotPc,
+    /// Offset in static TLS block
+    TlsTpOff,
+    /// Direct 16 bit
+    Abs16,
+    /// PC relative 16 bit
+    Pc16,
+    /// Direct 8 bit
+    Abs8,
+    /// PC relative 8 bit
+    Pc8,
+    /// ID of module containing symbol
+    TlsDtpMod32,
+    /// Offset in TLS block
+    TlsDtpOff32,
+    /// Negated offset in static TLS block
+    TlsTpOff32,
+    /// Adjust indirectly by program base
+    IRelative,
+    /// Load from 32 bit GOT entry, relaxable
+    Got32X,
+    /// User defined value
+    Any(u32),
+}
+
+impl I386Type {
+    pub fn to_bytes(&self) -> u32 {
+        match self {
+            Self::None => 0,
+            Self::Abs32 => 1,
+            Self::Pc32 => 2,
+            Self::Got32 => 3,
+            Self::Plt32 => 4,
+            Self::Copy => 5,
+            Self::GlobDat => 6,
+            Self::JmpSlot => 7,
+            Self::Relative => 8,
+            Self::GotOff => 9,
+            Self::GotPc => 10,
+            Self::TlsTpOff => 14,
+            Self::Abs16 => 20,
+            Self::Pc16 => 21,
+            Self::Abs8 => 22,
+            Self::Pc8 => 23,
+            Self::TlsDtpMod32 => 35,
+            Self::TlsDtpOff32 => 36,
+            Self::TlsTpOff32 => 37,
+            Self::IRelative => 42,
+            Self::Got32X => 43,
+            Self::Any(c) => *c,
+        }
+    }
+
+    /// the name defined in the psABI like `R_386_PC32`
+    pub fn name(&self) -> Option<&'static str> {
+        Some(match self {
+            Self::None => "R_386_NONE",
+            Self::Abs32 => "R_386_32",
+            Self::Pc32 => "R_386_PC32",
+            Self::Got32 => "R_386_GOT32",
+            Self::Plt32 => "R_386_PLT32",
+            Self::Copy => "R_386_COPY",
+            Self::GlobDat => "R_386_GLOB_DAT",
+            Self::JmpSlot => "R_386_JMP_SLOT",
+            Self::Relative => "R_386_RELATIVE",
+            Self::GotOff => "R_386_GOTOFF",
+            Self::GotPc => "R_386_GOTPC",
+            Self::TlsTpOff => "R_386_TLS_TPOFF",
+            Self::Abs16 => "R_386_16",
+            Self::Pc16 => "R_386_PC16",
+            Self::Abs8 => "R_386_8",
+            Self::Pc8 => "R_386_PC8",
+            Self::TlsDtpMod32 => "R_386_TLS_DTPMOD32",
+            Self::TlsDtpOff32 => "R_386_TLS_DTPOFF32",
+            Self::TlsTpOff32 => "R_386_TLS_TPOFF32",
+            Self::IRelative => "R_386_IRELATIVE",
+            Self::Got32X => "R_386_GOT32X",
+            Self::Any(_) => return None,
+        })
+    }
+
+    /// the number of bytes to be modified at `r_offset`
+    pub fn size(&self) -> Option<usize> {
+        match self {
+            Self::None | Self::Copy | Self::Any(_) => None,
+            Self::Abs8 | Self::Pc8 => Some(1),
+            Self::Abs16 | Self::Pc16 => Some(2),
+            _ => Some(4),
+        }
+    }
+
+    pub fn calculation(&self) -> Calculation {
+        match self {
+            Self::Abs32 | Self::GlobDat | Self::JmpSlot | Self::Abs16 | Self::Abs8 => {
+                Calculation::Absolute
+            }
+            Self::Pc32 | Self::Plt32 | Self::GotPc | Self::Pc16 | Self::Pc8 => {
+                Calculation::PcRelative
+            }
+            _ => Calculation::Other,
+        }
+    }
+}
+
+impl From<u32> for I386Type {
+    fn from(bytes: u32) -> Self {
+        match bytes {
+            0 => Self::None,
+            1 => Self::Abs32,
+            2 => Self::Pc32,
+            3 => Self::Got32,
+            4 => Self::Plt32,
+            5 => Self::Copy,
+            6 => Self::GlobDat,
+            7 => Self::JmpSlot,
+            8 => Self::Relative,
+            9 => Self::GotOff,
+            10 => Self::GotPc,
+            14 => Self::TlsTpOff,
+            20 => Self::Abs16,
+            21 => Self::Pc16,
+            22 => Self::Abs8,
+            23 => Self::Pc8,
+            35 => Self::TlsDtpMod32,
+            36 => Self::TlsDtpOff32,
+            37 => Self::TlsTpOff32,
+            42 => Self::IRelative,
+            43 => Self::Got32X,
+            _ => Self::Any(bytes),
+        }
+    }
+}
@@ -0,0 +1,184 @@
+use crate::relocation::{AArch64Type, ArmType, I386Type, RiscVType, X8664Type};
+use crate::*;
+
+/// How the value of a relocation is computed from the target address
+#[derive(Debug, Clone, Copy, Hash, Eq, Ord, PartialEq, PartialOrd)]
+pub enum Calculation {
+    /// S + A (e.g. `R_X86_64_64`)
+    Absolute,
+    /// S + A - P (e.g. `R_X86_64_PC32`)
+    PcRelative,
+    /// GOT/TLS/dynamic linker specific computations
+    Other,
+}
+
+/// A relocation type interpreted with `e_machine`.
+///
+/// # Examples
+///
+/// ```
+/// use elf_utilities::{header, relocation};
+///
+/// let kind = relocation::Kind::new(&header::Machine::X8664, 2);
+///
+/// assert_eq!(relocation::Kind::X8664(relocation::X8664Type::Pc32), kind);
+/// assert_eq!("R_X86_64_PC32", kind.name());
+/// assert_eq!(Some(4), kind.size());
+/// assert!(kind.is_pc_relative());
+/// ```
+#[derive(Debug, Clone, Copy, Hash, Eq, Ord, PartialEq, PartialOrd)]
+pub enum Kind {
+    X8664(X8664Type),
+    I386(I386Type),
+    AArch64(AArch64Type),
+    Arm(ArmType),
+    RiscV(RiscVType),
+    /// relocation type of unsupported machines
+    Any(u32),
+}
+
+impl Kind {
+    pub fn new(machine: &header::Machine, r_type: u32) -> Self {
+        match machine {
+            header::Machine::X8664 => Self::X8664(X8664Type::from(r_type)),
+            header::Machine::Intel386 => Self::I386(I386Type::from(r_type)),
+            header::Machine::AArch64 => Self::AArch64(AArch64Type::from(r_type)),
+            header::Machine::Arm => Self::Arm(ArmType::from(r_type)),
+            header::Machine::RiscV => Self::RiscV(RiscVType::from(r_type)),
+            _ => Self::Any(r_type),
+        }
+    }
+
+    /// the raw value stored in `r_info`
+    pub fn to_bytes(&self) -> u32 {
+        match self {
+            Self::X8664(t) => t.to_bytes(),
+            Self::I386(t) => t.to_bytes(),
+            Self::AArch64(t) => t.to_bytes(),
+            Self::Arm(t) => t.to_bytes(),
+            Self::RiscV(t) => t.to_bytes(),
+            Self::Any(c) => *c,
+        }
+    }
+
+    /// the name like `R_X86_64_PC32`.
+    /// unknown types are shown as their number.
+    pub fn name(&self) -> String {
+        let name = match self {
+            Self::X8664(t) => t.name(),
+            Self::I386(t) => t.name(),
+            Self::AArch64(t) => t.name(),
+            Self::Arm(t) => t.name(),
+            Self::RiscV(t) => t.name(),
+            Self::Any(_) => None,
+        };
+        match name {
+            Some(name) => name.to_string(),
+            None => format!("<unknown>: {:#x}", self.to_bytes()),
+        }
+    }
+
+    /// the number of bytes to be modified at `r_offset`.
+    /// return `None` if the relocation doesn't patch a fixed-size field or it's unknown.
+    pub fn size(&self) -> Option<usize> {
+        match self {
+            Self::X8664(t) => t.size(),
+            Self::I386(t) => t.size(),
+            Self::AArch64(t) => t.size(),
+            Self::Arm(t) => t.size(),
+            Self::RiscV(t) => t.size(),
+            Self::Any(_) => None,
+        }
+    }
+
+    pub fn calculation(&self) -> Calculation {
+        match self {
+            Self::X8664(t) => t.calculation(),
+            Self::I386(t) => t.calculation(),
+            Self::AArch64(t) => t.calculation(),
+            Self::Arm(t) => t.calculation(),
+            Self::RiscV(t) => t.calculation(),
+            Self::Any(_) => Calculation::Other,
+        }
+    }
+
+    pub fn is_pc_relative(&self) -> bool {
+        self.calculation() == Calculation::PcRelative
+    }
+
+    pub fn is_absolute(&self) -> bool {
+        self.calculation() == Calculation::Absolute
+    }
+}
+
+#[cfg(test)]
+mod kind_tests {
+    use super::*;
+
+    #[test]
+    fn kind_round_trip_test() {
+        let machines = [
+            header::Machine::X8664,
+            header::Machine::Intel386,
+            header::Machine::AArch64,
+            header::Machine::Arm,
+            header::Machine::RiscV,
+        ];
+        for machine in machines.iter() {
+            for r_type in 0..1100 {
+                let kind = Kind::new(machine, r_type);
+                // R_AARCH64_NONEの別名(256)だけは0に正規化される
+                if kind == Kind::AArch64(AArch64Type::None) {
+                    continue;
+                }
+                assert_eq!(r_type, kind.to_bytes());
+            }
+        }
+    }
+
+    #[test]
+    fn kind_name_test() {
+        assert_eq!(
+            "R_X86_64_PLT32",
+            Kind::new(&header::Machine::X8664, 4).name()
+        );
+        assert_eq!("R_386_32", Kind::new(&header::Machine::Intel386, 1).name());
+        assert_eq!(
+            "R_AARCH64_CALL26",
+            Kind::new(&header::Machine::AArch64, 283).name()
+        );
+        assert_eq!("R_ARM_CALL", Kind::new(&header::Machine::Arm, 28).name());
+        assert_eq!(
+            "R_RISCV_CALL_PLT",
+            Kind::new(&header::Machine::RiscV, 19).name()
+        );
+        assert_eq!(
+            "<unknown>: 0xff",
+            Kind::new(&header::Machine::X8664, 0xff).name()
+        );
+        assert_eq!(Kind::Any(1), Kind::new(&header::Machine::SPARC, 1));
+    }
+
+    #[test]
+    fn kind_classification_test() {
+        let abs64 = Kind::new(&header::Machine::X8664, 1);
+        assert_eq!(Some(8), abs64.size());
+        assert!(abs64.is_absolute());
+
+        let call26 = Kind::new(&header::Machine::AArch64, 283);
+        assert_eq!(Some(4), call26.size());
+        assert!(call26.is_pc_relative());
+
+        let pc16 = Kind::new(&header::Machine::Intel386, 21);
+        assert_eq!(Some(2), pc16.size());
+        assert!(pc16.is_pc_relative());
+
+        let copy = Kind::new(&header::Machine::Arm, 20);
+        assert_eq!(None, copy.size());
+        assert_eq!(Calculation::Other, copy.calculation());
+
+        let hi20 = Kind::new(&header::Machine::RiscV, 26);
+        assert!(hi20.is_absolute());
+        assert_eq!(None, Kind::new(&header::Machine::RiscV, 3).size());
+    }
+}
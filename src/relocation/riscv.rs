@@ -0,0 +1,328 @@
+use crate::relocation::Calculation;
+
+/// Relocation types for RISC-V(`R_RISCV_*`)
+#[derive(Debug, Clone, Copy, Hash, Eq, Ord, PartialEq, PartialOrd)]
+pub enum RiscVType {
+    /// No reloc
+    None,
+    /// Direct 32 bit
+    Abs32,
+    /// Direct 64 bit
+    Abs64,
+    /// Adjust by program base
+    Relative,
+    /// Copy symbol at runtime
+    Copy,
+    /// Create PLT entry
+    JumpSlot,
+    /// ID of module containing symbol, 32 bit
+    TlsDtpMod32,
+    /// ID of module containing symbol, 64 bit
+    TlsDtpMod64,
+    /// Offset in TLS block, 32 bit
+    TlsDtpRel32,
+    /// Offset in TLS block, 64 bit
+    TlsDtpRel64,
+    /// Offset in static TLS block, 32 bit
+    TlsTpRel32,
+    /// Offset in static TLS block, 64 bit
+    TlsTpRel64,
+    /// PC-relative branch(B-type)
+    Branch,
+    /// PC-relative jump(J-type)
+    Jal,
+    /// PC-relative call(AUIPC + JALR)
+    Call,
+    /// PC-relative call through PLT
+    CallPlt,
+    /// PC-relative GOT reference(U-type)
+    GotHi20,
+    /// PC-relative TLS IE GOT offset
+    TlsGotHi20,
+    /// PC-relative TLS GD reference
+    TlsGdHi20,
+    /// PC-relative reference(U-type)
+    PcrelHi20,
+    /// PC-relative reference(I-type)
+    PcrelLo12I,
+    /// PC-relative reference(S-type)
+    PcrelLo12S,
+    /// Absolute address(U-type)
+    Hi20,
+    /// Absolute address(I-type)
+    Lo12I,
+    /// Absolute address(S-type)
+    Lo12S,
+    /// TLS LE thread offset(U-type)
+    TprelHi20,
+    /// TLS LE thread offset(I-type)
+    TprelLo12I,
+    /// TLS LE thread offset(S-type)
+    TprelLo12S,
+    /// TLS LE thread usage
+    TprelAdd,
+    /// 8 bit label addition
+    Add8,
+    /// 16 bit label addition
+    Add16,
+    /// 32 bit label addition
+    Add32,
+    /// 64 bit label addition
+    Add64,
+    /// 8 bit label subtraction
+    Sub8,
+    /// 16 bit label subtraction
+    Sub16,
+    /// 32 bit label subtraction
+    Sub32,
+    /// 64 bit label subtraction
+    Sub64,
+    /// Alignment statement
+    Align,
+    /// PC-relative branch offset(CB-type)
+    RvcBranch,
+    /// PC-relative jump offset(CJ-type)
+    RvcJump,
+    /// Instruction can be relaxed
+    Relax,
+    /// Local label subtraction
+    Sub6,
+    /// Local label assignment
+    Set6,
+    /// Local label assignment
+    Set8,
+    /// Local label assignment
+    Set16,
+    /// Local label assignment
+    Set32,
+    /// 32-bit PC relative
+    Pcrel32,
+    /// Relocation against a non-preemptible ifunc symbol
+    IRelative,
+    /// User defined value
+    Any(u32),
+}
+
+impl RiscVType {
+    pub fn to_bytes(&self) -> u32 {
+        match self {
+            Self::None => 0,
+            Self::Abs32 => 1,
+            Self::Abs64 => 2,
+            Self::Relative => 3,
+            Self::Copy => 4,
+            Self::JumpSlot => 5,
+            Self::TlsDtpMod32 => 6,
+            Self::TlsDtpMod64 => 7,
+            Self::TlsDtpRel32 => 8,
+            Self::TlsDtpRel64 => 9,
+            Self::TlsTpRel32 => 10,
+            Self::TlsTpRel64 => 11,
+            Self::Branch => 16,
+            Self::Jal => 17,
+            Self::Call => 18,
+            Self::CallPlt => 19,
+            Self::GotHi20 => 20,
+            Self::TlsGotHi20 => 21,
+            Self::TlsGdHi20 => 22,
+            Self::PcrelHi20 => 23,
+            Self::PcrelLo12I => 24,
+            Self::PcrelLo12S => 25,
+            Self::Hi20 => 26,
+            Self::Lo12I => 27,
+            Self::Lo12S => 28,
+            Self::TprelHi20 => 29,
+            Self::TprelLo12I => 30,
+            Self::TprelLo12S => 31,
+            Self::TprelAdd => 32,
+            Self::Add8 => 33,
+            Self::Add16 => 34,
+            Self::Add32 => 35,
+            Self::Add64 => 36,
+            Self::Sub8 => 37,
+            Self::Sub16 => 38,
+            Self::Sub32 => 39,
+            Self::Sub64 => 40,
+            Self::Align => 43,
+            Self::RvcBranch => 44,
+            Self::RvcJump => 45,
+            Self::Relax => 51,
+            Self::Sub6 => 52,
+            Self::Set6 => 53,
+            Self::Set8 => 54,
+            Self::Set16 => 55,
+            Self::Set32 => 56,
+            Self::Pcrel32 => 57,
+            Self::IRelative => 58,
+            Self::Any(c) => *c,
+        }
+    }
+
+    /// the name defined in the psABI like `R_RISCV_CALL_PLT`
+    pub fn name(&self) -> Option<&'static str> {
+        Some(match self {
+            Self::None => "R_RISCV_NONE",
+            Self::Abs32 => "R_RISCV_32",
+            Self::Abs64 => "R_RISCV_64",
+            Self::Relative => "R_RISCV_RELATIVE",
+            Self::Copy => "R_RISCV_COPY",
+            Self::JumpSlot => "R_RISCV_JUMP_SLOT",
+            Self::TlsDtpMod32 => "R_RISCV_TLS_DTPMOD32",
+            Self::TlsDtpMod64 => "R_RISCV_TLS_DTPMOD64",
+            Self::TlsDtpRel32 => "R_RISCV_TLS_DTPREL32",
+            Self::TlsDtpRel64 => "R_RISCV_TLS_DTPREL64",
+            Self::TlsTpRel32 => "R_RISCV_TLS_TPREL32",
+            Self::TlsTpRel64 => "R_RISCV_TLS_TPREL64",
+            Self::Branch => "R_RISCV_BRANCH",
+            Self::Jal => "R_RISCV_JAL",
+            Self::Call => "R_RISCV_CALL",
+            Self::CallPlt => "R_RISCV_CALL_PLT",
+            Self::GotHi20 => "R_RISCV_GOT_HI20",
+            Self::TlsGotHi20 => "R_RISCV_TLS_GOT_HI20",
+            Self::TlsGdHi20 => "R_RISCV_TLS_GD_HI20",
+            Self::PcrelHi20 => "R_RISCV_PCREL_HI20",
+            Self::PcrelLo12I => "R_RISCV_PCREL_LO12_I",
+            Self::PcrelLo12S => "R_RISCV_PCREL_LO12_S",
+            Self::Hi20 => "R_RISCV_HI20",
+            Self::Lo12I => "R_RISCV_LO12_I",
+            Self::Lo12S => "R_RISCV_LO12_S",
+            Self::TprelHi20 => "R_RISCV_TPREL_HI20",
+            Self::TprelLo12I => "R_RISCV_TPREL_LO12_I",
+            Self::TprelLo12S => "R_RISCV_TPREL_LO12_S",
+            Self::TprelAdd => "R_RISCV_TPREL_ADD",
+            Self::Add8 => "R_RISCV_ADD8",
+            Self::Add16 => "R_RISCV_ADD16",
+            Self::Add32 => "R_RISCV_ADD32",
+            Self::Add64 => "R_RISCV_ADD64",
+            Self::Sub8 => "R_RISCV_SUB8",
+            Self::Sub16 => "R_RISCV_SUB16",
+            Self::Sub32 => "R_RISCV_SUB32",
+            Self::Sub64 => "R_RISCV_SUB64",
+            Self::Align => "R_RISCV_ALIGN",
+            Self::RvcBranch => "R_RISCV_RVC_BRANCH",
+            Self::RvcJump => "R_RISCV_RVC_JUMP",
+            Self::Relax => "R_RISCV_RELAX",
+            Self::Sub6 => "R_RISCV_SUB6",
+            Self::Set6 => "R_RISCV_SET6",
+            Self::Set8 => "R_RISCV_SET8",
+            Self::Set16 => "R_RISCV_SET16",
+            Self::Set32 => "R_RISCV_SET32",
+            Self::Pcrel32 => "R_RISCV_32_PCREL",
+            Self::IRelative => "R_RISCV_IRELATIVE",
+            Self::Any(_) => return None,
+        })
+    }
+
+    /// the number of bytes to be modified at `r_offset`.
+    /// return `None` for XLEN-sized relocations(e.g. `R_RISCV_RELATIVE`)
+    /// because the size depends on the class.
+    pub fn size(&self) -> Option<usize> {
+        match self {
+            Self::None
+            | Self::Copy
+            | Self::Align
+            | Self::Relax
+            | Self::TprelAdd
+            | Self::Relative
+            | Self::JumpSlot
+            | Self::IRelative
+            | Self::Any(_) => None,
+            Self::Add8 | Self::Sub8 | Self::Sub6 | Self::Set6 | Self::Set8 => Some(1),
+            Self::Add16 | Self::Sub16 | Self::Set16 | Self::RvcBranch | Self::RvcJump => Some(2),
+            Self::Abs64
+            | Self::TlsDtpMod64
+            | Self::TlsDtpRel64
+            | Self::TlsTpRel64
+            | Self::Add64
+            | Self::Sub64
+            | Self::Call
+            | Self::CallPlt => Some(8),
+            _ => Some(4),
+        }
+    }
+
+    pub fn calculation(&self) -> Calculation {
+        match self {
+            Self::Abs32
+            | Self::Abs64
+            | Self::JumpSlot
+            | Self::Hi20
+            | Self::Lo12I
+            | Self::Lo12S
+            | Self::Set6
+            | Self::Set8
+            | Self::Set16
+            | Self::Set32 => Calculation::Absolute,
+            Self::Branch
+            | Self::Jal
+            | Self::Call
+            | Self::CallPlt
+            | Self::GotHi20
+            | Self::TlsGotHi20
+            | Self::TlsGdHi20
+            | Self::PcrelHi20
+            | Self::PcrelLo12I
+            | Self::PcrelLo12S
+            | Self::RvcBranch
+            | Self::RvcJump
+            | Self::Pcrel32 => Calculation::PcRelative,
+            _ => Calculation::Other,
+        }
+    }
+}
+
+impl From<u32> for RiscVType {
+    fn from(bytes: u32) -> Self {
+        match bytes {
+            0 => Self::None,
+            1 => Self::Abs32,
+            2 => Self::Abs64,
+            3 => Self::Relative,
+            4 => Self::Copy,
+            5 => Self::JumpSlot,
+            6 => Self::TlsDtpMod32,
+            7 => Self::TlsDtpMod64,
+            8 => Self::TlsDtpRel32,
+            9 => Self::TlsDtpRel64,
+            10 => Self::TlsTpRel32,
+            11 => Self::TlsTpRel64,
+            16 => Self::Branch,
+            17 => Self::Jal,
+            18 => Self::Call,
+            19 => Self::CallPlt,
+            20 => Self::GotHi20,
+            21 => Self::TlsGotHi20,
+            22 => Self::TlsGdHi20,
+            23 => Self::PcrelHi20,
+            24 => Self::PcrelLo12I,
+            25 => Self::PcrelLo12S,
+            26 => Self::Hi20,
+            27 => Self::Lo12I,
+            28 => Self::Lo12S,
+            29 => Self::TprelHi20,
+            30 => Self::TprelLo12I,
+            31 => Self::TprelLo12S,
+            32 => Self::TprelAdd,
+            33 => Self::Add8,
+            34 => Self::Add16,
+            35 => Self::Add32,
+            36 => Self::Add64,
+            37 => Self::Sub8,
+            38 => Self::Sub16,
+            39 => Self::Sub32,
+            40 => Self::Sub64,
+            43 => Self::Align,
+            44 => Self::RvcBranch,
+            45 => Self::RvcJump,
+            51 => Self::Relax,
+            52 => Self::Sub6,
+            53 => Self::Set6,
+            54 => Self::Set8,
+            55 => Self::Set16,
+            56 => Self::Set32,
+            57 => Self::Pcrel32,
+            58 => Self::IRelative,
+            _ => Self::Any(bytes),
+        }
+    }
+}
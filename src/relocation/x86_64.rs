@@ -0,0 +1,251 @@
+use crate::relocation::Calculation;
+
+/// Relocation types for x86_64(`R_X86_64_*`)
+#[derive(Debug, Clone, Copy, Hash, Eq, Ord, PartialEq, PartialOrd)]
+pub enum X8664Type {
+    /// No reloc
+    None,
+    /// Direct 64 bit
+    Abs64,
+    /// PC relative 32 bit signed
+    Pc32,
+    /// 32 bit GOT entry
+    Got32,
+    /// 32 bit PLT address
+    Plt32,
+    /// Copy symbol at runtime
+    Copy,
+    /// Create GOT entry
+    GlobDat,
+    /// Create PLT entry
+    JumpSlot,
+    /// Adjust by program base
+    Relative,
+    /// 32 bit signed PC relative offset to GOT
+    GotPcRel,
+    /// Direct 32 bit zero extended
+    Abs32,
+    /// Direct 32 bit sign extended
+    Abs32S,
+    /// Direct 16 bit zero extended
+    Abs16,
+    /// 16 bit sign extended pc relative
+    Pc16,
+    /// Direct 8 bit sign extended
+    Abs8,
+    /// 8 bit sign extended pc relative
+    Pc8,
+    /// ID of module containing symbol
+    DtpMod64,
+    /// Offset in module's TLS block
+    DtpOff64,
+    /// Offset in initial TLS block
+    TpOff64,
+    /// 32 bit signed PC relative offset to two GOT entries for GD symbol
+    TlsGd,
+    /// 32 bit signed PC relative offset to two GOT entries for LD symbol
+    TlsLd,
+    /// Offset in TLS block
+    DtpOff32,
+    /// 32 bit signed PC relative offset to GOT entry for IE symbol
+    GotTpOff,
+    /// Offset in initial TLS block
+    TpOff32,
+    /// PC relative 64 bit
+    Pc64,
+    /// 64 bit offset to GOT
+    GotOff64,
+    /// 32 bit signed pc relative offset to GOT
+    GotPc32,
+    /// Size of symbol plus 32-bit addend
+    Size32,
+    /// Size of symbol plus 64-bit addend
+    Size64,
+    /// Adjust indirectly by program base
+    IRelative,
+    /// Load from 32 bit signed pc relative offset to GOT entry without REX prefix, relaxable
+    GotPcRelX,
+    /// Load from 32 bit signed pc relative offset to GOT entry with REX prefix, relaxable
+    RexGotPcRelX,
+    /// User defined value
+    Any(u32),
+}
+
+impl X8664Type {
+    pub fn to_bytes(&self) -> u32 {
+        match self {
+            Self::None => 0,
+            Self::Abs64 => 1,
+            Self::Pc32 => 2,
+            Self::Got32 => 3,
+            Self::Plt32 => 4,
+            Self::Copy => 5,
+            Self::GlobDat => 6,
+            Self::JumpSlot => 7,
+            Self::Relative => 8,
+            Self::GotPcRel => 9,
+            Self::Abs32 => 10,
+            Self::Abs32S => 11,
+            Self::Abs16 => 12,
+            Self::Pc16 => 13,
+            Self::Abs8 => 14,
+            Self::Pc8 => 15,
+            Self::DtpMod64 => 16,
+            Self::DtpOff64 => 17,
+            Self::TpOff64 => 18,
+            Self::TlsGd => 19,
+            Self::TlsLd => 20,
+            Self::DtpOff32 => 21,
+            Self::GotTpOff => 22,
+            Self::TpOff32 => 23,
+            Self::Pc64 => 24,
+            Self::GotOff64 => 25,
+            Self::GotPc32 => 26,
+            Self::Size32 => 32,
+            Self::Size64 => 33,
+            Self::IRelative => 37,
+            Self::GotPcRelX => 41,
+            Self::RexGotPcRelX => 42,
+            Self::Any(c) => *c,
+        }
+    }
+
+    /// the name defined in the psABI like `R_X86_64_PC32`
+    pub fn name(&self) -> Option<&'static str> {
+        Some(match self {
+            Self::None => "R_X86_64_NONE",
+            Self::Abs64 => "R_X86_64_64",
+            Self::Pc32 => "R_X86_64_PC32",
+            Self::Got32 => "R_X86_64_GOT32",
+            Self::Plt32 => "R_X86_64_PLT32",
+            Self::Copy => "R_X86_64_COPY",
+            Self::GlobDat => "R_X86_64_GLOB_DAT",
+            Self::JumpSlot => "R_X86_64_JUMP_SLOT",
+            Self::Relative => "R_X86_64_RELATIVE",
+            Self::GotPcRel => "R_X86_64_GOTPCREL",
+            Self::Abs32 => "R_X86_64_32",
+            Self::Abs32S => "R_X86_64_32S",
+            Self::Abs16 => "R_X86_64_16",
+            Self::Pc16 => "R_X86_64_PC16",
+            Self::Abs8 => "R_X86_64_8",
+            Self::Pc8 => "R_X86_64_PC8",
+            Self::DtpMod64 => "R_X86_64_DTPMOD64",
+            Self::DtpOff64 => "R_X86_64_DTPOFF64",
+            Self::TpOff64 => "R_X86_64_TPOFF64",
+            Self::TlsGd => "R_X86_64_TLSGD",
+            Self::TlsLd => "R_X86_64_TLSLD",
+            Self::DtpOff32 => "R_X86_64_DTPOFF32",
+            Self::GotTpOff => "R_X86_64_GOTTPOFF",
+            Self::TpOff32 => "R_X86_64_TPOFF32",
+            Self::Pc64 => "R_X86_64_PC64",
+            Self::GotOff64 => "R_X86_64_GOTOFF64",
+            Self::GotPc32 => "R_X86_64_GOTPC32",
+            Self::Size32 => "R_X86_64_SIZE32",
+            Self::Size64 => "R_X86_64_SIZE64",
+            Self::IRelative => "R_X86_64_IRELATIVE",
+            Self::GotPcRelX => "R_X86_64_GOTPCRELX",
+            Self::RexGotPcRelX => "R_X86_64_REX_GOTPCRELX",
+            Self::Any(_) => return None,
+        })
+    }
+
+    /// the number of bytes to be modified at `r_offset`
+    pub fn size(&self) -> Option<usize> {
+        match self {
+            Self::None | Self::Copy | Self::Any(_) => None,
+            Self::Abs8 | Self::Pc8 => Some(1),
+            Self::Abs16 | Self::Pc16 => Some(2),
+            Self::Pc32
+            | Self::Got32
+            | Self::Plt32
+            | Self::GotPcRel
+            | Self::Abs32
+            | Self::Abs32S
+            | Self::TlsGd
+            | Self::TlsLd
+            | Self::DtpOff32
+            | Self::GotTpOff
+            | Self::TpOff32
+            | Self::GotPc32
+            | Self::Size32
+            | Self::GotPcRelX
+            | Self::RexGotPcRelX => Some(4),
+            Self::Abs64
+            | Self::GlobDat
+            | Self::JumpSlot
+            | Self::Relative
+            | Self::DtpMod64
+            | Self::DtpOff64
+            | Self::TpOff64
+            | Self::Pc64
+            | Self::GotOff64
+            | Self::Size64
+            | Self::IRelative => Some(8),
+        }
+    }
+
+    pub fn calculation(&self) -> Calculation {
+        match self {
+            Self::Abs64
+            | Self::GlobDat
+            | Self::JumpSlot
+            | Self::Abs32
+            | Self::Abs32S
+            | Self::Abs16
+            | Self::Abs8 => Calculation::Absolute,
+            Self::Pc32
+            | Self::Plt32
+            | Self::GotPcRel
+            | Self::Pc16
+            | Self::Pc8
+            | Self::TlsGd
+            | Self::TlsLd
+            | Self::GotTpOff
+            | Self::Pc64
+            | Self::GotPc32
+            | Self::GotPcRelX
+            | Self::RexGotPcRelX => Calculation::PcRelative,
+            _ => Calculation::Other,
+        }
+    }
+}
+
+impl From<u32> for X8664Type {
+    fn from(bytes: u32) -> Self {
+        match bytes {
+            0 => Self::None,
+            1 => Self::Abs64,
+            2 => Self::Pc32,
+            3 => Self::Got32,
+            4 => Self::Plt32,
+            5 => Self::Copy,
+            6 => Self::GlobDat,
+            7 => Self::JumpSlot,
+            8 => Self::Relative,
+            9 => Self::GotPcRel,
+            10 => Self::Abs32,
+            11 => Self::Abs32S,
+            12 => Self::Abs16,
+            13 => Self::Pc16,
+            14 => Self::Abs8,
+            15 => Self::Pc8,
+            16 => Self::DtpMod64,
+            17 => Self::DtpOff64,
+            18 => Self::TpOff64,
+            19 => Self::TlsGd,
+            20 => Self::TlsLd,
+            21 => Self::DtpOff32,
+            22 => Self::GotTpOff,
+            23 => Self::TpOff32,
+            24 => Self::Pc64,
+            25 => Self::GotOff64,
+            26 => Self::GotPc32,
+            32 => Self::Size32,
+            33 => Self::Size64,
+            37 => Self::IRelative,
+            41 => Self::GotPcRelX,
+            42 => Self::RexGotPcRelX,
+            _ => Self::Any(bytes),
+        }
+    }
+}
@@ -4,17 +4,23 @@
 pub use base::*;
 pub use elf32::*;
 pub use elf64::*;
+pub use group::*;
+pub use hash::*;
 pub use section_flag::*;
 pub use section_type::*;
 
 mod base;
 mod elf32;
 mod elf64;
+mod group;
+mod hash;
 mod section_flag;
 mod section_type;
 
 /// Undefined section
 pub const SHN_UNDEF: u16 = 0;
+/// Start of reserved indices
+pub const SHN_LORESERVE: u16 = 0xff00;
 /// Start of processor-specific
 pub const SHN_LOPROC: u16 = 0xff00;
 /// End of processor-specific
@@ -20,12 +20,20 @@ pub(crate) enum Contents {
     Contents32(Contents32),
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Default, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct StrTabEntry {
     pub v: String,
     pub idx: usize,
 }
 
+/// the string which starts at `idx` in the string table.
+/// `idx` may point into the middle of an entry(suffix sharing).
+pub fn string_at(strs: &[StrTabEntry], idx: usize) -> Option<&str> {
+    strs.iter()
+        .find(|s| s.idx <= idx && idx <= s.idx + s.v.len())
+        .and_then(|s| s.v.get(idx - s.idx..))
+}
+
 impl Section {
     pub fn new(hdr: Shdr) -> Self {
         let is_64bit = matches!(hdr, Shdr::Shdr64(_));
@@ -91,6 +99,18 @@ impl Section {
             Shdr::Shdr64(shdr) => shdr.sh_link as usize,
         }
     }
+    pub fn info(&self) -> usize {
+        match self.header {
+            Shdr::Shdr32(shdr) => shdr.sh_info as usize,
+            Shdr::Shdr64(shdr) => shdr.sh_info as usize,
+        }
+    }
+    pub fn addralign(&self) -> u64 {
+        match self.header {
+            Shdr::Shdr32(shdr) => shdr.sh_addralign as u64,
+            Shdr::Shdr64(shdr) => shdr.sh_addralign,
+        }
+    }
 }
 
 impl Contents {
@@ -106,17 +126,20 @@ impl Contents {
             _ => unreachable!(),
         }
     }
-    pub fn as_strtab(&self) -> Vec<StrTabEntry> {
-        match self {
-            Contents::Contents32(contents) => match contents {
-                Contents32::StrTab(v) => v.clone(),
-                _ => unreachable!(),
-            },
-            Contents::Contents64(contents) => match contents {
-                Contents64::StrTab(v) => v.clone(),
-                _ => unreachable!(),
-            },
-        }
+    /// whether the contents have names in the string table linked by `sh_link`
+    pub fn has_linked_names(&self) -> bool {
+        matches!(
+            self,
+            Contents::Contents32(
+                Contents32::Symbols(_)
+                    | Contents32::VersionDefinitions(_)
+                    | Contents32::VersionRequirements(_)
+            ) | Contents::Contents64(
+                Contents64::Symbols(_)
+                    | Contents64::VersionDefinitions(_)
+                    | Contents64::VersionRequirements(_)
+            )
+        )
     }
 }
 
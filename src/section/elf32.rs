@@ -19,8 +19,26 @@ pub enum Contents32 {
     Symbols(Vec<symbol::Symbol32>),
     /// relocation symbol table's representation
     RelaSymbols(Vec<relocation::Rela32>),
+    /// relocation symbol table without addends(`SHT_REL`)
+    RelSymbols(Vec<relocation::Rel32>),
     /// dynamic information's representation
     Dynamics(Vec<dynamic::Dyn32>),
+    /// extended section indices of symbols(`SHT_SYMTAB_SHNDX`)
+    SymTabShNdx(Vec<Elf32Word>),
+    /// note entries(`SHT_NOTE`)
+    Notes(note::NoteTable),
+    /// section group(`SHT_GROUP`)
+    Group(section::Group),
+    /// SysV hash table(`SHT_HASH`)
+    SysvHash(section::SysvHash),
+    /// GNU hash table(`SHT_GNU_HASH`)
+    GnuHash(section::GnuHash),
+    /// version indices of dynamic symbols(`.gnu.version`)
+    VersionSymbols(Vec<Elf32Versym>),
+    /// version definitions(`.gnu.version_d`)
+    VersionDefinitions(Vec<version::VersionDefinition>),
+    /// version requirements(`.gnu.version_r`)
+    VersionRequirements(Vec<version::VersionRequirement>),
 }
 
 #[derive(Default, Clone, Hash, PartialOrd, Ord, PartialEq, Eq)]
@@ -30,7 +48,9 @@ pub struct Section32 {
     pub contents: Contents32,
 }
 
-#[derive(Debug, Clone, Copy, Hash, PartialOrd, Ord, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(
+    Default, Debug, Clone, Copy, Hash, PartialOrd, Ord, PartialEq, Eq, Serialize, Deserialize,
+)]
 #[repr(C)]
 pub struct Shdr32 {
     /// Section name, index in string tbl
@@ -104,7 +124,16 @@ impl Contents32 {
             Contents32::RelaSymbols(rela_syms) => {
                 relocation::Rela32::SIZE as usize * rela_syms.len()
             }
+            Contents32::RelSymbols(rel_syms) => relocation::Rel32::SIZE as usize * rel_syms.len(),
             Contents32::Dynamics(dyn_info) => dynamic::Dyn32::SIZE * dyn_info.len(),
+            Contents32::SymTabShNdx(indices) => 4 * indices.len(),
+            Contents32::Notes(table) => table.size(),
+            Contents32::Group(group) => group.size(),
+            Contents32::VersionSymbols(versyms) => 2 * versyms.len(),
+            Contents32::VersionDefinitions(defs) => defs.iter().map(|d| d.size()).sum(),
+            Contents32::VersionRequirements(reqs) => reqs.iter().map(|r| r.size()).sum(),
+            Contents32::SysvHash(table) => table.size(),
+            Contents32::GnuHash(table) => table.size(header::Class::Bit32),
         }
     }
 
@@ -126,23 +155,6 @@ impl Contents32 {
     }
 }
 
-impl Default for Shdr32 {
-    fn default() -> Self {
-        Self {
-            sh_name: 0,
-            sh_type: 0,
-            sh_flags: 0,
-            sh_addr: 0,
-            sh_offset: 0,
-            sh_size: 0,
-            sh_link: 0,
-            sh_info: 0,
-            sh_addralign: 0,
-            sh_entsize: 0,
-        }
-    }
-}
-
 impl Section32 {
     pub fn new(name: String, hdr: ShdrPreparation32, contents: Contents32) -> Self {
         Self {
@@ -154,6 +166,11 @@ impl Section32 {
 
     /// create binary without header
     pub fn to_le_bytes(&self) -> Vec<u8> {
+        self.to_bytes(header::Data::LSB2)
+    }
+
+    /// create binary without header with the given data encoding
+    pub fn to_bytes(&self, data: header::Data) -> Vec<u8> {
         match &self.contents {
             Contents32::Raw(bytes) => bytes.clone(),
             Contents32::StrTab(strs) => {
@@ -173,21 +190,52 @@ impl Section32 {
             Contents32::Symbols(syms) => {
                 let mut bytes = Vec::new();
                 for sym in syms.iter() {
-                    bytes.append(&mut sym.to_le_bytes());
+                    bytes.append(&mut sym.to_bytes(data));
                 }
                 bytes
             }
             Contents32::RelaSymbols(rela_syms) => {
                 let mut bytes = Vec::new();
                 for sym in rela_syms.iter() {
-                    bytes.append(&mut sym.to_le_bytes());
+                    bytes.append(&mut sym.to_bytes(data));
+                }
+                bytes
+            }
+            Contents32::RelSymbols(rel_syms) => {
+                let mut bytes = Vec::new();
+                for sym in rel_syms.iter() {
+                    bytes.append(&mut sym.to_bytes(data));
                 }
                 bytes
             }
             Contents32::Dynamics(dynamics) => {
                 let mut bytes = Vec::new();
                 for sym in dynamics.iter() {
-                    bytes.append(&mut sym.to_le_bytes());
+                    bytes.append(&mut sym.to_bytes(data));
+                }
+                bytes
+            }
+            Contents32::Notes(table) => table.to_bytes(data),
+            Contents32::Group(group) => group.to_bytes(data),
+            Contents32::VersionSymbols(versyms) => {
+                let mut bytes = Vec::new();
+                for versym in versyms.iter() {
+                    bytes.append(&mut data.serialize(versym));
+                }
+                bytes
+            }
+            Contents32::VersionDefinitions(defs) => {
+                version::VersionDefinition::table_to_bytes(defs, data)
+            }
+            Contents32::VersionRequirements(reqs) => {
+                version::VersionRequirement::table_to_bytes(reqs, data)
+            }
+            Contents32::SysvHash(table) => table.to_bytes(data),
+            Contents32::GnuHash(table) => table.to_bytes(data, header::Class::Bit32),
+            Contents32::SymTabShNdx(indices) => {
+                let mut bytes = Vec::new();
+                for idx in indices.iter() {
+                    bytes.append(&mut data.serialize(idx));
                 }
                 bytes
             }
@@ -197,6 +245,69 @@ impl Section32 {
     pub fn new_null_section() -> Self {
         Default::default()
     }
+
+    /// create a version symbol section(`.gnu.version`) linked with the dynamic symbol table
+    pub fn new_version_symbols_section(
+        name: String,
+        dynsym_idx: usize,
+        versyms: Vec<Elf32Versym>,
+    ) -> Self {
+        let hdr = ShdrPreparation32 {
+            sh_addralign: 2,
+            ..Default::default()
+        }
+        .ty(section::Type::GNUVerSym)
+        .flags([section::Flag::Alloc].iter())
+        .link(dynsym_idx as Elf32Word);
+        let mut sct = Self::new(name, hdr, Contents32::VersionSymbols(versyms));
+        sct.header.sh_entsize = 2;
+        sct
+    }
+
+    /// create a version definition section(`.gnu.version_d`) linked with the dynamic string table
+    pub fn new_version_definitions_section(
+        name: String,
+        dynstr_idx: usize,
+        defs: Vec<version::VersionDefinition>,
+    ) -> Self {
+        let hdr = ShdrPreparation32 {
+            sh_addralign: 4,
+            ..Default::default()
+        }
+        .ty(section::Type::GNUVerDef)
+        .flags([section::Flag::Alloc].iter())
+        .link(dynstr_idx as Elf32Word)
+        .info(defs.len() as Elf32Word);
+        Self::new(name, hdr, Contents32::VersionDefinitions(defs))
+    }
+
+    /// create a version requirement section(`.gnu.version_r`) linked with the dynamic string table
+    pub fn new_version_requirements_section(
+        name: String,
+        dynstr_idx: usize,
+        reqs: Vec<version::VersionRequirement>,
+    ) -> Self {
+        let hdr = ShdrPreparation32 {
+            sh_addralign: 4,
+            ..Default::default()
+        }
+        .ty(section::Type::GNUVerNeed)
+        .flags([section::Flag::Alloc].iter())
+        .link(dynstr_idx as Elf32Word)
+        .info(reqs.len() as Elf32Word);
+        Self::new(name, hdr, Contents32::VersionRequirements(reqs))
+    }
+
+    /// create an allocated note section(`SHT_NOTE`) aligned by `table.align`
+    pub fn new_note_section(name: String, table: note::NoteTable) -> Self {
+        let hdr = ShdrPreparation32 {
+            sh_addralign: table.align as Elf32Word,
+            ..Default::default()
+        }
+        .ty(section::Type::Note)
+        .flags([section::Flag::Alloc].iter());
+        Self::new(name, hdr, Contents32::Notes(table))
+    }
 }
 
 #[allow(dead_code)]
@@ -207,19 +318,12 @@ impl Shdr32 {
         section::Type::from(self.sh_type)
     }
     pub fn get_flags(&self) -> HashSet<section::Flag> {
-        let mut mask: Elf32Word = 0b1;
-        let mut flags = HashSet::new();
-        loop {
-            if mask == 0 {
-                break;
-            }
-            if self.sh_flags & mask != 0 {
-                flags.insert(section::Flag::from(mask));
-            }
-            mask <<= 1;
-        }
-
-        flags
+        // SHF_EXCLUDE等，表現できないビットは無視する
+        section::Flag::ALL
+            .iter()
+            .filter(|flag| self.sh_flags & Elf32Word::from(**flag) != 0)
+            .copied()
+            .collect()
     }
     // setter
     pub fn set_type(&mut self, ty: section::Type) {
@@ -230,7 +334,7 @@ impl Shdr32 {
         I: Iterator<Item = &'a section::Flag>,
     {
         for flag in flags {
-            self.sh_flags = self.sh_flags | Into::<Elf32Word>::into(*flag);
+            self.sh_flags |= Into::<Elf32Word>::into(*flag);
         }
     }
 
@@ -245,7 +349,12 @@ impl Shdr32 {
     /// assert_eq!([0].repeat(Shdr32::SIZE), null_sct.to_le_bytes());
     /// ```
     pub fn to_le_bytes(&self) -> Vec<u8> {
-        bincode::serialize(self).unwrap()
+        self.to_bytes(header::Data::LSB2)
+    }
+
+    /// Create Vec<u8> from this with the given data encoding.
+    pub fn to_bytes(&self, data: header::Data) -> Vec<u8> {
+        data.serialize(self)
     }
 }
 
@@ -287,18 +396,18 @@ impl Default for ShdrPreparation32 {
         }
     }
 }
-impl Into<Shdr32> for ShdrPreparation32 {
-    fn into(self) -> Shdr32 {
+impl From<ShdrPreparation32> for Shdr32 {
+    fn from(prep: ShdrPreparation32) -> Self {
         Shdr32 {
             sh_name: 0,
-            sh_type: self.sh_type.into(),
-            sh_flags: self.sh_flags,
+            sh_type: prep.sh_type.into(),
+            sh_flags: prep.sh_flags,
             sh_addr: 0,
             sh_offset: 0,
             sh_size: 0,
-            sh_link: self.sh_link,
-            sh_info: self.sh_info,
-            sh_addralign: self.sh_addralign,
+            sh_link: prep.sh_link,
+            sh_info: prep.sh_info,
+            sh_addralign: prep.sh_addralign,
             sh_entsize: 0,
         }
     }
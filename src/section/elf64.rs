@@ -17,8 +17,26 @@ pub enum Contents64 {
     Symbols(Vec<symbol::Symbol64>),
     /// relocation symbol table
     RelaSymbols(Vec<relocation::Rela64>),
+    /// relocation symbol table without addends(`SHT_REL`)
+    RelSymbols(Vec<relocation::Rel64>),
     /// dynamic information
     Dynamics(Vec<dynamic::Dyn64>),
+    /// extended section indices of symbols(`SHT_SYMTAB_SHNDX`)
+    SymTabShNdx(Vec<Elf64Word>),
+    /// note entries(`SHT_NOTE`)
+    Notes(note::NoteTable),
+    /// section group(`SHT_GROUP`)
+    Group(section::Group),
+    /// SysV hash table(`SHT_HASH`)
+    SysvHash(section::SysvHash),
+    /// GNU hash table(`SHT_GNU_HASH`)
+    GnuHash(section::GnuHash),
+    /// version indices of dynamic symbols(`.gnu.version`)
+    VersionSymbols(Vec<Elf64Versym>),
+    /// version definitions(`.gnu.version_d`)
+    VersionDefinitions(Vec<version::VersionDefinition>),
+    /// version requirements(`.gnu.version_r`)
+    VersionRequirements(Vec<version::VersionRequirement>),
     /// String Table
     StrTab(Vec<StrTabEntry>),
 }
@@ -31,7 +49,9 @@ pub struct Section64 {
     pub contents: Contents64,
 }
 
-#[derive(Debug, Clone, Copy, Hash, PartialOrd, Ord, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(
+    Default, Debug, Clone, Copy, Hash, PartialOrd, Ord, PartialEq, Eq, Serialize, Deserialize,
+)]
 #[repr(C)]
 pub struct Shdr64 {
     /// Section name, index in string tbl
@@ -86,23 +106,6 @@ pub struct ShdrPreparation64 {
     pub sh_addralign: Elf64Xword,
 }
 
-impl Default for Shdr64 {
-    fn default() -> Self {
-        Self {
-            sh_name: 0,
-            sh_type: 0,
-            sh_flags: 0,
-            sh_addr: 0,
-            sh_offset: 0,
-            sh_size: 0,
-            sh_link: 0,
-            sh_info: 0,
-            sh_addralign: 0,
-            sh_entsize: 0,
-        }
-    }
-}
-
 #[allow(dead_code)]
 impl Shdr64 {
     pub const SIZE: usize = 0x40;
@@ -112,19 +115,12 @@ impl Shdr64 {
         section::Type::from(self.sh_type)
     }
     pub fn get_flags(&self) -> HashSet<section::Flag> {
-        let mut mask: Elf64Xword = 0b1;
-        let mut flags = HashSet::new();
-        loop {
-            if mask == 0 {
-                break;
-            }
-            if self.sh_flags & mask != 0 {
-                flags.insert(section::Flag::from(mask));
-            }
-            mask <<= 1;
-        }
-
-        flags
+        // SHF_EXCLUDE等，表現できないビットは無視する
+        section::Flag::ALL
+            .iter()
+            .filter(|flag| self.sh_flags & Elf64Xword::from(**flag) != 0)
+            .copied()
+            .collect()
     }
 
     // setter
@@ -136,7 +132,7 @@ impl Shdr64 {
         I: Iterator<Item = &'a section::Flag>,
     {
         for flag in flags {
-            self.sh_flags = self.sh_flags | Into::<Elf64Xword>::into(*flag);
+            self.sh_flags |= Into::<Elf64Xword>::into(*flag);
         }
     }
 
@@ -151,7 +147,12 @@ impl Shdr64 {
     /// assert_eq!([0].repeat(Shdr64::SIZE), null_sct.to_le_bytes());
     /// ```
     pub fn to_le_bytes(&self) -> Vec<u8> {
-        bincode::serialize(self).unwrap()
+        self.to_bytes(header::Data::LSB2)
+    }
+
+    /// Create Vec<u8> from this with the given data encoding.
+    pub fn to_bytes(&self, data: header::Data) -> Vec<u8> {
+        data.serialize(self)
     }
 }
 
@@ -164,6 +165,69 @@ impl Section64 {
         }
     }
 
+    /// create a version symbol section(`.gnu.version`) linked with the dynamic symbol table
+    pub fn new_version_symbols_section(
+        name: String,
+        dynsym_idx: usize,
+        versyms: Vec<Elf64Versym>,
+    ) -> Self {
+        let hdr = ShdrPreparation64 {
+            sh_addralign: 2,
+            ..Default::default()
+        }
+        .ty(section::Type::GNUVerSym)
+        .flags([section::Flag::Alloc].iter())
+        .link(dynsym_idx as Elf64Word);
+        let mut sct = Self::new(name, hdr, Contents64::VersionSymbols(versyms));
+        sct.header.sh_entsize = 2;
+        sct
+    }
+
+    /// create a version definition section(`.gnu.version_d`) linked with the dynamic string table
+    pub fn new_version_definitions_section(
+        name: String,
+        dynstr_idx: usize,
+        defs: Vec<version::VersionDefinition>,
+    ) -> Self {
+        let hdr = ShdrPreparation64 {
+            sh_addralign: 8,
+            ..Default::default()
+        }
+        .ty(section::Type::GNUVerDef)
+        .flags([section::Flag::Alloc].iter())
+        .link(dynstr_idx as Elf64Word)
+        .info(defs.len() as Elf64Word);
+        Self::new(name, hdr, Contents64::VersionDefinitions(defs))
+    }
+
+    /// create a version requirement section(`.gnu.version_r`) linked with the dynamic string table
+    pub fn new_version_requirements_section(
+        name: String,
+        dynstr_idx: usize,
+        reqs: Vec<version::VersionRequirement>,
+    ) -> Self {
+        let hdr = ShdrPreparation64 {
+            sh_addralign: 8,
+            ..Default::default()
+        }
+        .ty(section::Type::GNUVerNeed)
+        .flags([section::Flag::Alloc].iter())
+        .link(dynstr_idx as Elf64Word)
+        .info(reqs.len() as Elf64Word);
+        Self::new(name, hdr, Contents64::VersionRequirements(reqs))
+    }
+
+    /// create an allocated note section(`SHT_NOTE`) aligned by `table.align`
+    pub fn new_note_section(name: String, table: note::NoteTable) -> Self {
+        let hdr = ShdrPreparation64 {
+            sh_addralign: table.align as Elf64Xword,
+            ..Default::default()
+        }
+        .ty(section::Type::Note)
+        .flags([section::Flag::Alloc].iter());
+        Self::new(name, hdr, Contents64::Notes(table))
+    }
+
     pub fn new(name: String, hdr: ShdrPreparation64, contents: Contents64) -> Self {
         Self {
             contents,
@@ -174,6 +238,11 @@ impl Section64 {
 
     /// create binary without header
     pub fn to_le_bytes(&self) -> Vec<u8> {
+        self.to_bytes(header::Data::LSB2)
+    }
+
+    /// create binary without header with the given data encoding
+    pub fn to_bytes(&self, data: header::Data) -> Vec<u8> {
         match &self.contents {
             Contents64::Raw(bytes) => bytes.clone(),
             Contents64::StrTab(strs) => {
@@ -193,21 +262,52 @@ impl Section64 {
             Contents64::Symbols(syms) => {
                 let mut bytes = Vec::new();
                 for sym in syms.iter() {
-                    bytes.append(&mut sym.to_le_bytes());
+                    bytes.append(&mut sym.to_bytes(data));
                 }
                 bytes
             }
             Contents64::RelaSymbols(rela_syms) => {
                 let mut bytes = Vec::new();
                 for sym in rela_syms.iter() {
-                    bytes.append(&mut sym.to_le_bytes());
+                    bytes.append(&mut sym.to_bytes(data));
+                }
+                bytes
+            }
+            Contents64::RelSymbols(rel_syms) => {
+                let mut bytes = Vec::new();
+                for sym in rel_syms.iter() {
+                    bytes.append(&mut sym.to_bytes(data));
                 }
                 bytes
             }
             Contents64::Dynamics(dynamics) => {
                 let mut bytes = Vec::new();
                 for sym in dynamics.iter() {
-                    bytes.append(&mut sym.to_le_bytes());
+                    bytes.append(&mut sym.to_bytes(data));
+                }
+                bytes
+            }
+            Contents64::Notes(table) => table.to_bytes(data),
+            Contents64::Group(group) => group.to_bytes(data),
+            Contents64::VersionSymbols(versyms) => {
+                let mut bytes = Vec::new();
+                for versym in versyms.iter() {
+                    bytes.append(&mut data.serialize(versym));
+                }
+                bytes
+            }
+            Contents64::VersionDefinitions(defs) => {
+                version::VersionDefinition::table_to_bytes(defs, data)
+            }
+            Contents64::VersionRequirements(reqs) => {
+                version::VersionRequirement::table_to_bytes(reqs, data)
+            }
+            Contents64::SysvHash(table) => table.to_bytes(data),
+            Contents64::GnuHash(table) => table.to_bytes(data, header::Class::Bit64),
+            Contents64::SymTabShNdx(indices) => {
+                let mut bytes = Vec::new();
+                for idx in indices.iter() {
+                    bytes.append(&mut data.serialize(idx));
                 }
                 bytes
             }
@@ -254,18 +354,18 @@ impl Default for ShdrPreparation64 {
     }
 }
 
-impl Into<Shdr64> for ShdrPreparation64 {
-    fn into(self) -> Shdr64 {
+impl From<ShdrPreparation64> for Shdr64 {
+    fn from(prep: ShdrPreparation64) -> Self {
         Shdr64 {
             sh_name: 0,
-            sh_type: self.sh_type.into(),
-            sh_flags: self.sh_flags,
+            sh_type: prep.sh_type.into(),
+            sh_flags: prep.sh_flags,
             sh_addr: 0,
             sh_offset: 0,
             sh_size: 0,
-            sh_link: self.sh_link,
-            sh_info: self.sh_info,
-            sh_addralign: self.sh_addralign,
+            sh_link: prep.sh_link,
+            sh_info: prep.sh_info,
+            sh_addralign: prep.sh_addralign,
             sh_entsize: 0,
         }
     }
@@ -284,7 +384,16 @@ impl Contents64 {
             Contents64::RelaSymbols(rela_syms) => {
                 relocation::Rela64::SIZE as usize * rela_syms.len()
             }
+            Contents64::RelSymbols(rel_syms) => relocation::Rel64::SIZE as usize * rel_syms.len(),
             Contents64::Dynamics(dyn_info) => dynamic::Dyn64::SIZE * dyn_info.len(),
+            Contents64::SymTabShNdx(indices) => 4 * indices.len(),
+            Contents64::Notes(table) => table.size(),
+            Contents64::Group(group) => group.size(),
+            Contents64::VersionSymbols(versyms) => 2 * versyms.len(),
+            Contents64::VersionDefinitions(defs) => defs.iter().map(|d| d.size()).sum(),
+            Contents64::VersionRequirements(reqs) => reqs.iter().map(|r| r.size()).sum(),
+            Contents64::SysvHash(table) => table.size(),
+            Contents64::GnuHash(table) => table.size(header::Class::Bit64),
         }
     }
 
@@ -0,0 +1,47 @@
+//! Type definitions for section groups(`SHT_GROUP`).
+
+use crate::*;
+
+/// This is a COMDAT group
+pub const GRP_COMDAT: u32 = 0x1;
+/// OS-specific flags
+pub const GRP_MASKOS: u32 = 0x0ff00000;
+/// Processor-specific flags
+pub const GRP_MASKPROC: u32 = 0xf0000000;
+
+/// contents of a section group.
+/// the layout is an array of `Elf32Word`/`Elf64Word` in both of 32-bit and 64-bit ELF.
+///
+/// the signature symbol is specified by the group's header,
+/// `sh_link` is the index of the symbol table and `sh_info` is the symbol index in it.
+#[derive(Default, Debug, Clone, Hash, PartialOrd, Ord, PartialEq, Eq)]
+pub struct Group {
+    /// `GRP_COMDAT` etc.
+    pub flags: Elf64Word,
+    /// indices of member sections
+    pub sections: Vec<Elf64Word>,
+}
+
+impl Group {
+    pub const ENTRY_SIZE: usize = 4;
+
+    pub fn new(flags: Elf64Word, sections: Vec<Elf64Word>) -> Self {
+        Self { flags, sections }
+    }
+
+    pub fn is_comdat(&self) -> bool {
+        self.flags & GRP_COMDAT != 0
+    }
+
+    pub fn size(&self) -> usize {
+        Self::ENTRY_SIZE * (self.sections.len() + 1)
+    }
+
+    pub fn to_bytes(&self, data: header::Data) -> Vec<u8> {
+        let mut bytes = data.serialize(&self.flags);
+        for idx in self.sections.iter() {
+            bytes.append(&mut data.serialize(idx));
+        }
+        bytes
+    }
+}
@@ -0,0 +1,256 @@
+//! Type definitions for symbol hash tables(`.hash`, `.gnu.hash`).
+
+use crate::*;
+
+/// SysV hash table(`SHT_HASH`)
+#[derive(Default, Debug, Clone, Hash, PartialOrd, Ord, PartialEq, Eq)]
+pub struct SysvHash {
+    pub buckets: Vec<Elf64Word>,
+    /// `chains[sym_idx]` is the next symbol index in the same bucket
+    pub chains: Vec<Elf64Word>,
+}
+
+/// GNU hash table(`SHT_GNU_HASH`)
+///
+/// bloom filter words are `Elf64Xword` in 64-bit ELF and `Elf32Word` in 32-bit ELF,
+/// so encoding/decoding requires the ELF class.
+#[derive(Default, Debug, Clone, Hash, PartialOrd, Ord, PartialEq, Eq)]
+pub struct GnuHash {
+    /// index of the first symbol which is accessible via this table
+    pub symoffset: Elf64Word,
+    pub bloom_shift: Elf64Word,
+    pub bloom: Vec<Elf64Xword>,
+    pub buckets: Vec<Elf64Word>,
+    /// hash values of symbols from `symoffset`, the lowest bit marks the end of a chain
+    pub chains: Vec<Elf64Word>,
+}
+
+/// the hash function of SysV hash table
+pub fn sysv_hash(name: &str) -> u32 {
+    let mut h: u32 = 0;
+    for c in name.bytes() {
+        h = (h << 4).wrapping_add(c as u32);
+        let g = h & 0xf0000000;
+        if g != 0 {
+            h ^= g >> 24;
+        }
+        h &= !g;
+    }
+    h
+}
+
+/// the hash function of GNU hash table(djb2)
+pub fn gnu_hash(name: &str) -> u32 {
+    name.bytes()
+        .fold(5381u32, |h, c| h.wrapping_mul(33).wrapping_add(c as u32))
+}
+
+impl SysvHash {
+    pub fn size(&self) -> usize {
+        4 * (2 + self.buckets.len() + self.chains.len())
+    }
+
+    pub fn to_bytes(&self, data: header::Data) -> Vec<u8> {
+        let mut bytes = data.serialize(&(self.buckets.len() as Elf64Word));
+        bytes.append(&mut data.serialize(&(self.chains.len() as Elf64Word)));
+        for v in self.buckets.iter().chain(self.chains.iter()) {
+            bytes.append(&mut data.serialize(v));
+        }
+        bytes
+    }
+
+    /// decode the table from the section contents
+    pub fn parse(data: header::Data, buf: &[u8]) -> Option<Self> {
+        let nbucket = read_word(data, buf, 0)? as usize;
+        let nchain = read_word(data, buf, 1)? as usize;
+        Some(Self {
+            buckets: read_words(data, buf, 2, nbucket)?,
+            chains: read_words(data, buf, 2 + nbucket, nchain)?,
+        })
+    }
+
+    /// find the symbol index in the bucket of `name` which `matches` returns true for.
+    /// `matches` should compare the symbol name with `name`.
+    pub fn lookup<F>(&self, name: &str, matches: F) -> Option<usize>
+    where
+        F: Fn(usize) -> bool,
+    {
+        if self.buckets.is_empty() {
+            return None;
+        }
+
+        let mut sym_idx = self.buckets[sysv_hash(name) as usize % self.buckets.len()] as usize;
+        // STN_UNDEFでチェーンが終わる
+        while sym_idx != 0 {
+            if matches(sym_idx) {
+                return Some(sym_idx);
+            }
+            sym_idx = *self.chains.get(sym_idx)? as usize;
+        }
+
+        None
+    }
+}
+
+impl GnuHash {
+    pub fn size(&self, class: header::Class) -> usize {
+        16 + bloom_word_size(class) * self.bloom.len()
+            + 4 * (self.buckets.len() + self.chains.len())
+    }
+
+    pub fn to_bytes(&self, data: header::Data, class: header::Class) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.size(class));
+        for v in [
+            self.buckets.len() as Elf64Word,
+            self.symoffset,
+            self.bloom.len() as Elf64Word,
+            self.bloom_shift,
+        ] {
+            bytes.append(&mut data.serialize(&v));
+        }
+        for word in self.bloom.iter() {
+            match class {
+                header::Class::Bit32 => bytes.append(&mut data.serialize(&(*word as Elf32Word))),
+                _ => bytes.append(&mut data.serialize(word)),
+            }
+        }
+        for v in self.buckets.iter().chain(self.chains.iter()) {
+            bytes.append(&mut data.serialize(v));
+        }
+        bytes
+    }
+
+    /// decode the table from the section contents.
+    /// the chain array lasts until the end of `buf`.
+    pub fn parse(data: header::Data, class: header::Class, buf: &[u8]) -> Option<Self> {
+        let nbuckets = read_word(data, buf, 0)? as usize;
+        let symoffset = read_word(data, buf, 1)?;
+        let bloom_size = read_word(data, buf, 2)? as usize;
+        let bloom_shift = read_word(data, buf, 3)?;
+        // シフト量がbloom filterのワード幅以上のテーブルは不正
+        if bloom_shift >= 8 * bloom_word_size(class) as Elf64Word {
+            return None;
+        }
+
+        let word_size = bloom_word_size(class);
+        let bloom_bytes = buf.get(16..word_size.checked_mul(bloom_size)?.checked_add(16)?)?;
+        let bloom = bloom_bytes
+            .chunks_exact(word_size)
+            .map(|word| match class {
+                header::Class::Bit32 => data
+                    .deserialize::<Elf32Word>(word)
+                    .ok()
+                    .map(|w| w as Elf64Xword),
+                _ => data.deserialize::<Elf64Xword>(word).ok(),
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        // 以降は4バイト単位のテーブル
+        let rest = &buf[16 + bloom_bytes.len()..];
+        let buckets = read_words(data, rest, 0, nbuckets)?;
+        let chains = read_words(
+            data,
+            rest,
+            nbuckets,
+            (rest.len() / 4).checked_sub(nbuckets)?,
+        )?;
+
+        Some(Self {
+            symoffset,
+            bloom_shift,
+            bloom,
+            buckets,
+            chains,
+        })
+    }
+
+    /// find the symbol index in the chain of `name` which `matches` returns true for, like the dynamic linker.
+    /// `matches` should compare the symbol name with `name`.
+    pub fn lookup<F>(&self, class: header::Class, name: &str, matches: F) -> Option<usize>
+    where
+        F: Fn(usize) -> bool,
+    {
+        if self.buckets.is_empty() || self.bloom.is_empty() {
+            return None;
+        }
+
+        // bloom filterで存在しないシンボルを弾く
+        let h = gnu_hash(name);
+        let bits = 8 * bloom_word_size(class) as u32;
+        let word = self.bloom[(h / bits) as usize % self.bloom.len()];
+        // bloom_shiftはpubなので，ワード幅以上のシフトは見つからないものとする
+        let shifted = Elf64Xword::from(h).checked_shr(self.bloom_shift)?;
+        let mask: Elf64Xword = (1 << (h % bits)) | (1 << (shifted % Elf64Xword::from(bits)));
+        if word & mask != mask {
+            return None;
+        }
+
+        let mut sym_idx = self.buckets[h as usize % self.buckets.len()] as usize;
+        if sym_idx < self.symoffset as usize {
+            return None;
+        }
+        loop {
+            let chain_hash = *self.chains.get(sym_idx - self.symoffset as usize)?;
+            // 最下位ビットはチェーン終端のマークなので比較から除く
+            if (h | 1) == (chain_hash | 1) && matches(sym_idx) {
+                return Some(sym_idx);
+            }
+            if chain_hash & 1 != 0 {
+                return None;
+            }
+            sym_idx += 1;
+        }
+    }
+}
+
+/// the number of symbols covered by the table which starts at the head of `buf`.
+/// used to know the size of the dynamic symbol table without section headers.
+/// `buf` may contain trailing bytes after the table.
+pub(crate) fn gnu_hash_symbol_number(
+    data: header::Data,
+    class: header::Class,
+    buf: &[u8],
+) -> Option<usize> {
+    let nbuckets = read_word(data, buf, 0)? as usize;
+    let symoffset = read_word(data, buf, 1)? as usize;
+    let bloom_size = read_word(data, buf, 2)? as usize;
+
+    let buckets_start = bloom_word_size(class)
+        .checked_mul(bloom_size)?
+        .checked_add(16)?;
+    let rest = buf.get(buckets_start..)?;
+    let buckets = read_words(data, rest, 0, nbuckets)?;
+
+    // 最大のバケットが指すチェーンの終端が最後のシンボル
+    let last_bucket = match buckets.iter().max() {
+        Some(&idx) if idx as usize >= symoffset => idx as usize,
+        _ => return Some(symoffset),
+    };
+    let mut sym_idx = last_bucket;
+    loop {
+        let chain_hash = read_word(data, rest, nbuckets + sym_idx - symoffset)?;
+        if chain_hash & 1 != 0 {
+            return Some(sym_idx + 1);
+        }
+        sym_idx += 1;
+    }
+}
+
+fn bloom_word_size(class: header::Class) -> usize {
+    match class {
+        header::Class::Bit32 => 4,
+        _ => 8,
+    }
+}
+
+fn read_word(data: header::Data, buf: &[u8], idx: usize) -> Option<Elf64Word> {
+    let start = idx.checked_mul(4)?;
+    data.deserialize(buf.get(start..start.checked_add(4)?)?)
+        .ok()
+}
+
+fn read_words(data: header::Data, buf: &[u8], from: usize, n: usize) -> Option<Vec<Elf64Word>> {
+    (from..from.checked_add(n)?)
+        .map(|idx| read_word(data, buf, idx))
+        .collect()
+}
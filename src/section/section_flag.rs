@@ -29,9 +29,26 @@ pub enum Flag {
     COMPRESSED,
 }
 
-impl Into<Elf32Word> for Flag {
-    fn into(self) -> Elf32Word {
-        match self {
+impl Flag {
+    /// all flags which this crate can represent
+    pub const ALL: [Flag; 11] = [
+        Flag::Write,
+        Flag::Alloc,
+        Flag::ExecInstr,
+        Flag::Merge,
+        Flag::Strings,
+        Flag::InfoLink,
+        Flag::LinkOrder,
+        Flag::OSNonConforming,
+        Flag::Group,
+        Flag::TLS,
+        Flag::COMPRESSED,
+    ];
+}
+
+impl From<Flag> for Elf32Word {
+    fn from(flag: Flag) -> Self {
+        match flag {
             Flag::Write => 1 << 0,
             Flag::Alloc => 1 << 1,
             Flag::ExecInstr => 1 << 2,
@@ -47,9 +64,9 @@ impl Into<Elf32Word> for Flag {
     }
 }
 
-impl Into<Elf64Xword> for Flag {
-    fn into(self) -> Elf64Xword {
-        match self {
+impl From<Flag> for Elf64Xword {
+    fn from(flag: Flag) -> Self {
+        match flag {
             Flag::Write => 1 << 0,
             Flag::Alloc => 1 << 1,
             Flag::ExecInstr => 1 << 2,
@@ -71,14 +88,14 @@ impl From<Elf32Word> for Flag {
             0b1 => Flag::Write,
             0b10 => Flag::Alloc,
             0b100 => Flag::ExecInstr,
-            0b1000 => Flag::Merge,
-            0b10000 => Flag::Strings,
-            0b100000 => Flag::InfoLink,
-            0b1000000 => Flag::LinkOrder,
-            0b10000000 => Flag::OSNonConforming,
-            0b100000000 => Flag::Group,
-            0b1000000000 => Flag::TLS,
-            0b10000000000 => Flag::COMPRESSED,
+            0b10000 => Flag::Merge,
+            0b100000 => Flag::Strings,
+            0b1000000 => Flag::InfoLink,
+            0b10000000 => Flag::LinkOrder,
+            0b100000000 => Flag::OSNonConforming,
+            0b1000000000 => Flag::Group,
+            0b10000000000 => Flag::TLS,
+            0b100000000000 => Flag::COMPRESSED,
             _ => unimplemented!(),
         }
     }
@@ -90,15 +107,56 @@ impl From<Elf64Xword> for Flag {
             0b1 => Flag::Write,
             0b10 => Flag::Alloc,
             0b100 => Flag::ExecInstr,
-            0b1000 => Flag::Merge,
-            0b10000 => Flag::Strings,
-            0b100000 => Flag::InfoLink,
-            0b1000000 => Flag::LinkOrder,
-            0b10000000 => Flag::OSNonConforming,
-            0b100000000 => Flag::Group,
-            0b1000000000 => Flag::TLS,
-            0b10000000000 => Flag::COMPRESSED,
+            0b10000 => Flag::Merge,
+            0b100000 => Flag::Strings,
+            0b1000000 => Flag::InfoLink,
+            0b10000000 => Flag::LinkOrder,
+            0b100000000 => Flag::OSNonConforming,
+            0b1000000000 => Flag::Group,
+            0b10000000000 => Flag::TLS,
+            0b100000000000 => Flag::COMPRESSED,
             _ => unimplemented!(),
         }
     }
 }
+
+#[cfg(test)]
+mod section_flag_tests {
+    use super::*;
+
+    #[test]
+    fn flag_bits_test() {
+        // SHF_MERGEは0x10であり，0x8は予約されている
+        assert_eq!(0x10, Elf64Xword::from(Flag::Merge));
+        assert_eq!(0x200, Elf64Xword::from(Flag::Group));
+        assert_eq!(0x400, Elf64Xword::from(Flag::TLS));
+        assert_eq!(0x800, Elf32Word::from(Flag::COMPRESSED));
+
+        for flag in Flag::ALL.iter() {
+            assert_eq!(*flag, Flag::from(Elf64Xword::from(*flag)));
+            assert_eq!(*flag, Flag::from(Elf32Word::from(*flag)));
+        }
+    }
+
+    #[test]
+    fn get_flags_test() {
+        // SHF_ALLOC | SHF_MERGE | SHF_STRINGS | SHF_EXCLUDE
+        let shdr = section::Shdr64 {
+            sh_flags: 0x2 | 0x10 | 0x20 | 0x8000_0000,
+            ..Default::default()
+        };
+        let flags = shdr.get_flags();
+        assert_eq!(3, flags.len());
+        assert!(flags.contains(&Flag::Alloc));
+        assert!(flags.contains(&Flag::Merge));
+        assert!(flags.contains(&Flag::Strings));
+
+        let shdr = section::Shdr32 {
+            sh_flags: 0x200 | 0x400,
+            ..Default::default()
+        };
+        let flags = shdr.get_flags();
+        assert!(flags.contains(&Flag::Group));
+        assert!(flags.contains(&Flag::TLS));
+    }
+}
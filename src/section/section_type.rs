@@ -40,31 +40,43 @@ pub enum Type {
     SymTabShNdx,
     /// Number of defined types
     Num,
+    /// GNU-style hash table
+    GNUHash,
+    /// Version definition section
+    GNUVerDef,
+    /// Version needs section
+    GNUVerNeed,
+    /// Version symbol table
+    GNUVerSym,
     Any(Elf64Word),
 }
 
-impl Into<Elf64Word> for Type {
-    fn into(self) -> Elf64Word {
-        match self {
-            Self::Null => 0,
-            Self::ProgBits => 1,
-            Self::SymTab => 2,
-            Self::StrTab => 3,
-            Self::Rela => 4,
-            Self::Hash => 5,
-            Self::Dynamic => 6,
-            Self::Note => 7,
-            Self::NoBits => 8,
-            Self::Rel => 9,
-            Self::ShLib => 10,
-            Self::DynSym => 11,
-            Self::InitArray => 14,
-            Self::FiniArray => 15,
-            Self::PreInitArray => 16,
-            Self::Group => 17,
-            Self::SymTabShNdx => 18,
-            Self::Num => 19,
-            Self::Any(c) => c,
+impl From<Type> for Elf64Word {
+    fn from(ty: Type) -> Self {
+        match ty {
+            Type::Null => 0,
+            Type::ProgBits => 1,
+            Type::SymTab => 2,
+            Type::StrTab => 3,
+            Type::Rela => 4,
+            Type::Hash => 5,
+            Type::Dynamic => 6,
+            Type::Note => 7,
+            Type::NoBits => 8,
+            Type::Rel => 9,
+            Type::ShLib => 10,
+            Type::DynSym => 11,
+            Type::InitArray => 14,
+            Type::FiniArray => 15,
+            Type::PreInitArray => 16,
+            Type::Group => 17,
+            Type::SymTabShNdx => 18,
+            Type::Num => 19,
+            Type::GNUHash => 0x6ffffff6,
+            Type::GNUVerDef => 0x6ffffffd,
+            Type::GNUVerNeed => 0x6ffffffe,
+            Type::GNUVerSym => 0x6fffffff,
+            Type::Any(c) => c,
         }
     }
 }
@@ -90,6 +102,10 @@ impl From<Elf64Word> for Type {
             17 => Self::Group,
             18 => Self::SymTabShNdx,
             19 => Self::Num,
+            0x6ffffff6 => Self::GNUHash,
+            0x6ffffffd => Self::GNUVerDef,
+            0x6ffffffe => Self::GNUVerNeed,
+            0x6fffffff => Self::GNUVerSym,
             _ => Self::Any(bytes),
         }
     }
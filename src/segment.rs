@@ -3,6 +3,8 @@
 mod base;
 mod elf32;
 mod elf64;
+mod generation;
+mod mapping;
 mod segment_flag;
 mod segment_type;
 
@@ -10,5 +12,11 @@ mod segment_type;
 pub use base::*;
 pub use elf32::*;
 pub use elf64::*;
+pub use generation::*;
+pub(crate) use mapping::*;
 pub use segment_flag::*;
 pub use segment_type::*;
+
+/// Special value for `e_phnum`.
+/// the actual number of program headers is stored in `sh_info` of section 0.
+pub const PN_XNUM: u16 = 0xffff;
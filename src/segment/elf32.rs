@@ -18,7 +18,9 @@ impl Segment32 {
 }
 
 #[repr(C)]
-#[derive(Debug, Clone, Copy, Hash, PartialOrd, Ord, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(
+    Default, Debug, Clone, Copy, Hash, PartialOrd, Ord, PartialEq, Eq, Serialize, Deserialize,
+)]
 pub struct Phdr32 {
     /// Segment type
     pub p_type: Elf32Word,
@@ -45,21 +47,6 @@ pub struct Phdr32 {
     pub p_align: Elf32Word,
 }
 
-impl Default for Phdr32 {
-    fn default() -> Self {
-        Self {
-            p_type: 0,
-            p_flags: 0,
-            p_offset: 0,
-            p_vaddr: 0,
-            p_paddr: 0,
-            p_filesz: 0,
-            p_memsz: 0,
-            p_align: 0,
-        }
-    }
-}
-
 impl Phdr32 {
     pub const SIZE: usize = 0x20;
     // getter
@@ -73,7 +60,7 @@ impl Phdr32 {
         I: Iterator<Item = &'a segment::Flag>,
     {
         for flag in flags {
-            self.p_flags = self.p_flags | Into::<Elf32Word>::into(*flag);
+            self.p_flags |= Into::<Elf32Word>::into(*flag);
         }
     }
 
@@ -102,7 +89,12 @@ impl Phdr32 {
     /// assert_eq!([0].repeat(Phdr32::SIZE), null_phdr.to_le_bytes());
     /// ```
     pub fn to_le_bytes(&self) -> Vec<u8> {
-        bincode::serialize(self).unwrap()
+        self.to_bytes(header::Data::LSB2)
+    }
+
+    /// Create Vec<u8> from this with the given data encoding.
+    pub fn to_bytes(&self, data: header::Data) -> Vec<u8> {
+        data.serialize(self)
     }
 
     pub fn deserialize(buf: &[u8], start: usize) -> Result<Self, Box<dyn std::error::Error>> {
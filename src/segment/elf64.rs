@@ -15,7 +15,9 @@ pub struct Segment64 {
 }
 
 #[repr(C)]
-#[derive(Debug, Clone, Copy, Hash, PartialOrd, Ord, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(
+    Default, Debug, Clone, Copy, Hash, PartialOrd, Ord, PartialEq, Eq, Serialize, Deserialize,
+)]
 pub struct Phdr64 {
     /// Segment type
     pub p_type: Elf64Word,
@@ -42,21 +44,6 @@ pub struct Phdr64 {
     pub p_align: Elf64Xword,
 }
 
-impl Default for Phdr64 {
-    fn default() -> Self {
-        Self {
-            p_type: 0,
-            p_flags: 0,
-            p_offset: 0,
-            p_vaddr: 0,
-            p_paddr: 0,
-            p_filesz: 0,
-            p_memsz: 0,
-            p_align: 0,
-        }
-    }
-}
-
 impl Phdr64 {
     pub const SIZE: usize = 0x38;
 
@@ -100,7 +87,7 @@ impl Phdr64 {
         I: Iterator<Item = &'a segment::Flag>,
     {
         for flag in flags {
-            self.p_flags = self.p_flags | Into::<Elf64Word>::into(*flag);
+            self.p_flags |= Into::<Elf64Word>::into(*flag);
         }
     }
 
@@ -115,7 +102,12 @@ impl Phdr64 {
     /// assert_eq!([0].repeat(Phdr64::SIZE), null_phdr.to_le_bytes());
     /// ```
     pub fn to_le_bytes(&self) -> Vec<u8> {
-        bincode::serialize(self).unwrap()
+        self.to_bytes(header::Data::LSB2)
+    }
+
+    /// Create Vec<u8> from this with the given data encoding.
+    pub fn to_bytes(&self, data: header::Data) -> Vec<u8> {
+        data.serialize(self)
     }
 
     pub fn deserialize(buf: &[u8], start: usize) -> Result<Self, Box<dyn std::error::Error>> {
@@ -0,0 +1,394 @@
+//! Generating program headers from section flags.
+
+use std::collections::HashSet;
+
+use crate::{section, segment};
+
+/// sections which become read-only after relocation(`PT_GNU_RELRO`)
+const RELRO_SECTIONS: [&str; 5] = [
+    ".preinit_array",
+    ".init_array",
+    ".fini_array",
+    ".dynamic",
+    ".got",
+];
+
+/// options of `ELF64::generate_segments()`/`ELF32::generate_segments()`
+///
+/// # Examples
+///
+/// ```
+/// use elf_utilities::segment;
+///
+/// let opts = segment::GenerateOptions::new(0x400000, 0x1000)
+///     .phdr(true)
+///     .gnu_stack(true);
+/// assert_eq!(0x400000, opts.base_address);
+/// assert!(opts.phdr);
+/// assert!(!opts.interp);
+/// ```
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub struct GenerateOptions {
+    /// the virtual address of the first `PT_LOAD`, which contains ELF header and PHT
+    pub base_address: u64,
+    /// the alignment of `PT_LOAD`
+    pub page_size: u64,
+    /// emit `PT_PHDR`
+    pub phdr: bool,
+    /// emit `PT_INTERP` for `.interp`
+    pub interp: bool,
+    /// emit `PT_DYNAMIC` for the `SHT_DYNAMIC` section
+    pub dynamic: bool,
+    /// emit a non-executable `PT_GNU_STACK`
+    pub gnu_stack: bool,
+    /// emit `PT_GNU_RELRO` for `.init_array`, `.dynamic`, `.got` and etc.
+    pub gnu_relro: bool,
+}
+
+impl GenerateOptions {
+    pub fn new(base_address: u64, page_size: u64) -> Self {
+        Self {
+            base_address,
+            page_size,
+            phdr: false,
+            interp: false,
+            dynamic: false,
+            gnu_stack: false,
+            gnu_relro: false,
+        }
+    }
+
+    pub fn phdr(mut self, emit: bool) -> Self {
+        self.phdr = emit;
+        self
+    }
+    pub fn interp(mut self, emit: bool) -> Self {
+        self.interp = emit;
+        self
+    }
+    pub fn dynamic(mut self, emit: bool) -> Self {
+        self.dynamic = emit;
+        self
+    }
+    pub fn gnu_stack(mut self, emit: bool) -> Self {
+        self.gnu_stack = emit;
+        self
+    }
+    pub fn gnu_relro(mut self, emit: bool) -> Self {
+        self.gnu_relro = emit;
+        self
+    }
+}
+
+/// class-independent fields of a section used for the generation
+pub(crate) struct SectionSource<'a> {
+    pub name: &'a str,
+    pub ty: section::Type,
+    pub flags: HashSet<section::Flag>,
+    /// the memory size
+    pub size: u64,
+    pub align: u64,
+}
+
+/// class-independent fields of a program header to be generated
+pub(crate) struct GeneratedSegment {
+    pub ty: segment::Type,
+    pub flags: Vec<segment::Flag>,
+    pub vaddr: u64,
+    pub filesz: u64,
+    pub memsz: u64,
+    pub align: u64,
+}
+
+/// `sh_addr` of each section(`None` for non-`SHF_ALLOC` ones) and program headers
+pub(crate) struct GeneratedLayout {
+    pub addresses: Vec<Option<u64>>,
+    pub segments: Vec<GeneratedSegment>,
+}
+
+fn align_up(v: u64, align: u64) -> u64 {
+    if align <= 1 {
+        v
+    } else {
+        v.div_ceil(align) * align
+    }
+}
+
+fn is_relro(name: &str) -> bool {
+    RELRO_SECTIONS.contains(&name) || name == ".data.rel.ro" || name.starts_with(".data.rel.ro.")
+}
+
+/// group consecutive `SHF_ALLOC` sections by `SHF_WRITE`/`SHF_EXECINSTR` into `PT_LOAD`s
+/// and assign addresses from `opts.base_address`.
+/// the first `PT_LOAD` starts at offset 0 and contains ELF header and PHT.
+pub(crate) fn generate(
+    sections: &[SectionSource],
+    opts: &GenerateOptions,
+    ehdr_size: u64,
+    phdr_size: u64,
+    word_size: u64,
+) -> GeneratedLayout {
+    let mut addresses = vec![None; sections.len()];
+
+    // (書き込み可能か, 実行可能か)が変わるごとに新しいPT_LOADにする
+    let mut groups: Vec<((bool, bool), Vec<usize>)> = Vec::new();
+    for (sct_idx, sct) in sections.iter().enumerate().skip(1) {
+        // 非ALLOCセクションはグループを分けず，ファイル上ではPT_LOADの後ろに置かれる
+        if !sct.flags.contains(&section::Flag::Alloc) {
+            continue;
+        }
+        let key = (
+            sct.flags.contains(&section::Flag::Write),
+            sct.flags.contains(&section::Flag::ExecInstr),
+        );
+        match groups.last_mut() {
+            Some((last_key, members)) if *last_key == key => members.push(sct_idx),
+            _ => groups.push((key, vec![sct_idx])),
+        }
+    }
+
+    let alloc_section_by = |pred: &dyn Fn(&SectionSource) -> bool| {
+        sections
+            .iter()
+            .enumerate()
+            .skip(1)
+            .find(|(_, sct)| sct.flags.contains(&section::Flag::Alloc) && pred(sct))
+            .map(|(idx, _)| idx)
+    };
+    let interp_idx = alloc_section_by(&|sct| sct.name == ".interp").filter(|_| opts.interp);
+    let dynamic_idx =
+        alloc_section_by(&|sct| sct.ty == section::Type::Dynamic).filter(|_| opts.dynamic);
+    // 最初の書き込み可能なPT_LOADの先頭に並んでいるものだけをRELROとする
+    let relro_members: Vec<usize> = if opts.gnu_relro {
+        groups
+            .iter()
+            .find(|((write, _), _)| *write)
+            .map(|(_, members)| {
+                members
+                    .iter()
+                    .copied()
+                    .take_while(|idx| is_relro(sections[*idx].name))
+                    .collect()
+            })
+            .unwrap_or_default()
+    } else {
+        Vec::new()
+    };
+
+    let phnum = opts.phdr as u64
+        + interp_idx.is_some() as u64
+        + groups.len().max(1) as u64
+        + dynamic_idx.is_some() as u64
+        + opts.gnu_stack as u64
+        + !relro_members.is_empty() as u64;
+    let headers_end = opts.base_address + ehdr_size + phdr_size * phnum;
+
+    let mut loads = Vec::new();
+    let mut addr = headers_end;
+    for (group_idx, ((write, exec), members)) in groups.iter().enumerate() {
+        // ファイル上の位置を詰めたまま，新しいページから始める(GNU ldと同様)
+        if group_idx != 0 {
+            addr = align_up(addr, opts.page_size) + addr % opts.page_size.max(1);
+        }
+        let mut vaddr = None;
+        let mut file_end = if group_idx == 0 { addr } else { 0 };
+        for sct_idx in members.iter() {
+            let sct = &sections[*sct_idx];
+            let sct_addr = align_up(addr, sct.align);
+            addresses[*sct_idx] = Some(sct_addr);
+            vaddr.get_or_insert(sct_addr);
+
+            let is_tbss =
+                sct.ty == section::Type::NoBits && sct.flags.contains(&section::Flag::TLS);
+            if is_tbss {
+                // .tbssはスレッドごとの領域にのみ存在する
+                continue;
+            }
+            addr = sct_addr + sct.size;
+            if sct.ty != section::Type::NoBits {
+                file_end = addr;
+            }
+        }
+
+        let vaddr = if group_idx == 0 {
+            opts.base_address
+        } else {
+            vaddr.unwrap_or(addr)
+        };
+        let mut flags = vec![segment::Flag::R];
+        if *write {
+            flags.push(segment::Flag::W);
+        }
+        if *exec {
+            flags.push(segment::Flag::X);
+        }
+        loads.push(GeneratedSegment {
+            ty: segment::Type::Load,
+            flags,
+            vaddr,
+            filesz: file_end.max(vaddr) - vaddr,
+            memsz: addr - vaddr,
+            align: opts.page_size,
+        });
+    }
+    if loads.is_empty() {
+        loads.push(GeneratedSegment {
+            ty: segment::Type::Load,
+            flags: vec![segment::Flag::R],
+            vaddr: opts.base_address,
+            filesz: headers_end - opts.base_address,
+            memsz: headers_end - opts.base_address,
+            align: opts.page_size,
+        });
+    }
+
+    let section_segment = |ty, flags: Vec<segment::Flag>, sct_idx: usize, align| {
+        let sct = &sections[sct_idx];
+        GeneratedSegment {
+            ty,
+            flags,
+            vaddr: addresses[sct_idx].unwrap_or(0),
+            filesz: sct.size,
+            memsz: sct.size,
+            align,
+        }
+    };
+
+    let mut segments = Vec::with_capacity(phnum as usize);
+    if opts.phdr {
+        segments.push(GeneratedSegment {
+            ty: segment::Type::Phdr,
+            flags: vec![segment::Flag::R],
+            vaddr: opts.base_address + ehdr_size,
+            filesz: phdr_size * phnum,
+            memsz: phdr_size * phnum,
+            align: word_size,
+        });
+    }
+    if let Some(interp_idx) = interp_idx {
+        segments.push(section_segment(
+            segment::Type::Interp,
+            vec![segment::Flag::R],
+            interp_idx,
+            1,
+        ));
+    }
+    segments.append(&mut loads);
+    if let Some(dynamic_idx) = dynamic_idx {
+        segments.push(section_segment(
+            segment::Type::Dynamic,
+            vec![segment::Flag::R, segment::Flag::W],
+            dynamic_idx,
+            word_size,
+        ));
+    }
+    if opts.gnu_stack {
+        segments.push(GeneratedSegment {
+            ty: segment::Type::GNUStack,
+            flags: vec![segment::Flag::R, segment::Flag::W],
+            vaddr: 0,
+            filesz: 0,
+            memsz: 0,
+            align: 0x10,
+        });
+    }
+    if let (Some(first), Some(last)) = (relro_members.first(), relro_members.last()) {
+        let start = addresses[*first].unwrap_or(0);
+        let end = addresses[*last].unwrap_or(0) + sections[*last].size;
+        segments.push(GeneratedSegment {
+            ty: segment::Type::GNURelRO,
+            flags: vec![segment::Flag::R],
+            vaddr: start,
+            filesz: end - start,
+            memsz: end - start,
+            align: 1,
+        });
+    }
+
+    GeneratedLayout {
+        addresses,
+        segments,
+    }
+}
+
+#[cfg(test)]
+mod generation_tests {
+    use super::*;
+
+    fn sct<'a>(name: &'a str, flags: &[section::Flag], size: u64, align: u64) -> SectionSource<'a> {
+        SectionSource {
+            name,
+            ty: if name == ".bss" {
+                section::Type::NoBits
+            } else {
+                section::Type::ProgBits
+            },
+            flags: flags.iter().copied().collect(),
+            size,
+            align,
+        }
+    }
+
+    #[test]
+    fn generate_test() {
+        use section::Flag::{Alloc, ExecInstr, Write};
+        let sections = vec![
+            sct("", &[], 0, 0),
+            sct(".text", &[Alloc, ExecInstr], 0x20, 16),
+            sct(".rodata", &[Alloc], 0x8, 8),
+            sct(".init_array", &[Alloc, Write], 0x8, 8),
+            sct(".data", &[Alloc, Write], 0x4, 4),
+            sct(".bss", &[Alloc, Write], 0x100, 32),
+            sct(".comment", &[], 0x10, 1),
+        ];
+        let opts = GenerateOptions::new(0x400000, 0x1000)
+            .phdr(true)
+            .gnu_stack(true)
+            .gnu_relro(true);
+        let l = generate(&sections, &opts, 0x40, 0x38, 8);
+
+        assert_eq!(
+            vec![
+                None,
+                Some(0x400190),
+                Some(0x4011b0),
+                Some(0x4021b8),
+                Some(0x4021c0),
+                Some(0x4021e0),
+                None
+            ],
+            l.addresses
+        );
+        let types: Vec<&segment::Type> = l.segments.iter().map(|seg| &seg.ty).collect();
+        assert_eq!(
+            vec![
+                &segment::Type::Phdr,
+                &segment::Type::Load,
+                &segment::Type::Load,
+                &segment::Type::Load,
+                &segment::Type::GNUStack,
+                &segment::Type::GNURelRO,
+            ],
+            types
+        );
+        assert_eq!(0x38 * 6, l.segments[0].filesz);
+        // ヘッダを含むPT_LOAD
+        assert_eq!(
+            (0x400000, 0x1b0, 0x1b0),
+            (
+                l.segments[1].vaddr,
+                l.segments[1].filesz,
+                l.segments[1].memsz
+            )
+        );
+        assert_eq!(
+            (0x4021b8, 0xc, 0x128),
+            (
+                l.segments[3].vaddr,
+                l.segments[3].filesz,
+                l.segments[3].memsz
+            )
+        );
+        assert_eq!((0x4021b8, 0x8), (l.segments[5].vaddr, l.segments[5].memsz));
+    }
+}
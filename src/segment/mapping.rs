@@ -0,0 +1,145 @@
+//! Section to segment mapping rules(`ELF_SECTION_IN_SEGMENT` in binutils).
+
+use crate::*;
+
+const PT_GNU_SFRAME: Elf64Word = 0x6474e554;
+const PT_GNU_MBIND_LO: Elf64Word = 0x6474e555;
+const PT_GNU_MBIND_HI: Elf64Word = 0x6474f554;
+
+/// class-independent fields of a section header used for the mapping
+pub(crate) struct SectionExtent {
+    pub ty: section::Type,
+    pub alloc: bool,
+    pub tls: bool,
+    pub addr: u64,
+    pub offset: u64,
+    pub size: u64,
+    pub align: u64,
+}
+
+/// class-independent fields of a program header used for the mapping
+pub(crate) struct SegmentExtent {
+    pub ty: segment::Type,
+    pub offset: u64,
+    pub vaddr: u64,
+    pub filesz: u64,
+    pub memsz: u64,
+    pub align: u64,
+}
+
+impl From<&section::Shdr64> for SectionExtent {
+    fn from(shdr: &section::Shdr64) -> Self {
+        let flags = shdr.get_flags();
+        Self {
+            ty: shdr.get_type(),
+            alloc: flags.contains(&section::Flag::Alloc),
+            tls: flags.contains(&section::Flag::TLS),
+            addr: shdr.sh_addr,
+            offset: shdr.sh_offset,
+            size: shdr.sh_size,
+            align: shdr.sh_addralign,
+        }
+    }
+}
+
+impl From<&section::Shdr32> for SectionExtent {
+    fn from(shdr: &section::Shdr32) -> Self {
+        let flags = shdr.get_flags();
+        Self {
+            ty: shdr.get_type(),
+            alloc: flags.contains(&section::Flag::Alloc),
+            tls: flags.contains(&section::Flag::TLS),
+            addr: shdr.sh_addr as u64,
+            offset: shdr.sh_offset as u64,
+            size: shdr.sh_size as u64,
+            align: shdr.sh_addralign as u64,
+        }
+    }
+}
+
+impl From<&segment::Phdr64> for SegmentExtent {
+    fn from(phdr: &segment::Phdr64) -> Self {
+        Self {
+            ty: phdr.get_type(),
+            offset: phdr.p_offset,
+            vaddr: phdr.p_vaddr,
+            filesz: phdr.p_filesz,
+            memsz: phdr.p_memsz,
+            align: phdr.p_align,
+        }
+    }
+}
+
+impl From<&segment::Phdr32> for SegmentExtent {
+    fn from(phdr: &segment::Phdr32) -> Self {
+        Self {
+            ty: phdr.get_type(),
+            offset: phdr.p_offset as u64,
+            vaddr: phdr.p_vaddr as u64,
+            filesz: phdr.p_filesz as u64,
+            memsz: phdr.p_memsz as u64,
+            align: phdr.p_align as u64,
+        }
+    }
+}
+
+/// whether the section is listed in the segment by `readelf -l`.
+/// this is `ELF_SECTION_IN_SEGMENT_STRICT` excluding `ELF_TBSS_SPECIAL`.
+pub(crate) fn section_in_segment(sct: &SectionExtent, seg: &SegmentExtent) -> bool {
+    let is_tbss = sct.tls && sct.ty == section::Type::NoBits;
+    // .tbssはPT_TLS以外ではアドレス空間を占有しない
+    if is_tbss && seg.ty != segment::Type::TLS {
+        return false;
+    }
+
+    // TLSセクションはPT_TLS/PT_GNU_RELRO/PT_LOADにのみ含まれる
+    let tls_ok = if sct.tls {
+        matches!(
+            seg.ty,
+            segment::Type::TLS | segment::Type::GNURelRO | segment::Type::Load
+        )
+    } else {
+        !matches!(seg.ty, segment::Type::TLS | segment::Type::Phdr)
+    };
+
+    // PT_LOAD等はSHF_ALLOCなセクションのみを含む
+    let p_type = seg.ty.to_bytes();
+    let alloc_only = matches!(
+        seg.ty,
+        segment::Type::Load
+            | segment::Type::Dynamic
+            | segment::Type::GNUEHFrame
+            | segment::Type::GNUStack
+            | segment::Type::GNURelRO
+    ) || p_type == PT_GNU_SFRAME
+        || (PT_GNU_MBIND_LO..=PT_GNU_MBIND_HI).contains(&p_type);
+    if !tls_ok || (!sct.alloc && alloc_only) {
+        return false;
+    }
+
+    // NoBits以外はファイルオフセットがセグメント内に収まっている必要がある
+    // binutilsと同様に，サイズ0のセグメントでは `p_filesz - 1` がラップアラウンドする
+    let size = sct.size;
+    let in_file = sct.ty == section::Type::NoBits
+        || (sct.offset >= seg.offset
+            && sct.offset - seg.offset <= seg.filesz.wrapping_sub(1)
+            && (sct.offset - seg.offset).saturating_add(size) <= seg.filesz);
+    // SHF_ALLOCなセクションはアドレスがセグメント内に収まっている必要がある
+    let in_memory = !sct.alloc
+        || (sct.addr >= seg.vaddr
+            && sct.addr - seg.vaddr <= seg.memsz.wrapping_sub(1)
+            && (sct.addr - seg.vaddr).saturating_add(size) <= seg.memsz);
+    if !in_file || !in_memory {
+        return false;
+    }
+
+    // PT_DYNAMIC/PT_NOTEの先頭・末尾にあるサイズ0のセクションは含めない
+    if matches!(seg.ty, segment::Type::Dynamic | segment::Type::Note) && sct.size == 0 {
+        return seg.memsz > 0
+            && (sct.offset > seg.offset || sct.addr > seg.vaddr)
+            && (sct.offset.wrapping_sub(seg.offset) < seg.filesz
+                || sct.addr.wrapping_sub(seg.vaddr) < seg.memsz);
+    }
+
+    true
+}
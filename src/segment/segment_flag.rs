@@ -13,9 +13,9 @@ pub enum Flag {
     R,
 }
 
-impl Into<Elf64Word> for Flag {
-    fn into(self) -> Elf64Word {
-        match self {
+impl From<Flag> for Elf64Word {
+    fn from(flag: Flag) -> Self {
+        match flag {
             Flag::X => 1 << 0,
             Flag::W => 1 << 1,
             Flag::R => 1 << 2,
@@ -0,0 +1,209 @@
+//! Removing symbols and debugging information like GNU strip.
+
+use crate::{header, symbol};
+
+/// what `strip()` removes
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum StripMode {
+    /// `--strip-all`: all symbols and debugging sections
+    All,
+    /// `--strip-debug`: debugging sections and symbols defined in them
+    Debug,
+    /// `--strip-unneeded`: debugging sections and symbols not needed for relocation processing
+    Unneeded,
+}
+
+/// options of `ELF64::strip()`/`ELF32::strip()`
+///
+/// # Examples
+///
+/// ```
+/// use elf_utilities::strip;
+///
+/// let opts = strip::StripOptions::new(strip::StripMode::All).keep_symbol("main");
+/// assert_eq!(vec!["main".to_string()], opts.keep_symbols);
+/// ```
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct StripOptions {
+    pub mode: StripMode,
+    /// `--keep-symbol`
+    pub keep_symbols: Vec<String>,
+}
+
+impl StripOptions {
+    pub fn new(mode: StripMode) -> Self {
+        Self {
+            mode,
+            keep_symbols: Vec::new(),
+        }
+    }
+
+    pub fn keep_symbol(mut self, name: &str) -> Self {
+        self.keep_symbols.push(name.to_string());
+        self
+    }
+}
+
+/// whether the section has debugging information by its name.
+///
+/// # Examples
+///
+/// ```
+/// use elf_utilities::strip;
+///
+/// assert!(strip::is_debug_section(".debug_info"));
+/// assert!(strip::is_debug_section(".zdebug_line"));
+/// assert!(!strip::is_debug_section(".text"));
+/// ```
+pub fn is_debug_section(name: &str) -> bool {
+    [".debug", ".zdebug", ".gnu.debuglto_", ".stab", ".line"]
+        .iter()
+        .any(|prefix| name.starts_with(prefix))
+}
+
+/// whether the symbol remains.
+/// symbols referenced by relocations or section groups are always kept.
+/// global symbols are needed only in relocatable files in `StripMode::Unneeded` like GNU strip.
+pub(crate) fn keeps_symbol(
+    opts: &StripOptions,
+    e_type: header::Type,
+    name: &str,
+    bind: symbol::Bind,
+    referenced: bool,
+    in_removed_section: bool,
+) -> bool {
+    if referenced || opts.keep_symbols.iter().any(|keep| keep == name) {
+        return true;
+    }
+    if in_removed_section {
+        return false;
+    }
+
+    match opts.mode {
+        StripMode::All => false,
+        StripMode::Debug => true,
+        // ローカルシンボル(STT_FILE/STT_SECTIONを含む)は再配置に使われていなければ不要
+        // 実行ファイルや共有オブジェクトのリンクには.dynsymが用いられるので，グローバルシンボルも不要
+        StripMode::Unneeded => {
+            e_type == header::Type::Rel
+                && matches!(
+                    bind,
+                    symbol::Bind::Global | symbol::Bind::Weak | symbol::Bind::GNUUnique
+                )
+        }
+    }
+}
+
+/// whether `.symtab` and its string table are removed when no symbol remains.
+pub(crate) fn removes_empty_symtab(mode: StripMode, e_type: header::Type) -> bool {
+    match mode {
+        StripMode::All => true,
+        StripMode::Debug => false,
+        StripMode::Unneeded => e_type != header::Type::Rel,
+    }
+}
+
+#[cfg(test)]
+mod strip_tests {
+    use super::*;
+
+    #[test]
+    fn keeps_symbol_test() {
+        let all = StripOptions::new(StripMode::All).keep_symbol("main");
+        assert!(!keeps_symbol(
+            &all,
+            header::Type::Rel,
+            "f",
+            symbol::Bind::Global,
+            false,
+            false
+        ));
+        assert!(keeps_symbol(
+            &all,
+            header::Type::Rel,
+            "f",
+            symbol::Bind::Global,
+            true,
+            false
+        ));
+        assert!(keeps_symbol(
+            &all,
+            header::Type::Rel,
+            "main",
+            symbol::Bind::Global,
+            false,
+            true
+        ));
+
+        let debug = StripOptions::new(StripMode::Debug);
+        assert!(keeps_symbol(
+            &debug,
+            header::Type::Rel,
+            "x",
+            symbol::Bind::Local,
+            false,
+            false
+        ));
+        assert!(!keeps_symbol(
+            &debug,
+            header::Type::Rel,
+            "",
+            symbol::Bind::Local,
+            false,
+            true
+        ));
+
+        let unneeded = StripOptions::new(StripMode::Unneeded);
+        assert!(!keeps_symbol(
+            &unneeded,
+            header::Type::Rel,
+            "x",
+            symbol::Bind::Local,
+            false,
+            false
+        ));
+        assert!(keeps_symbol(
+            &unneeded,
+            header::Type::Rel,
+            "w",
+            symbol::Bind::Weak,
+            false,
+            false
+        ));
+        assert!(!keeps_symbol(
+            &unneeded,
+            header::Type::Rel,
+            "g",
+            symbol::Bind::Global,
+            false,
+            true
+        ));
+
+        // 実行ファイルではグローバルシンボルも不要
+        assert!(!keeps_symbol(
+            &unneeded,
+            header::Type::Exec,
+            "g",
+            symbol::Bind::Global,
+            false,
+            false
+        ));
+        assert!(keeps_symbol(
+            &unneeded,
+            header::Type::Exec,
+            "g",
+            symbol::Bind::Global,
+            true,
+            false
+        ));
+        assert!(removes_empty_symtab(
+            StripMode::Unneeded,
+            header::Type::Exec
+        ));
+        assert!(!removes_empty_symtab(
+            StripMode::Unneeded,
+            header::Type::Rel
+        ));
+        assert!(!removes_empty_symtab(StripMode::Debug, header::Type::Exec));
+    }
+}
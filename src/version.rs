@@ -0,0 +1,74 @@
+//! GNU symbol versioning(`.gnu.version`, `.gnu.version_d`, `.gnu.version_r`) utilities.
+
+mod definition;
+mod requirement;
+
+pub use definition::*;
+pub use requirement::*;
+
+use crate::*;
+
+/// Symbol is local
+pub const VER_NDX_LOCAL: Elf64Versym = 0;
+/// Symbol is global and unversioned
+pub const VER_NDX_GLOBAL: Elf64Versym = 1;
+/// the version is hidden(`sym@VER`, not the default `sym@@VER`)
+pub const VERSYM_HIDDEN: Elf64Versym = 0x8000;
+/// mask of the version index in `Elf64Versym`
+pub const VERSYM_VERSION: Elf64Versym = 0x7fff;
+
+/// `vd_version`/`vn_version` this crate supports
+pub const VER_DEF_CURRENT: Elf64Half = 1;
+pub const VER_NEED_CURRENT: Elf64Half = 1;
+
+/// Version definition of the file itself
+pub const VER_FLG_BASE: Elf64Half = 0x1;
+/// Weak version identifier
+pub const VER_FLG_WEAK: Elf64Half = 0x2;
+
+/// A version associated with a dynamic symbol
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SymbolVersion {
+    /// version name like `GLIBC_2.2.5`
+    pub name: String,
+    /// the file which the version is required from, `None` if the version is defined in this file
+    pub file: Option<String>,
+    /// whether `VERSYM_HIDDEN` is set
+    pub hidden: bool,
+}
+
+impl SymbolVersion {
+    /// decorate the symbol name like `nm -D` does.
+    /// the default version of a defined symbol is separated by `@@`, otherwise `@`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use elf_utilities::version::SymbolVersion;
+    ///
+    /// let ver = SymbolVersion {
+    ///     name: "GLIBC_2.2.5".to_string(),
+    ///     file: Some("libc.so.6".to_string()),
+    ///     hidden: false,
+    /// };
+    /// assert_eq!("printf@GLIBC_2.2.5", ver.decorate("printf"));
+    /// ```
+    pub fn decorate(&self, symbol_name: &str) -> String {
+        let separator = if self.file.is_none() && !self.hidden {
+            "@@"
+        } else {
+            "@"
+        };
+        format!("{}{}{}", symbol_name, separator, self.name)
+    }
+}
+
+/// the version index in a `.gnu.version` entry
+pub fn versym_index(versym: Elf64Versym) -> Elf64Versym {
+    versym & VERSYM_VERSION
+}
+
+/// the hash function for `vd_hash`/`vna_hash`, which is same as SysV hash table's one
+pub fn version_hash(name: &str) -> Elf64Word {
+    section::sysv_hash(name)
+}
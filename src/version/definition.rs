@@ -0,0 +1,149 @@
+use crate::*;
+use serde::{Deserialize, Serialize};
+
+use section::StrTabEntry;
+
+/// Version definition entry. the layout is same in 32-bit and 64-bit ELF.
+#[derive(
+    Default, Debug, Clone, Copy, Hash, PartialOrd, Ord, PartialEq, Eq, Serialize, Deserialize,
+)]
+#[repr(C)]
+pub struct Verdef {
+    /// Version revision
+    pub vd_version: Elf64Half,
+    /// Version information
+    pub vd_flags: Elf64Half,
+    /// Version Index
+    pub vd_ndx: Elf64Half,
+    /// Number of associated aux entries
+    pub vd_cnt: Elf64Half,
+    /// Version name hash value
+    pub vd_hash: Elf64Word,
+    /// Offset in bytes to verdaux array
+    pub vd_aux: Elf64Word,
+    /// Offset in bytes to next verdef entry
+    pub vd_next: Elf64Word,
+}
+
+/// Auxiliary version information of `Verdef`
+#[derive(
+    Default, Debug, Clone, Copy, Hash, PartialOrd, Ord, PartialEq, Eq, Serialize, Deserialize,
+)]
+#[repr(C)]
+pub struct Verdaux {
+    /// Version or dependency names
+    pub vda_name: Elf64Word,
+    /// Offset in bytes to next verdaux entry
+    pub vda_next: Elf64Word,
+}
+
+/// A version defined in the file(an entry of `.gnu.version_d`)
+#[derive(Default, Debug, Clone, Hash, PartialOrd, Ord, PartialEq, Eq)]
+pub struct VersionDefinition {
+    /// `VER_FLG_BASE` etc.
+    pub flags: Elf64Half,
+    /// the version index referred from `.gnu.version`
+    pub ndx: Elf64Half,
+    pub hash: Elf64Word,
+    /// `vda_name` offsets and names in the linked string table.
+    /// the first is the version itself and the rest are its predecessors.
+    pub names: Vec<StrTabEntry>,
+}
+
+impl Verdef {
+    pub const SIZE: usize = 20;
+}
+
+impl Verdaux {
+    pub const SIZE: usize = 8;
+}
+
+impl VersionDefinition {
+    /// create a definition with computing the hash of `name`.
+    pub fn new(flags: Elf64Half, ndx: Elf64Half, name: StrTabEntry) -> Self {
+        Self {
+            flags,
+            ndx,
+            hash: version::version_hash(&name.v),
+            names: vec![name],
+        }
+    }
+
+    /// the version name
+    pub fn name(&self) -> &str {
+        self.names.first().map_or("", |n| n.v.as_str())
+    }
+
+    pub fn size(&self) -> usize {
+        Verdef::SIZE + Verdaux::SIZE * self.names.len()
+    }
+
+    /// decode the chain of `Verdef` and its `Verdaux` entries.
+    /// names are left empty, only offsets in the string table are set.
+    pub fn parse_table(data: header::Data, buf: &[u8]) -> Option<Vec<Self>> {
+        let mut defs = Vec::new();
+        let mut offset = 0;
+        while offset < buf.len() {
+            let verdef: Verdef = data.deserialize(buf.get(offset..)?).ok()?;
+
+            let mut names = Vec::with_capacity(verdef.vd_cnt as usize);
+            let mut aux_offset = offset.checked_add(verdef.vd_aux as usize)?;
+            for _ in 0..verdef.vd_cnt {
+                let verdaux: Verdaux = data.deserialize(buf.get(aux_offset..)?).ok()?;
+                names.push(StrTabEntry {
+                    v: String::new(),
+                    idx: verdaux.vda_name as usize,
+                });
+                aux_offset = aux_offset.checked_add(verdaux.vda_next as usize)?;
+            }
+
+            defs.push(Self {
+                flags: verdef.vd_flags,
+                ndx: verdef.vd_ndx,
+                hash: verdef.vd_hash,
+                names,
+            });
+
+            if verdef.vd_next == 0 {
+                break;
+            }
+            offset = offset.checked_add(verdef.vd_next as usize)?;
+        }
+
+        Some(defs)
+    }
+
+    /// encode definitions with placing each `Verdaux` array just after its `Verdef`
+    pub fn table_to_bytes(defs: &[Self], data: header::Data) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        for (i, def) in defs.iter().enumerate() {
+            let verdef = Verdef {
+                vd_version: version::VER_DEF_CURRENT,
+                vd_flags: def.flags,
+                vd_ndx: def.ndx,
+                vd_cnt: def.names.len() as Elf64Half,
+                vd_hash: def.hash,
+                vd_aux: Verdef::SIZE as Elf64Word,
+                vd_next: if i + 1 == defs.len() {
+                    0
+                } else {
+                    def.size() as Elf64Word
+                },
+            };
+            bytes.append(&mut data.serialize(&verdef));
+
+            for (j, name) in def.names.iter().enumerate() {
+                let verdaux = Verdaux {
+                    vda_name: name.idx as Elf64Word,
+                    vda_next: if j + 1 == def.names.len() {
+                        0
+                    } else {
+                        Verdaux::SIZE as Elf64Word
+                    },
+                };
+                bytes.append(&mut data.serialize(&verdaux));
+            }
+        }
+        bytes
+    }
+}
@@ -0,0 +1,166 @@
+use crate::*;
+use serde::{Deserialize, Serialize};
+
+use section::StrTabEntry;
+
+/// Version dependency entry. the layout is same in 32-bit and 64-bit ELF.
+#[derive(
+    Default, Debug, Clone, Copy, Hash, PartialOrd, Ord, PartialEq, Eq, Serialize, Deserialize,
+)]
+#[repr(C)]
+pub struct Verneed {
+    /// Version of structure
+    pub vn_version: Elf64Half,
+    /// Number of associated aux entries
+    pub vn_cnt: Elf64Half,
+    /// Offset of filename for this dependency
+    pub vn_file: Elf64Word,
+    /// Offset in bytes to vernaux array
+    pub vn_aux: Elf64Word,
+    /// Offset in bytes to next verneed entry
+    pub vn_next: Elf64Word,
+}
+
+/// Auxiliary needed version information of `Verneed`
+#[derive(
+    Default, Debug, Clone, Copy, Hash, PartialOrd, Ord, PartialEq, Eq, Serialize, Deserialize,
+)]
+#[repr(C)]
+pub struct Vernaux {
+    /// Hash value of dependency name
+    pub vna_hash: Elf64Word,
+    /// Dependency specific information
+    pub vna_flags: Elf64Half,
+    /// the version index referred from `.gnu.version`
+    pub vna_other: Elf64Half,
+    /// Dependency name string offset
+    pub vna_name: Elf64Word,
+    /// Offset in bytes to next vernaux entry
+    pub vna_next: Elf64Word,
+}
+
+/// Versions required from a shared object(an entry of `.gnu.version_r`)
+#[derive(Default, Debug, Clone, Hash, PartialOrd, Ord, PartialEq, Eq)]
+pub struct VersionRequirement {
+    /// the file name like `libc.so.6`
+    pub file: StrTabEntry,
+    pub entries: Vec<VersionRequirementEntry>,
+}
+
+/// A version required from a shared object
+#[derive(Default, Debug, Clone, Hash, PartialOrd, Ord, PartialEq, Eq)]
+pub struct VersionRequirementEntry {
+    pub hash: Elf64Word,
+    /// `VER_FLG_WEAK` etc.
+    pub flags: Elf64Half,
+    /// the version index referred from `.gnu.version`
+    pub other: Elf64Half,
+    /// the version name like `GLIBC_2.2.5`
+    pub name: StrTabEntry,
+}
+
+impl Verneed {
+    pub const SIZE: usize = 16;
+}
+
+impl Vernaux {
+    pub const SIZE: usize = 16;
+}
+
+impl VersionRequirementEntry {
+    /// create an entry with computing the hash of `name`.
+    pub fn new(flags: Elf64Half, other: Elf64Half, name: StrTabEntry) -> Self {
+        Self {
+            hash: version::version_hash(&name.v),
+            flags,
+            other,
+            name,
+        }
+    }
+}
+
+impl VersionRequirement {
+    pub fn new(file: StrTabEntry, entries: Vec<VersionRequirementEntry>) -> Self {
+        Self { file, entries }
+    }
+
+    pub fn size(&self) -> usize {
+        Verneed::SIZE + Vernaux::SIZE * self.entries.len()
+    }
+
+    /// decode the chain of `Verneed` and its `Vernaux` entries.
+    /// names are left empty, only offsets in the string table are set.
+    pub fn parse_table(data: header::Data, buf: &[u8]) -> Option<Vec<Self>> {
+        let mut reqs = Vec::new();
+        let mut offset = 0;
+        while offset < buf.len() {
+            let verneed: Verneed = data.deserialize(buf.get(offset..)?).ok()?;
+
+            let mut entries = Vec::with_capacity(verneed.vn_cnt as usize);
+            let mut aux_offset = offset.checked_add(verneed.vn_aux as usize)?;
+            for _ in 0..verneed.vn_cnt {
+                let vernaux: Vernaux = data.deserialize(buf.get(aux_offset..)?).ok()?;
+                entries.push(VersionRequirementEntry {
+                    hash: vernaux.vna_hash,
+                    flags: vernaux.vna_flags,
+                    other: vernaux.vna_other,
+                    name: StrTabEntry {
+                        v: String::new(),
+                        idx: vernaux.vna_name as usize,
+                    },
+                });
+                aux_offset = aux_offset.checked_add(vernaux.vna_next as usize)?;
+            }
+
+            reqs.push(Self {
+                file: StrTabEntry {
+                    v: String::new(),
+                    idx: verneed.vn_file as usize,
+                },
+                entries,
+            });
+
+            if verneed.vn_next == 0 {
+                break;
+            }
+            offset = offset.checked_add(verneed.vn_next as usize)?;
+        }
+
+        Some(reqs)
+    }
+
+    /// encode requirements with placing each `Vernaux` array just after its `Verneed`
+    pub fn table_to_bytes(reqs: &[Self], data: header::Data) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        for (i, req) in reqs.iter().enumerate() {
+            let verneed = Verneed {
+                vn_version: version::VER_NEED_CURRENT,
+                vn_cnt: req.entries.len() as Elf64Half,
+                vn_file: req.file.idx as Elf64Word,
+                vn_aux: Verneed::SIZE as Elf64Word,
+                vn_next: if i + 1 == reqs.len() {
+                    0
+                } else {
+                    req.size() as Elf64Word
+                },
+            };
+            bytes.append(&mut data.serialize(&verneed));
+
+            for (j, entry) in req.entries.iter().enumerate() {
+                let vernaux = Vernaux {
+                    vna_hash: entry.hash,
+                    vna_flags: entry.flags,
+                    vna_other: entry.other,
+                    vna_name: entry.name.idx as Elf64Word,
+                    vna_next: if j + 1 == req.entries.len() {
+                        0
+                    } else {
+                        Vernaux::SIZE as Elf64Word
+                    },
+                };
+                bytes.append(&mut data.serialize(&vernaux));
+            }
+        }
+        bytes
+    }
+}
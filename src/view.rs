@@ -0,0 +1,43 @@
+//! Zero-copy views over ELF images.
+
+pub use elf32::*;
+pub use elf64::*;
+
+mod elf32;
+mod elf64;
+
+use crate::parser::{self, ReadELFError};
+use crate::*;
+
+/// A borrowed view over an ELF image of either class.
+pub enum ElfView<'a> {
+    ElfView32(ElfView32<'a>),
+    ElfView64(ElfView64<'a>),
+}
+
+impl<'a> ElfView<'a> {
+    /// create a view with checking `e_ident[EI_CLASS]`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use elf_utilities::view::ElfView;
+    ///
+    /// let bytes = std::fs::read("src/parser/testdata/32bit").unwrap();
+    /// assert!(matches!(ElfView::new(&bytes), Ok(ElfView::ElfView32(_))));
+    /// ```
+    pub fn new(buf: &'a [u8]) -> Result<Self, ReadELFError> {
+        match parser::check_elf_ident(parser::IN_MEMORY_SOURCE, buf)?.0 {
+            header::Class::Bit32 => Ok(ElfView::ElfView32(ElfView32::new(buf)?)),
+            _ => Ok(ElfView::ElfView64(ElfView64::new(buf)?)),
+        }
+    }
+
+    /// construct the owned `file::ELF` to mutate it.
+    pub fn to_elf(&self) -> Result<file::ELF, ReadELFError> {
+        match self {
+            ElfView::ElfView32(v) => Ok(file::ELF::ELF32(v.to_elf32()?)),
+            ElfView::ElfView64(v) => Ok(file::ELF::ELF64(v.to_elf64()?)),
+        }
+    }
+}
@@ -0,0 +1,653 @@
+//! Zero-copy view for 64-bit ELF binaries.
+
+use crate::parser::{self, ReadELFError};
+use crate::*;
+
+/// A borrowed view over a 64-bit ELF image.
+///
+/// only the ELF header is decoded on construction.
+/// section/program headers and symbols are decoded each time they are accessed,
+/// and section contents/names are handed out as slices of the original buffer.
+///
+/// # Examples
+///
+/// ```
+/// use elf_utilities::view::ElfView64;
+///
+/// let bytes = std::fs::read("src/parser/testdata/sample").unwrap();
+/// let view = ElfView64::new(&bytes).unwrap();
+///
+/// let interp = view.section_by_name(".interp").unwrap().unwrap();
+/// assert_eq!(b"/lib64/ld-linux-x86-64.so.2\0", view.section_data(interp).unwrap());
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct ElfView64<'a> {
+    buf: &'a [u8],
+    ehdr: header::Ehdr64,
+    numbers: parser::HeaderNumbers,
+}
+
+impl<'a> ElfView64<'a> {
+    /// create a view with validating the ELF header and the extent of header tables.
+    pub fn new(buf: &'a [u8]) -> Result<Self, ReadELFError> {
+        let (class, data) = parser::check_elf_ident(parser::IN_MEMORY_SOURCE, buf)?;
+        if class != header::Class::Bit64 {
+            return Err(ReadELFError::ClassMismatch { expected: 64 });
+        }
+
+        let size = header::Ehdr64::SIZE as usize;
+        let ehdr: header::Ehdr64 =
+            data.deserialize(buf)
+                .map_err(|_| ReadELFError::TruncatedHeader {
+                    size,
+                    len: buf.len(),
+                })?;
+
+        // e_shnum等に収まらない値はNULLセクションに格納されている
+        let (e_shnum, e_shstrndx, e_phnum) = (
+            ehdr.e_shnum as usize,
+            ehdr.e_shstrndx as usize,
+            ehdr.e_phnum as usize,
+        );
+        let null_shdr = if ehdr.e_shoff != 0
+            && parser::HeaderNumbers::is_extended(e_shnum, e_shstrndx, e_phnum)
+        {
+            let sh0: section::Shdr64 =
+                parser::checked_slice(buf, ehdr.e_shoff as usize, section::Shdr64::SIZE)
+                    .and_then(|bytes| data.deserialize(bytes).ok())
+                    .ok_or(ReadELFError::SectionHeaderTableOutOfBounds {
+                        offset: ehdr.e_shoff as usize,
+                        number: 1,
+                    })?;
+            Some((
+                sh0.sh_size as usize,
+                sh0.sh_link as usize,
+                sh0.sh_info as usize,
+            ))
+        } else {
+            None
+        };
+        let numbers = parser::HeaderNumbers::resolve(e_shnum, e_shstrndx, e_phnum, null_shdr);
+
+        // NULLセクション由来の個数は巨大になりうる
+        section::Shdr64::SIZE
+            .checked_mul(numbers.shnum)
+            .and_then(|size| parser::checked_slice(buf, ehdr.e_shoff as usize, size))
+            .ok_or(ReadELFError::SectionHeaderTableOutOfBounds {
+                offset: ehdr.e_shoff as usize,
+                number: numbers.shnum,
+            })?;
+        segment::Phdr64::SIZE
+            .checked_mul(numbers.phnum)
+            .and_then(|size| parser::checked_slice(buf, ehdr.e_phoff as usize, size))
+            .ok_or(ReadELFError::ProgramHeaderTableOutOfBounds {
+                offset: ehdr.e_phoff as usize,
+                number: numbers.phnum,
+            })?;
+
+        Ok(Self { buf, ehdr, numbers })
+    }
+
+    /// the whole image this view borrows
+    pub fn bytes(&self) -> &'a [u8] {
+        self.buf
+    }
+    pub fn ehdr(&self) -> &header::Ehdr64 {
+        &self.ehdr
+    }
+    pub fn data(&self) -> header::Data {
+        self.ehdr.get_data()
+    }
+    /// the number of sections, resolved from section 0 when `e_shnum` is 0.
+    pub fn section_number(&self) -> usize {
+        self.numbers.shnum
+    }
+    /// the number of segments, resolved from section 0 when `e_phnum` is `PN_XNUM`.
+    pub fn segment_number(&self) -> usize {
+        self.numbers.phnum
+    }
+    /// the index of `.shstrtab`, resolved from section 0 when `e_shstrndx` is `SHN_XINDEX`.
+    pub fn shstrndx(&self) -> usize {
+        self.numbers.shstrndx
+    }
+
+    /// decode the section header at `idx`
+    pub fn shdr(&self, idx: usize) -> Result<section::Shdr64, ReadELFError> {
+        if idx >= self.section_number() {
+            return Err(ReadELFError::InvalidSectionIndex {
+                index: idx,
+                number: self.section_number(),
+            });
+        }
+
+        let start = self.ehdr.e_shoff as usize + section::Shdr64::SIZE * idx;
+        self.data().deserialize(&self.buf[start..]).map_err(|_| {
+            ReadELFError::SectionHeaderTableOutOfBounds {
+                offset: self.ehdr.e_shoff as usize,
+                number: self.section_number(),
+            }
+        })
+    }
+
+    /// decode the program header at `idx`
+    pub fn phdr(&self, idx: usize) -> Result<segment::Phdr64, ReadELFError> {
+        let pht_out_of_bounds = || ReadELFError::ProgramHeaderTableOutOfBounds {
+            offset: self.ehdr.e_phoff as usize,
+            number: self.segment_number(),
+        };
+        if idx >= self.segment_number() {
+            return Err(pht_out_of_bounds());
+        }
+
+        let start = self.ehdr.e_phoff as usize + segment::Phdr64::SIZE * idx;
+        self.data()
+            .deserialize(&self.buf[start..])
+            .map_err(|_| pht_out_of_bounds())
+    }
+
+    /// section contents in the buffer.
+    /// `NoBits` sections have no bytes in the file, so an empty slice is returned.
+    pub fn section_data(&self, idx: usize) -> Result<&'a [u8], ReadELFError> {
+        let shdr = self.shdr(idx)?;
+        if shdr.get_type() == section::Type::NoBits {
+            return Ok(&[]);
+        }
+
+        parser::checked_slice(self.buf, shdr.sh_offset as usize, shdr.sh_size as usize).ok_or(
+            ReadELFError::SectionOutOfBounds {
+                index: idx,
+                offset: shdr.sh_offset as usize,
+                size: shdr.sh_size as usize,
+            },
+        )
+    }
+
+    /// segment contents in the file(`p_filesz` bytes from `p_offset`).
+    pub fn segment_data(&self, idx: usize) -> Result<&'a [u8], ReadELFError> {
+        let phdr = self.phdr(idx)?;
+        parser::checked_slice(self.buf, phdr.p_offset as usize, phdr.p_filesz as usize).ok_or(
+            ReadELFError::SegmentOutOfBounds {
+                index: idx,
+                offset: phdr.p_offset as usize,
+                size: phdr.p_filesz as usize,
+            },
+        )
+    }
+
+    /// decode note entries in a `PT_NOTE` segment.
+    pub fn segment_notes(&self, idx: usize) -> Result<note::NoteTable, ReadELFError> {
+        let phdr = self.phdr(idx)?;
+        let align = note::note_alignment(phdr.p_align);
+        parser::parse_note_table(self.data(), align, idx, self.segment_data(idx)?)
+    }
+
+    /// a NUL-terminated string at `name_idx` in the string table section `strtab_idx`
+    pub fn string_at(&self, strtab_idx: usize, name_idx: usize) -> Result<&'a str, ReadELFError> {
+        parser::string_in_table(self.section_data(strtab_idx)?, strtab_idx, name_idx)
+    }
+
+    /// the section name looked up from `.shstrtab`
+    pub fn section_name(&self, idx: usize) -> Result<&'a str, ReadELFError> {
+        let shdr = self.shdr(idx)?;
+        if shdr.sh_name == 0 || self.shstrndx() == section::SHN_UNDEF as usize {
+            return Ok("");
+        }
+        self.string_at(self.shstrndx(), shdr.sh_name as usize)
+    }
+
+    /// get the first section index which has the name
+    pub fn section_by_name(&self, name: &str) -> Result<Option<usize>, ReadELFError> {
+        for idx in 0..self.section_number() {
+            if self.section_name(idx)? == name {
+                return Ok(Some(idx));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// the number of entries in the symbol table section `symtab_idx`
+    pub fn symbol_number(&self, symtab_idx: usize) -> Result<usize, ReadELFError> {
+        let shdr = self.symbol_table_header(symtab_idx)?;
+        Ok(shdr.sh_size as usize / shdr.sh_entsize as usize)
+    }
+
+    /// decode a symbol in the symbol table section `symtab_idx`.
+    /// `Symbol64.symbol_name` is left empty, use `symbol_name()` to borrow it.
+    pub fn symbol(
+        &self,
+        symtab_idx: usize,
+        sym_idx: usize,
+    ) -> Result<symbol::Symbol64, ReadELFError> {
+        let shdr = self.symbol_table_header(symtab_idx)?;
+        let entsize = shdr.sh_entsize as usize;
+        let table = self.section_data(symtab_idx)?;
+
+        let invalid = || ReadELFError::SectionOutOfBounds {
+            index: symtab_idx,
+            offset: shdr.sh_offset as usize + entsize * sym_idx,
+            size: entsize,
+        };
+        let entry = parser::checked_slice(table, entsize * sym_idx, entsize).ok_or_else(invalid)?;
+        self.data().deserialize(entry).map_err(|_| invalid())
+    }
+
+    /// iterate symbols in the symbol table section `symtab_idx` with decoding each entry lazily.
+    pub fn symbols(
+        &self,
+        symtab_idx: usize,
+    ) -> Result<impl Iterator<Item = Result<symbol::Symbol64, ReadELFError>> + 'a, ReadELFError>
+    {
+        let number = self.symbol_number(symtab_idx)?;
+        let view = *self;
+        Ok((0..number).map(move |sym_idx| view.symbol(symtab_idx, sym_idx)))
+    }
+
+    /// the symbol name looked up from the string table linked with `symtab_idx`
+    pub fn symbol_name(
+        &self,
+        symtab_idx: usize,
+        sym: &symbol::Symbol64,
+    ) -> Result<&'a str, ReadELFError> {
+        if sym.st_name == 0 {
+            return Ok("");
+        }
+        let shdr = self.shdr(symtab_idx)?;
+        self.string_at(shdr.sh_link as usize, sym.st_name as usize)
+    }
+
+    /// translate a virtual address into a file offset via `PT_LOAD` segments.
+    /// addresses in the zero-filled part(`p_filesz..p_memsz`) have no offset.
+    pub fn vaddr_to_offset(&self, vaddr: u64) -> Option<usize> {
+        self.load_segment_containing(vaddr)
+            .and_then(|phdr| phdr.p_offset.checked_add(vaddr - phdr.p_vaddr))
+            .map(|offset| offset as usize)
+    }
+
+    /// entries in the `PT_DYNAMIC` segment until `DT_NULL`.
+    /// return an empty vector if the image has no `PT_DYNAMIC`.
+    pub fn dynamic_entries(&self) -> Result<Vec<dynamic::Dyn64>, ReadELFError> {
+        let dyn_idx = match self.dynamic_segment_index() {
+            Some(idx) => idx,
+            None => return Ok(Vec::new()),
+        };
+
+        let mut entries = Vec::new();
+        for entry in self
+            .segment_data(dyn_idx)?
+            .chunks_exact(dynamic::Dyn64::SIZE)
+        {
+            let dyn_ent: dynamic::Dyn64 =
+                self.data()
+                    .deserialize(entry)
+                    .map_err(|_| ReadELFError::SegmentOutOfBounds {
+                        index: dyn_idx,
+                        offset: self.phdr(dyn_idx).map_or(0, |phdr| phdr.p_offset as usize),
+                        size: dynamic::Dyn64::SIZE,
+                    })?;
+            if dyn_ent.get_type() == dynamic::EntryType::Null {
+                break;
+            }
+            entries.push(dyn_ent);
+        }
+
+        Ok(entries)
+    }
+
+    /// a NUL-terminated string at `name_idx` in the table located by `DT_STRTAB`/`DT_STRSZ`.
+    /// this works without section headers.
+    pub fn dynamic_string(&self, name_idx: usize) -> Result<&'a str, ReadELFError> {
+        let strtab = self.dynamic_strtab(&self.dynamic_entries()?)?;
+        // セクションが無いため，エラーにはPT_DYNAMICのインデックスを用いる
+        let dyn_idx = self.dynamic_segment_index().unwrap_or(0);
+        parser::string_in_table(strtab, dyn_idx, name_idx)
+    }
+
+    /// the number of dynamic symbols.
+    /// the symbol table has no size in the dynamic section,
+    /// so it is taken from `DT_HASH`(nchain) or computed by walking `DT_GNU_HASH`.
+    pub fn dynamic_symbol_number(&self) -> Result<usize, ReadELFError> {
+        let entries = self.dynamic_entries()?;
+
+        if let Some(addr) = dynamic_value(&entries, dynamic::EntryType::Hash) {
+            let nchain_addr = addr.checked_add(4).ok_or(ReadELFError::UnmappedAddress {
+                addr: addr as usize,
+                size: 8,
+            })?;
+            let nchain = self.slice_at(nchain_addr, 4)?;
+            return Ok(self
+                .data()
+                .deserialize::<Elf64Word>(nchain)
+                .map_or(0, |n| n as usize));
+        }
+        if let Some(addr) = dynamic_value(&entries, dynamic::EntryType::GNUHash) {
+            let table = self.slice_to_segment_end(addr)?;
+            return section::gnu_hash_symbol_number(self.data(), header::Class::Bit64, table)
+                .ok_or(ReadELFError::UnmappedAddress {
+                    addr: addr as usize,
+                    size: table.len(),
+                });
+        }
+
+        Ok(0)
+    }
+
+    /// decode the dynamic symbols located by `DT_SYMTAB` with their names.
+    pub fn dynamic_symbols(&self) -> Result<Vec<symbol::Symbol64>, ReadELFError> {
+        let entries = self.dynamic_entries()?;
+        let number = self.dynamic_symbol_number()?;
+        if number == 0 {
+            return Ok(Vec::new());
+        }
+
+        let entsize = dynamic_value(&entries, dynamic::EntryType::SymEnt)
+            .unwrap_or(symbol::Symbol64::SIZE as u64) as usize;
+        let addr = dynamic_value(&entries, dynamic::EntryType::SymTab).unwrap_or(0);
+        let dyn_idx = self.dynamic_segment_index().unwrap_or(0);
+        let invalid_entsize = || ReadELFError::InvalidEntrySize {
+            index: dyn_idx,
+            offset: self.vaddr_to_offset(addr).unwrap_or(0),
+            entsize,
+        };
+        // DT_SYMENTが0だとchunks_exactがパニックする
+        if entsize < symbol::Symbol64::SIZE {
+            return Err(invalid_entsize());
+        }
+        let size = entsize.checked_mul(number).ok_or_else(invalid_entsize)?;
+        let table = self.slice_at(addr, size as u64)?;
+        // .dynstrは一度だけ解決する
+        let strtab = self.dynamic_strtab(&entries)?;
+
+        let mut syms = Vec::with_capacity(number);
+        for entry in table.chunks_exact(entsize) {
+            let mut sym: symbol::Symbol64 = self
+                .data()
+                .deserialize(entry)
+                .map_err(|_| invalid_entsize())?;
+            if sym.st_name != 0 {
+                sym.symbol_name =
+                    parser::string_in_table(strtab, dyn_idx, sym.st_name as usize)?.to_string();
+            }
+            syms.push(sym);
+        }
+
+        Ok(syms)
+    }
+
+    /// decode relocations located by `DT_RELA`/`DT_RELASZ`.
+    pub fn dynamic_relocations(&self) -> Result<Vec<relocation::Rela64>, ReadELFError> {
+        let entries = self.dynamic_entries()?;
+        let table = self.dynamic_table(
+            &entries,
+            dynamic::EntryType::Rela,
+            dynamic::EntryType::RelaSz,
+        )?;
+        self.decode_relas(table)
+    }
+
+    /// decode PLT relocations located by `DT_JMPREL`/`DT_PLTRELSZ`.
+    /// only the RELA form(`DT_PLTREL` == `DT_RELA`) is decoded.
+    pub fn plt_relocations(&self) -> Result<Vec<relocation::Rela64>, ReadELFError> {
+        let entries = self.dynamic_entries()?;
+        // DT_PLTRELの値はDT_RELA(7)かDT_REL(17)
+        if dynamic_value(&entries, dynamic::EntryType::PLTRel) != Some(7) {
+            return Ok(Vec::new());
+        }
+        let table = self.dynamic_table(
+            &entries,
+            dynamic::EntryType::JmpRel,
+            dynamic::EntryType::PLTRelSz,
+        )?;
+        self.decode_relas(table)
+    }
+
+    /// construct the owned `file::ELF64` to mutate it.
+    pub fn to_elf64(&self) -> Result<file::ELF64, ReadELFError> {
+        parser::parse_elf64_bytes(self.buf)
+    }
+
+    /// the `PT_LOAD` whose file image contains `vaddr`
+    fn load_segment_containing(&self, vaddr: u64) -> Option<segment::Phdr64> {
+        (0..self.segment_number())
+            .filter_map(|idx| self.phdr(idx).ok())
+            .find(|phdr| {
+                phdr.get_type() == segment::Type::Load
+                    && phdr.p_vaddr <= vaddr
+                    && vaddr - phdr.p_vaddr < phdr.p_filesz
+            })
+    }
+
+    fn dynamic_segment_index(&self) -> Option<usize> {
+        (0..self.segment_number()).find(|idx| {
+            self.phdr(*idx)
+                .is_ok_and(|phdr| phdr.get_type() == segment::Type::Dynamic)
+        })
+    }
+
+    /// the table whose address and size are in the dynamic entries.
+    /// an empty slice is returned if the address is absent.
+    fn dynamic_table(
+        &self,
+        entries: &[dynamic::Dyn64],
+        addr_tag: dynamic::EntryType,
+        size_tag: dynamic::EntryType,
+    ) -> Result<&'a [u8], ReadELFError> {
+        match dynamic_value(entries, addr_tag) {
+            Some(addr) => self.slice_at(addr, dynamic_value(entries, size_tag).unwrap_or(0)),
+            None => Ok(&[]),
+        }
+    }
+
+    /// the string table located by `DT_STRTAB`/`DT_STRSZ`
+    fn dynamic_strtab(&self, entries: &[dynamic::Dyn64]) -> Result<&'a [u8], ReadELFError> {
+        self.dynamic_table(
+            entries,
+            dynamic::EntryType::StrTab,
+            dynamic::EntryType::StrSz,
+        )
+    }
+
+    /// bytes in the file which are mapped at `vaddr`
+    fn slice_at(&self, vaddr: u64, size: u64) -> Result<&'a [u8], ReadELFError> {
+        self.vaddr_to_offset(vaddr)
+            .and_then(|offset| parser::checked_slice(self.buf, offset, size as usize))
+            .ok_or(ReadELFError::UnmappedAddress {
+                addr: vaddr as usize,
+                size: size as usize,
+            })
+    }
+
+    /// bytes from `vaddr` to the end of the file image of the containing `PT_LOAD`
+    fn slice_to_segment_end(&self, vaddr: u64) -> Result<&'a [u8], ReadELFError> {
+        let phdr = self
+            .load_segment_containing(vaddr)
+            .ok_or(ReadELFError::UnmappedAddress {
+                addr: vaddr as usize,
+                size: 0,
+            })?;
+        self.slice_at(vaddr, phdr.p_filesz - (vaddr - phdr.p_vaddr))
+    }
+
+    fn decode_relas(&self, table: &[u8]) -> Result<Vec<relocation::Rela64>, ReadELFError> {
+        table
+            .chunks_exact(relocation::Rela64::SIZE as usize)
+            .map(|entry| {
+                self.data()
+                    .deserialize(entry)
+                    .map_err(|_| ReadELFError::InvalidEntrySize {
+                        index: self.dynamic_segment_index().unwrap_or(0),
+                        offset: 0,
+                        entsize: relocation::Rela64::SIZE as usize,
+                    })
+            })
+            .collect()
+    }
+
+    fn symbol_table_header(&self, symtab_idx: usize) -> Result<section::Shdr64, ReadELFError> {
+        let shdr = self.shdr(symtab_idx)?;
+        if (shdr.sh_entsize as usize) < symbol::Symbol64::SIZE {
+            return Err(ReadELFError::InvalidEntrySize {
+                index: symtab_idx,
+                offset: shdr.sh_offset as usize,
+                entsize: shdr.sh_entsize as usize,
+            });
+        }
+        Ok(shdr)
+    }
+}
+
+fn dynamic_value(entries: &[dynamic::Dyn64], tag: dynamic::EntryType) -> Option<u64> {
+    entries
+        .iter()
+        .find(|dyn_ent| dyn_ent.get_type() == tag)
+        .map(|dyn_ent| dyn_ent.d_un)
+}
+
+#[cfg(test)]
+mod elf64_tests {
+    use super::*;
+    use crate::section::Contents64;
+
+    #[test]
+    fn view_matches_parsed_elf64_test() {
+        let bytes = std::fs::read("src/parser/testdata/sample").unwrap();
+        let view = ElfView64::new(&bytes).unwrap();
+        let f = view.to_elf64().unwrap();
+
+        assert_eq!(f.sections.len(), view.section_number());
+        assert_eq!(f.segments.len(), view.segment_number());
+
+        for (idx, sct) in f.sections.iter().enumerate() {
+            assert_eq!(sct.header, view.shdr(idx).unwrap());
+            assert_eq!(sct.name, view.section_name(idx).unwrap());
+
+            if let Contents64::Raw(raw) = &sct.contents {
+                let borrowed = view.section_data(idx).unwrap();
+                assert_eq!(raw.as_slice(), borrowed);
+                // 元のバッファを指していること
+                assert!(borrowed.is_empty() || bytes.as_ptr_range().contains(&borrowed.as_ptr()));
+            }
+
+            if let Contents64::Symbols(syms) = &sct.contents {
+                let lazy_syms: Vec<symbol::Symbol64> =
+                    view.symbols(idx).unwrap().map(|s| s.unwrap()).collect();
+                assert_eq!(syms.len(), lazy_syms.len());
+                for (sym, lazy) in syms.iter().zip(lazy_syms.iter()) {
+                    assert_eq!(sym.symbol_name, view.symbol_name(idx, lazy).unwrap());
+                    assert_eq!(sym.st_value, lazy.st_value);
+                }
+            }
+        }
+
+        for (idx, seg) in f.segments.iter().enumerate() {
+            assert_eq!(seg.header, view.phdr(idx).unwrap());
+        }
+
+        // PT_NOTEには.note.gnu.build-idと.note.ABI-tagが含まれる
+        let notes = view.segment_notes(8).unwrap();
+        assert_eq!(2, notes.notes.len());
+        assert!(notes
+            .find(note::ELF_NOTE_GNU, note::NT_GNU_BUILD_ID)
+            .is_some());
+
+        assert!(view.shdr(29).is_err());
+        assert!(view.phdr(13).is_err());
+        assert_eq!(None, view.section_by_name(".nothing").unwrap());
+        assert!(ElfView64::new(&bytes[..0x100]).is_err());
+
+        // NULLセクションのsh_sizeが巨大でもパニックしない
+        let mut huge = bytes.clone();
+        let shoff = view.ehdr().e_shoff as usize;
+        huge[0x3c..0x3e].fill(0);
+        huge[shoff + 0x20..shoff + 0x28].fill(0xff);
+        assert!(matches!(
+            ElfView64::new(&huge),
+            Err(ReadELFError::SectionHeaderTableOutOfBounds { .. })
+        ));
+    }
+
+    #[test]
+    fn segment_only_dynamic_test() {
+        for path in [
+            "src/parser/testdata/libdyn.so",
+            "src/parser/testdata/libhash.so",
+        ] {
+            let bytes = std::fs::read(path).unwrap();
+            let f = parser::parse_elf64_bytes(&bytes).unwrap();
+
+            // sstripと同様に，セクションヘッダテーブルへの参照を消す
+            let mut stripped = bytes.clone();
+            stripped[0x28..0x30].fill(0);
+            stripped[0x3c..0x40].fill(0);
+            let view = ElfView64::new(&stripped).unwrap();
+            assert_eq!(0, view.section_number());
+
+            let dynsym = f.first_section_by(|sct| sct.name == ".dynsym").unwrap();
+            let syms = match &dynsym.contents {
+                Contents64::Symbols(syms) => syms,
+                _ => unreachable!(),
+            };
+            let lazy_syms = view.dynamic_symbols().unwrap();
+            assert_eq!(syms.len(), lazy_syms.len());
+            for (sym, lazy) in syms.iter().zip(lazy_syms.iter()) {
+                assert_eq!(sym.symbol_name, lazy.symbol_name);
+                assert_eq!(sym.st_value, lazy.st_value);
+            }
+
+            let relas = |name: &str| match f.first_section_by(|sct| sct.name == name) {
+                Some(sct) => match &sct.contents {
+                    Contents64::RelaSymbols(relas) => relas.clone(),
+                    _ => unreachable!(),
+                },
+                None => Vec::new(),
+            };
+            assert_eq!(relas(".rela.dyn"), view.dynamic_relocations().unwrap());
+            assert_eq!(relas(".rela.plt"), view.plt_relocations().unwrap());
+        }
+
+        let bytes = std::fs::read("src/parser/testdata/libdyn.so").unwrap();
+        let view = ElfView64::new(&bytes).unwrap();
+        assert_eq!(7, view.dynamic_relocations().unwrap().len());
+        assert_eq!(1, view.plt_relocations().unwrap().len());
+        let soname = view
+            .dynamic_entries()
+            .unwrap()
+            .iter()
+            .find(|dyn_ent| dyn_ent.get_type() == dynamic::EntryType::SOName)
+            .unwrap()
+            .d_un;
+        assert_eq!("libdyn.so.1", view.dynamic_string(soname as usize).unwrap());
+
+        // .dynstrはPT_LOADの先頭から0x330に配置されている
+        assert_eq!(Some(0x330), view.vaddr_to_offset(0x330));
+        assert!(view.vaddr_to_offset(0xffff_ffff).is_none());
+    }
+
+    #[test]
+    fn invalid_dynamic_symbol_entsize_test() {
+        let bytes = std::fs::read("src/parser/testdata/libdyn.so").unwrap();
+        let view = ElfView64::new(&bytes).unwrap();
+        let dyn_offset = view
+            .phdr(view.dynamic_segment_index().unwrap())
+            .unwrap()
+            .p_offset;
+        let syment_idx = view
+            .dynamic_entries()
+            .unwrap()
+            .iter()
+            .position(|dyn_ent| dyn_ent.get_type() == dynamic::EntryType::SymEnt)
+            .unwrap();
+        let start = dyn_offset as usize + dynamic::Dyn64::SIZE * syment_idx + 8;
+
+        // DT_SYMENTが0や小さすぎる値，乗算で溢れる値でもパニックしない
+        for entsize in [0u64, 8, u64::MAX] {
+            let mut broken = bytes.clone();
+            broken[start..start + 8].copy_from_slice(&entsize.to_le_bytes());
+            let view = ElfView64::new(&broken).unwrap();
+            assert!(matches!(
+                view.dynamic_symbols(),
+                Err(ReadELFError::InvalidEntrySize { .. })
+            ));
+        }
+    }
+}
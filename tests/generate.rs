@@ -1,8 +1,8 @@
 mod tests {
     use elf_utilities::{
-        file, header,
+        file, header, link, note, objcopy, parser,
         section::{self, Contents64},
-        segment, Elf64Half, Elf64Off,
+        segment, strip, symbol, version, Elf64Half, Elf64Off,
     };
 
     #[test]
@@ -66,7 +66,8 @@ mod tests {
                 e_version: 0,
                 e_entry: 0,
                 e_phoff: header::Ehdr64::SIZE as Elf64Off,
-                e_shoff: header::Ehdr64::SIZE as Elf64Off + 0xb + 1024,
+                // ファイルオフセットはfinalize()まで更新されない
+                e_shoff: header::Ehdr64::SIZE as Elf64Off + 0xb,
                 e_flags: 0,
                 e_ehsize: header::Ehdr64::SIZE as Elf64Half,
                 e_phentsize: segment::Phdr64::SIZE as Elf64Half,
@@ -80,11 +81,11 @@ mod tests {
         // 追加された .test1セクションの情報が正当であるか
         assert_eq!(
             section::Shdr64 {
-                sh_name: 12,
+                sh_name: 11,
                 sh_type: section::Type::Null.into(),
                 sh_flags: 0,
                 sh_addr: 0,
-                sh_offset: 0x4b,
+                sh_offset: 0,
                 sh_size: 1024,
                 sh_link: 0,
                 sh_info: 0,
@@ -94,5 +95,1105 @@ mod tests {
             f.sections[1].header
         );
         assert!(matches!(f.sections[2].contents, Contents64::StrTab(_)));
+
+        f.finalize();
+        assert_eq!(0x40, f.sections[1].header.sh_offset);
+        assert_eq!(0x440, f.sections[2].header.sh_offset);
+        // "\0.test1\0.shstrtab\0"
+        assert_eq!(0x12, f.sections[2].header.sh_size);
+        assert_eq!(0x458, f.ehdr.e_shoff);
+    }
+
+    #[test]
+    fn generate_elf64_extended_numbers_test() {
+        let mut f = file::ELF64::default();
+        // .shstrtabがSHN_LORESERVE - 1番目に来るように，空のセクションを詰めておく
+        f.sections.resize(
+            section::SHN_LORESERVE as usize,
+            section::Section64::new_null_section(),
+        );
+        assert!(f.move_section(1, section::SHN_LORESERVE as usize - 1));
+
+        f.add_section(section::Section64::new(
+            ".test1".to_string(),
+            section::ShdrPreparation64::default(),
+            section::Contents64::Raw(vec![0x00; 16]),
+        ));
+
+        let shnum = section::SHN_LORESERVE as usize + 1;
+        assert_eq!(shnum, f.sections.len());
+        assert_eq!(0, f.ehdr.e_shnum);
+        assert_eq!(section::SHN_XINDEX, f.ehdr.e_shstrndx);
+        assert_eq!(shnum as u64, f.sections[0].header.sh_size);
+        assert_eq!(shnum as u32 - 1, f.sections[0].header.sh_link);
+        assert_eq!(shnum - 1, f.shstrndx());
+
+        // セグメントも同様に，PN_XNUM以上はsh_infoに格納される
+        f.segments
+            .resize(segment::PN_XNUM as usize, Default::default());
+        f.add_segment(Default::default());
+        assert_eq!(segment::PN_XNUM, f.ehdr.e_phnum);
+        assert_eq!(segment::PN_XNUM as u32 + 1, f.sections[0].header.sh_info);
+        assert_eq!(shnum - 1, f.shstrndx());
+    }
+
+    #[test]
+    fn generate_elf64_extended_symbol_index_test() {
+        let mut f = file::ELF64::default();
+        f.ehdr.set_class(header::Class::Bit64);
+        f.ehdr.set_data(header::Data::LSB2);
+        f.add_section(section::Section64::new(
+            ".strtab".to_string(),
+            section::ShdrPreparation64::default().ty(section::Type::StrTab),
+            Contents64::new_string_table(vec!["far".to_string()]),
+        ));
+        let mut far = symbol::Symbol64 {
+            st_name: 1,
+            ..Default::default()
+        };
+        far.set_info(symbol::Type::Func, symbol::Bind::Global);
+        let mut symtab = section::Section64::new(
+            ".symtab".to_string(),
+            section::ShdrPreparation64 {
+                sh_addralign: 8,
+                ..Default::default()
+            }
+            .ty(section::Type::SymTab)
+            .link(1)
+            .info(1),
+            Contents64::Symbols(vec![symbol::Symbol64::new_null_symbol(), far]),
+        );
+        symtab.header.sh_entsize = symbol::Symbol64::SIZE as u64;
+        f.add_section(symtab);
+
+        // .farがSHN_LORESERVE番目に来るように，空のセクションを詰めておく
+        f.sections.resize(
+            section::SHN_LORESERVE as usize + 1,
+            section::Section64::new_null_section(),
+        );
+        assert!(f.move_section(3, section::SHN_LORESERVE as usize));
+        f.add_section(section::Section64::new(
+            ".far".to_string(),
+            section::ShdrPreparation64::default()
+                .ty(section::Type::ProgBits)
+                .flags([section::Flag::Alloc, section::Flag::ExecInstr].iter()),
+            Contents64::Raw(vec![0xc3]),
+        ));
+        let far_idx = section::SHN_LORESERVE as usize;
+
+        assert!(f.set_symbol_section_index(2, 1, far_idx));
+        assert!(!f.set_symbol_section_index(2, 2, far_idx));
+        assert!(!f.set_symbol_section_index(1, 0, far_idx));
+        f.finalize();
+
+        // .symtab_shndxは末尾に追加される
+        let shndx_idx = f.sections.len() - 1;
+        assert_eq!(".symtab_shndx", f.sections[shndx_idx].name);
+        assert_eq!(2, f.sections[shndx_idx].header.sh_link);
+        assert_eq!(far_idx + 1, f.shstrndx());
+
+        let parsed = parser::parse_elf64_bytes(&f.to_le_bytes()).unwrap();
+        assert_eq!(".symtab_shndx", parsed.sections[shndx_idx].name);
+        assert_eq!(8, parsed.sections[shndx_idx].header.sh_size);
+        if let Contents64::Symbols(ref syms) = parsed.sections[2].contents {
+            assert_eq!(section::SHN_XINDEX, syms[1].st_shndx);
+            assert_eq!("far", syms[1].symbol_name);
+        }
+        assert_eq!(Some(0), parsed.symbol_section_index(2, 0));
+        assert_eq!(Some(far_idx), parsed.symbol_section_index(2, 1));
+
+        // 小さい番号に戻すとSHT_SYMTAB_SHNDXの要素は0になる
+        f.set_symbol_section_index(2, 1, 1);
+        assert_eq!(Some(1), f.symbol_section_index(2, 1));
+        assert!(f.sections[shndx_idx].contents == Contents64::SymTabShNdx(vec![0, 0]));
+    }
+
+    #[test]
+    fn generate_elf64_note_section_test() {
+        let mut f = file::ELF64::default();
+        let build_id = note::Descriptor::GNUBuildID(vec![0x6e, 0xfb, 0x5f, 0x10]);
+        f.add_section(section::Section64::new_note_section(
+            ".note.gnu.build-id".to_string(),
+            note::NoteTable::new(4).note(build_id.to_note(header::Data::LSB2, 4)),
+        ));
+
+        let sct = &f.sections[1];
+        assert_eq!(section::Type::Note, sct.header.get_type());
+        assert!(sct.header.get_flags().contains(&section::Flag::Alloc));
+        assert_eq!(4, sct.header.sh_addralign);
+        assert_eq!(20, sct.header.sh_size);
+        assert_eq!(
+            vec![
+                0x04, 0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, b'G', b'N',
+                b'U', 0x00, 0x6e, 0xfb, 0x5f, 0x10,
+            ],
+            sct.to_le_bytes()
+        );
+    }
+
+    #[test]
+    fn generate_elf64_group_test() {
+        let mut f = file::ELF64::default();
+        let group_idx = f.add_group(
+            ".group".to_string(),
+            4,
+            1,
+            section::GRP_COMDAT,
+            vec![section::Section64::new(
+                ".text._Z1fi".to_string(),
+                section::ShdrPreparation64::default()
+                    .ty(section::Type::ProgBits)
+                    .flags([section::Flag::Alloc, section::Flag::ExecInstr].iter()),
+                section::Contents64::Raw(vec![0xc3]),
+            )],
+        );
+
+        assert_eq!(1, group_idx);
+        assert_eq!(".text._Z1fi", f.sections[2].name);
+        assert_eq!(Some(1), f.group_of(2));
+
+        let group = &f.sections[1];
+        assert_eq!(section::Type::Group, group.header.get_type());
+        assert_eq!(4, group.header.sh_link);
+        assert_eq!(1, group.header.sh_info);
+        assert_eq!(4, group.header.sh_entsize);
+        assert_eq!(8, group.header.sh_size);
+        assert_eq!(
+            vec![0x01, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00],
+            group.to_le_bytes()
+        );
+        assert!(f.sections[2]
+            .header
+            .get_flags()
+            .contains(&section::Flag::Group));
+    }
+
+    #[test]
+    fn generate_elf64_version_definitions_test() {
+        let mut f = file::ELF64::default();
+        let name = |v: &str, idx: usize| section::StrTabEntry {
+            v: v.to_string(),
+            idx,
+        };
+        let mut ver_2 = version::VersionDefinition::new(0, 3, name("VER_2", 17));
+        ver_2.names.push(name("VER_1", 11));
+        f.add_section(section::Section64::new_version_definitions_section(
+            ".gnu.version_d".to_string(),
+            2,
+            vec![
+                version::VersionDefinition::new(version::VER_FLG_BASE, 1, name("libver.so", 1)),
+                version::VersionDefinition::new(0, 2, name("VER_1", 11)),
+                ver_2,
+            ],
+        ));
+
+        let sct = &f.sections[1];
+        assert_eq!(section::Type::GNUVerDef, sct.header.get_type());
+        assert_eq!(2, sct.header.sh_link);
+        assert_eq!(3, sct.header.sh_info);
+        assert_eq!(0x5c, sct.header.sh_size);
+
+        let bytes = sct.to_le_bytes();
+        assert_eq!(0x5c, bytes.len());
+        // vd_next of each Verdef
+        assert_eq!([0x1c, 0, 0, 0], bytes[0x10..0x14]);
+        assert_eq!([0x1c, 0, 0, 0], bytes[0x2c..0x30]);
+        assert_eq!([0, 0, 0, 0], bytes[0x48..0x4c]);
+        // the parent of VER_2
+        assert_eq!([11, 0, 0, 0, 0, 0, 0, 0], bytes[0x54..0x5c]);
+    }
+    #[test]
+    fn finalize_elf64_test() {
+        let mut f = file::ELF64::default();
+        f.ehdr.set_class(header::Class::Bit64);
+        f.ehdr.set_data(header::Data::LSB2);
+        f.ehdr.set_file_version(header::Version::Current);
+        f.ehdr.set_elf_type(header::Type::Exec);
+        f.ehdr.set_machine(header::Machine::X8664);
+        f.ehdr.e_entry = 0x401000;
+
+        let alloc_section = |name: &str, ty, write: bool, align, contents| {
+            let mut flags = vec![section::Flag::Alloc];
+            flags.push(if write {
+                section::Flag::Write
+            } else {
+                section::Flag::ExecInstr
+            });
+            section::Section64::new(
+                name.to_string(),
+                section::ShdrPreparation64 {
+                    sh_addralign: align,
+                    ..Default::default()
+                }
+                .ty(ty)
+                .flags(flags.iter()),
+                contents,
+            )
+        };
+        // mov edi, 42; mov eax, 60; syscall
+        let text = vec![
+            0xbf, 0x2a, 0x00, 0x00, 0x00, 0xb8, 0x3c, 0x00, 0x00, 0x00, 0x0f, 0x05,
+        ];
+        f.add_section(alloc_section(
+            ".text",
+            section::Type::ProgBits,
+            false,
+            16,
+            Contents64::Raw(text.clone()),
+        ));
+        f.add_section(alloc_section(
+            ".data",
+            section::Type::ProgBits,
+            true,
+            8,
+            Contents64::Raw(vec![0x01; 8]),
+        ));
+        f.add_section(alloc_section(
+            ".bss",
+            section::Type::NoBits,
+            true,
+            32,
+            Contents64::Raw(Vec::new()),
+        ));
+        f.add_section(section::Section64::new(
+            ".comment".to_string(),
+            section::ShdrPreparation64::default().ty(section::Type::ProgBits),
+            Contents64::Raw(b"gen\0".to_vec()),
+        ));
+        f.sections[1].header.sh_addr = 0x401000;
+        f.sections[2].header.sh_addr = 0x402000;
+        f.sections[3].header.sh_addr = 0x402020;
+        f.sections[3].header.sh_size = 0x100;
+
+        let phdr = |ty, flags: &[segment::Flag], vaddr, filesz, memsz, align| {
+            let mut phdr = segment::Phdr64 {
+                p_vaddr: vaddr,
+                p_paddr: vaddr,
+                p_filesz: filesz,
+                p_memsz: memsz,
+                p_align: align,
+                ..Default::default()
+            };
+            phdr.set_type(ty);
+            phdr.set_flags(flags.iter());
+            segment::Segment64 { header: phdr }
+        };
+        let (r, w, x) = (segment::Flag::R, segment::Flag::W, segment::Flag::X);
+        f.add_segment(phdr(segment::Type::Phdr, &[r], 0x400040, 0, 0, 8));
+        f.add_segment(phdr(
+            segment::Type::Load,
+            &[r, x],
+            0x400000,
+            0x100c,
+            0x100c,
+            0x1000,
+        ));
+        f.add_segment(phdr(
+            segment::Type::Load,
+            &[r, w],
+            0x402000,
+            8,
+            0x120,
+            0x1000,
+        ));
+        f.add_segment(phdr(segment::Type::GNUStack, &[r, w], 0, 0, 0, 0x10));
+        f.finalize();
+
+        assert_eq!(0x40, f.ehdr.e_phoff);
+        assert_eq!(4, f.ehdr.e_phnum);
+        assert_eq!(6, f.ehdr.e_shnum);
+        assert_eq!(5, f.ehdr.e_shstrndx);
+        let offsets: Vec<Elf64Off> = f.sections.iter().map(|s| s.header.sh_offset).collect();
+        assert_eq!(vec![0, 0x1000, 0x2000, 0x2020, 0x2008, 0x200c], offsets);
+        assert_eq!(0x100, f.sections[3].header.sh_size);
+        assert_eq!(0x25, f.sections[5].header.sh_size);
+        assert_eq!(0x2038, f.ehdr.e_shoff);
+
+        let offsets: Vec<Elf64Off> = f.segments.iter().map(|s| s.header.p_offset).collect();
+        assert_eq!(vec![0x40, 0, 0x2000, 0], offsets);
+        assert_eq!(0xe0, f.segments[0].header.p_filesz);
+        for seg in f.segments.iter() {
+            let align = seg.header.p_align.max(1);
+            assert_eq!(seg.header.p_vaddr % align, seg.header.p_offset % align);
+        }
+
+        let bytes = f.to_le_bytes();
+        assert_eq!(0x2038 + 6 * section::Shdr64::SIZE, bytes.len());
+        assert_eq!(text, bytes[0x1000..0x100c]);
+        assert_eq!(vec![0x01; 8], bytes[0x2000..0x2008]);
+
+        let parsed = parser::parse_elf64_bytes(&bytes).unwrap();
+        let names: Vec<&str> = parsed.sections.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(
+            vec!["", ".text", ".data", ".bss", ".comment", ".shstrtab"],
+            names
+        );
+        assert_eq!(Some(0x2000), parsed.vaddr_to_offset(0x402000));
+        assert_eq!(
+            vec![vec![], vec![1], vec![2, 3], vec![]],
+            parsed.segment_sections()
+        );
+    }
+
+    #[test]
+    fn finalize_elf64_broken_shstrndx_test() {
+        let mut f = parser::parse_elf64("src/parser/testdata/reloc_aarch64.o").unwrap();
+        let symtab_idx = f.first_shidx_by(|sct| sct.name == ".symtab").unwrap();
+        let symtab = f.sections[symtab_idx].contents.clone();
+
+        // e_shstrndxが文字列テーブル以外を指していても，その内容を壊さない
+        f.ehdr.e_shstrndx = symtab_idx as u16;
+        f.finalize();
+        assert!(symtab == f.sections[symtab_idx].contents);
+    }
+
+    #[test]
+    fn finalize_elf64_round_trip_test() {
+        let f = parser::parse_elf64("src/parser/testdata/sample").unwrap();
+        let mut finalized = f.clone();
+        finalized.finalize();
+
+        for (before, after) in f.sections.iter().zip(finalized.sections.iter()) {
+            assert_eq!(before.name, after.name);
+            assert_eq!(before.header.sh_offset, after.header.sh_offset);
+        }
+        for (before, after) in f.segments.iter().zip(finalized.segments.iter()) {
+            assert_eq!(before.header.p_offset, after.header.p_offset);
+        }
+        let parsed = parser::parse_elf64_bytes(&finalized.to_le_bytes()).unwrap();
+        assert_eq!(f.segment_sections(), parsed.segment_sections());
+    }
+
+    #[test]
+    fn finalize_elf32_round_trip_test() {
+        let f = parser::parse_elf32("src/parser/testdata/32bit").unwrap();
+        let mut finalized = f.clone();
+        finalized.finalize();
+
+        for (before, after) in f.sections.iter().zip(finalized.sections.iter()) {
+            assert_eq!(before.name, after.name);
+            assert_eq!(before.header.sh_offset, after.header.sh_offset);
+        }
+        for (before, after) in f.segments.iter().zip(finalized.segments.iter()) {
+            assert_eq!(before.header.p_offset, after.header.p_offset);
+        }
+        let parsed = parser::parse_elf32_bytes(&finalized.to_le_bytes()).unwrap();
+        assert_eq!(f.segment_sections(), parsed.segment_sections());
+    }
+
+    #[test]
+    fn generate_segments_elf64_test() {
+        let mut f = file::ELF64::default();
+        f.ehdr.set_class(header::Class::Bit64);
+        f.ehdr.set_data(header::Data::LSB2);
+        f.ehdr.set_file_version(header::Version::Current);
+        f.ehdr.set_elf_type(header::Type::Exec);
+        f.ehdr.set_machine(header::Machine::X8664);
+
+        let prep = |ty, flags: &[section::Flag], align| {
+            section::ShdrPreparation64 {
+                sh_addralign: align,
+                ..Default::default()
+            }
+            .ty(ty)
+            .flags(flags.iter())
+        };
+        use section::Flag::{Alloc, ExecInstr, Write};
+        // mov edi, [rip + .data - next]; mov eax, 60; syscall
+        f.add_section(section::Section64::new(
+            ".text".to_string(),
+            prep(section::Type::ProgBits, &[Alloc, ExecInstr], 16),
+            Contents64::Raw(vec![
+                0x8b, 0x3d, 0x00, 0x00, 0x00, 0x00, 0xb8, 0x3c, 0x00, 0x00, 0x00, 0x0f, 0x05,
+            ]),
+        ));
+        f.add_section(section::Section64::new(
+            ".rodata".to_string(),
+            prep(section::Type::ProgBits, &[Alloc], 8),
+            Contents64::Raw(b"generated\0".to_vec()),
+        ));
+        f.add_section(section::Section64::new(
+            ".data".to_string(),
+            prep(section::Type::ProgBits, &[Alloc, Write], 4),
+            Contents64::Raw(vec![42, 0, 0, 0]),
+        ));
+        f.add_section(section::Section64::new(
+            ".bss".to_string(),
+            prep(section::Type::NoBits, &[Alloc, Write], 32),
+            Contents64::Raw(Vec::new()),
+        ));
+        f.sections[4].header.sh_size = 0x2000;
+
+        f.generate_segments(
+            segment::GenerateOptions::new(0x400000, 0x1000)
+                .phdr(true)
+                .interp(true)
+                .gnu_stack(true),
+        );
+
+        let types: Vec<segment::Type> = f.segments.iter().map(|s| s.header.get_type()).collect();
+        assert_eq!(
+            vec![
+                segment::Type::Phdr,
+                segment::Type::Load,
+                segment::Type::Load,
+                segment::Type::Load,
+                segment::Type::GNUStack,
+            ],
+            types
+        );
+        let text_addr = f.sections[1].header.sh_addr;
+        let data_addr = f.sections[3].header.sh_addr;
+        assert_eq!(0x400160, text_addr);
+        assert_eq!(0x401170, f.sections[2].header.sh_addr);
+        assert_eq!(0x40217c, data_addr);
+        assert_eq!(0x402180, f.sections[4].header.sh_addr);
+
+        let data_load = &f.segments[3].header;
+        assert_eq!(
+            (data_addr, 4, 0x2004),
+            (data_load.p_vaddr, data_load.p_filesz, data_load.p_memsz)
+        );
+        for seg in f.segments.iter() {
+            let align = seg.header.p_align.max(1);
+            assert_eq!(seg.header.p_vaddr % align, seg.header.p_offset % align);
+        }
+        assert_eq!(f.ehdr.e_phoff, f.segments[0].header.p_offset);
+        assert_eq!(
+            vec![vec![], vec![1], vec![2], vec![3, 4], vec![]],
+            f.segment_sections()
+        );
+
+        // 相対アドレスの埋め込み
+        let disp = (data_addr - (text_addr + 6)) as u32;
+        if let Contents64::Raw(ref mut bytes) = f.sections[1].contents {
+            bytes[2..6].copy_from_slice(&disp.to_le_bytes());
+        }
+        f.ehdr.e_entry = text_addr;
+        let bytes = f.to_le_bytes();
+        let parsed = parser::parse_elf64_bytes(&bytes).unwrap();
+        assert_eq!(f.segment_sections(), parsed.segment_sections());
+
+        #[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+        {
+            use std::os::unix::fs::PermissionsExt;
+
+            let path =
+                std::env::temp_dir().join(format!("elf-utilities-generate-{}", std::process::id()));
+            std::fs::write(&path, &bytes).unwrap();
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+            let status = std::process::Command::new(&path).status().unwrap();
+            std::fs::remove_file(&path).unwrap();
+            assert_eq!(Some(42), status.code());
+        }
+    }
+
+    #[test]
+    fn generate_segments_elf32_test() {
+        let mut f = file::ELF32 {
+            sections: vec![
+                section::Section32::new_null_section(),
+                section::Section32::new(
+                    ".text".to_string(),
+                    section::ShdrPreparation32 {
+                        sh_addralign: 16,
+                        ..Default::default()
+                    }
+                    .ty(section::Type::ProgBits)
+                    .flags([section::Flag::Alloc, section::Flag::ExecInstr].iter()),
+                    section::Contents32::Raw(vec![0xcc; 0x10]),
+                ),
+                section::Section32::new(
+                    ".got".to_string(),
+                    section::ShdrPreparation32 {
+                        sh_addralign: 4,
+                        ..Default::default()
+                    }
+                    .ty(section::Type::ProgBits)
+                    .flags([section::Flag::Alloc, section::Flag::Write].iter()),
+                    section::Contents32::Raw(vec![0x00; 8]),
+                ),
+                section::Section32::new(
+                    ".shstrtab".to_string(),
+                    section::ShdrPreparation32::default().ty(section::Type::StrTab),
+                    section::Contents32::new_string_table(Vec::new()),
+                ),
+            ],
+            ..Default::default()
+        };
+        f.ehdr.set_class(header::Class::Bit32);
+        f.ehdr.set_data(header::Data::LSB2);
+        f.ehdr.e_shstrndx = 3;
+        f.generate_segments(
+            segment::GenerateOptions::new(0x8048000, 0x1000)
+                .gnu_stack(true)
+                .gnu_relro(true),
+        );
+
+        assert_eq!(4, f.ehdr.e_phnum);
+        assert_eq!(0x34, f.ehdr.e_phoff);
+        assert_eq!(0x80480c0, f.sections[1].header.sh_addr);
+        assert_eq!(0x80490d0, f.sections[2].header.sh_addr);
+        assert_eq!(0xd0, f.sections[2].header.sh_offset);
+        assert_eq!(0, f.segments[0].header.p_offset);
+        assert_eq!(0xd0, f.segments[1].header.p_offset);
+        assert_eq!(segment::Type::GNURelRO, f.segments[3].header.get_type());
+        assert_eq!(0x80490d0, f.segments[3].header.p_vaddr);
+        assert_eq!(8, f.segments[3].header.p_memsz);
+        assert_eq!(".got", f.sections[2].name);
+        assert_eq!(1, f.sections[1].header.sh_name);
+
+        let parsed = parser::parse_elf32_bytes(&f.to_le_bytes()).unwrap();
+        assert_eq!(".got", parsed.sections[2].name);
+        assert_eq!(f.segment_sections(), parsed.segment_sections());
+    }
+
+    #[test]
+    fn generate_segments_interleaved_test() {
+        let mut f = file::ELF64::default();
+        f.ehdr.set_class(header::Class::Bit64);
+        f.ehdr.set_data(header::Data::LSB2);
+        f.ehdr.set_elf_type(header::Type::Exec);
+
+        let prep = |ty, flags: &[section::Flag], align| {
+            section::ShdrPreparation64 {
+                sh_addralign: align,
+                ..Default::default()
+            }
+            .ty(ty)
+            .flags(flags.iter())
+        };
+        use section::Flag::{Alloc, ExecInstr};
+        // .commentが同じグループの.textと.text2の間にある
+        f.add_section(section::Section64::new(
+            ".text".to_string(),
+            prep(section::Type::ProgBits, &[Alloc, ExecInstr], 16),
+            Contents64::Raw(vec![0x90; 0x30]),
+        ));
+        f.add_section(section::Section64::new(
+            ".comment".to_string(),
+            prep(section::Type::ProgBits, &[], 1),
+            Contents64::Raw(vec![0x41; 0x200]),
+        ));
+        f.add_section(section::Section64::new(
+            ".text2".to_string(),
+            prep(section::Type::ProgBits, &[Alloc, ExecInstr], 16),
+            Contents64::Raw(vec![0xc3; 0x10]),
+        ));
+        f.generate_segments(segment::GenerateOptions::new(0x400000, 0x1000));
+
+        assert_eq!(1, f.segments.len());
+        assert_eq!(vec![vec![1, 3]], f.segment_sections());
+        let load = &f.segments[0].header;
+        assert_eq!(load.p_vaddr + load.p_filesz, {
+            let text2 = &f.sections[3].header;
+            text2.sh_addr + text2.sh_size
+        });
+        for sct in [&f.sections[1], &f.sections[3]] {
+            assert_eq!(
+                sct.header.sh_addr - load.p_vaddr,
+                sct.header.sh_offset - load.p_offset
+            );
+        }
+        // .commentはPT_LOADの外に置かれる
+        assert!(f.sections[2].header.sh_offset >= load.p_offset + load.p_filesz);
+
+        let bytes = f.to_le_bytes();
+        let parsed = parser::parse_elf64_bytes(&bytes).unwrap();
+        assert_eq!(f.segment_sections(), parsed.segment_sections());
+        let text2_offset = parsed.sections[3].header.sh_offset as usize;
+        assert_eq!(vec![0xc3; 0x10], bytes[text2_offset..text2_offset + 0x10]);
+    }
+
+    #[test]
+    fn remove_section_test() {
+        let mut f = parser::parse_elf64("src/parser/testdata/reloc.o").unwrap();
+        assert!(f.remove_section(0).is_none());
+        assert!(f.remove_section(f.sections.len()).is_none());
+
+        let removed = f.remove_section(6).unwrap();
+        assert_eq!(".comment", removed.name);
+        assert_eq!(9, f.shstrndx());
+        assert_eq!(".symtab", f.sections[7].name);
+        // .rela.text/.rela.dataのsh_linkはシンボルテーブル，sh_infoは適用先
+        assert_eq!(
+            (7, 1),
+            (f.sections[2].header.sh_link, f.sections[2].header.sh_info)
+        );
+        assert_eq!(
+            (7, 3),
+            (f.sections[4].header.sh_link, f.sections[4].header.sh_info)
+        );
+        assert_eq!(8, f.sections[7].header.sh_link);
+
+        // 削除されたセクションで定義されたシンボルは未定義になる
+        f.remove_section(3).unwrap();
+        assert_eq!(0, f.sections[3].header.sh_info);
+        if let Contents64::Symbols(ref syms) = f.sections[6].contents {
+            let shndx: Vec<u16> = syms.iter().map(|sym| sym.st_shndx).collect();
+            assert_eq!(vec![0, section::SHN_ABS, 1, 0, 0, 1, 0], shndx);
+        } else {
+            unreachable!();
+        }
+
+        f.finalize();
+        let parsed = parser::parse_elf64_bytes(&f.to_le_bytes()).unwrap();
+        let names: Vec<&str> = parsed.sections.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(
+            vec![
+                "",
+                ".text",
+                ".rela.text",
+                ".rela.data",
+                ".bss",
+                ".note.GNU-stack",
+                ".symtab",
+                ".strtab",
+                ".shstrtab"
+            ],
+            names
+        );
+        assert_eq!(8, parsed.ehdr.e_shstrndx);
+    }
+
+    #[test]
+    fn move_and_rename_section_test() {
+        let mut f = parser::parse_elf64("src/parser/testdata/comdat.o").unwrap();
+        assert!(!f.move_section(0, 3));
+        assert!(!f.move_section(3, f.sections.len()));
+
+        assert!(f.move_section(6, 2));
+        assert_eq!(".text._Z1fi", f.sections[2].name);
+        assert_eq!(".text", f.sections[3].name);
+        assert_eq!(Some(1), f.group_of(2));
+        assert_eq!(None, f.group_of(3));
+        // .rela.text => 4
+        assert_eq!(3, f.sections[4].header.sh_info);
+
+        // .symtabを末尾へ移動する
+        assert!(f.move_section(11, 13));
+        assert_eq!(".symtab", f.sections[13].name);
+        assert_eq!(12, f.shstrndx());
+        assert_eq!(13, f.sections[1].header.sh_link);
+        assert_eq!(13, f.sections[4].header.sh_link);
+        assert_eq!(11, f.sections[13].header.sh_link);
+        assert_eq!(Some("_Z1fi"), f.group_signature(1));
+        if let Contents64::Symbols(ref syms) = f.sections[13].contents {
+            let sym = syms.iter().find(|sym| sym.symbol_name == "_Z1fi").unwrap();
+            assert_eq!(2, sym.st_shndx);
+        } else {
+            unreachable!();
+        }
+
+        assert!(f.rename_section(3, ".text.main".to_string()));
+        assert!(!f.rename_section(0, ".null".to_string()));
+        f.finalize();
+        let parsed = parser::parse_elf64_bytes(&f.to_le_bytes()).unwrap();
+        let names: Vec<&str> = parsed.sections.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(
+            vec![
+                "",
+                ".group",
+                ".text._Z1fi",
+                ".text.main",
+                ".rela.text",
+                ".data",
+                ".bss",
+                ".comment",
+                ".note.GNU-stack",
+                ".eh_frame",
+                ".rela.eh_frame",
+                ".strtab",
+                ".shstrtab",
+                ".symtab"
+            ],
+            names
+        );
+        assert_eq!(Some(1), parsed.group_of(2));
+        assert_eq!(Some("_Z1fi"), parsed.group_signature(1));
+    }
+
+    fn symtab_section(syms: Vec<symbol::Symbol64>, strtab_idx: usize) -> section::Section64 {
+        let mut symtab = section::Section64::new(
+            ".symtab".to_string(),
+            section::ShdrPreparation64 {
+                sh_addralign: 8,
+                ..Default::default()
+            }
+            .ty(section::Type::SymTab)
+            .link(strtab_idx as u32)
+            .info(1),
+            Contents64::Symbols(syms),
+        );
+        symtab.header.sh_entsize = symbol::Symbol64::SIZE as u64;
+        symtab
+    }
+
+    #[test]
+    fn rename_section_shared_shstrtab_test() {
+        let mut f = file::ELF64::default();
+        f.ehdr.set_class(header::Class::Bit64);
+        f.ehdr.set_data(header::Data::LSB2);
+        f.add_section(section::Section64::new(
+            ".text".to_string(),
+            section::ShdrPreparation64::default().ty(section::Type::ProgBits),
+            Contents64::Raw(vec![0xc3]),
+        ));
+        // .shstrtabをシンボル名にも使う(".text"の末尾を共有する)
+        let sym = symbol::Symbol64 {
+            st_name: f.sections[1].header.sh_name + 1,
+            st_shndx: 1,
+            ..Default::default()
+        };
+        f.add_section(symtab_section(
+            vec![symbol::Symbol64::new_null_symbol(), sym],
+            3,
+        ));
+        assert_eq!(3, f.shstrndx());
+
+        assert!(f.rename_section(1, ".code".to_string()));
+        f.finalize();
+        let parsed = parser::parse_elf64_bytes(&f.to_le_bytes()).unwrap();
+        assert_eq!(
+            vec!["", ".code", ".symtab", ".shstrtab"],
+            section_names(&parsed)
+        );
+        assert_eq!("text", symbols(&parsed, 2)[1].symbol_name);
+    }
+
+    #[test]
+    fn move_section_extended_symbol_index_test() {
+        let mut f = file::ELF64::default();
+        f.ehdr.set_class(header::Class::Bit64);
+        f.ehdr.set_data(header::Data::LSB2);
+        f.add_section(section::Section64::new(
+            ".strtab".to_string(),
+            section::ShdrPreparation64::default().ty(section::Type::StrTab),
+            Contents64::new_string_table(vec!["main".to_string()]),
+        ));
+        let sym = symbol::Symbol64 {
+            st_name: 1,
+            st_shndx: 3,
+            ..Default::default()
+        };
+        f.add_section(symtab_section(
+            vec![symbol::Symbol64::new_null_symbol(), sym],
+            1,
+        ));
+        f.add_section(section::Section64::new(
+            ".text".to_string(),
+            section::ShdrPreparation64::default().ty(section::Type::ProgBits),
+            Contents64::Raw(vec![0xc3]),
+        ));
+
+        // .shstrtabがSHN_LORESERVE + 1番目に来るように，空のセクションを詰めておく
+        f.sections.resize(
+            section::SHN_LORESERVE as usize + 1,
+            section::Section64::new_null_section(),
+        );
+        assert!(f.move_section(4, section::SHN_LORESERVE as usize));
+        f.add_section(section::Section64::new(
+            ".pad".to_string(),
+            section::ShdrPreparation64::default().ty(section::Type::ProgBits),
+            Contents64::Raw(Vec::new()),
+        ));
+
+        // .textがSHN_LORESERVE番目に移動し，シンボルはSHN_XINDEXになる
+        let text_idx = section::SHN_LORESERVE as usize;
+        assert!(f.move_section(3, text_idx));
+        assert_eq!(".text", f.sections[text_idx].name);
+        assert_eq!(section::SHN_XINDEX, symbols(&f, 2)[1].st_shndx);
+        assert_eq!(Some(text_idx), f.symbol_section_index(2, 1));
+
+        f.finalize();
+        let parsed = parser::parse_elf64_bytes(&f.to_le_bytes()).unwrap();
+        assert_eq!(".symtab_shndx", parsed.sections.last().unwrap().name);
+        assert_eq!(".text", parsed.sections[text_idx].name);
+        assert_eq!(Some(text_idx), parsed.symbol_section_index(2, 1));
+
+        // 元の位置に戻しても正しいセクションを参照する
+        assert!(f.move_section(text_idx, 3));
+        assert_eq!(Some(3), f.symbol_section_index(2, 1));
+    }
+
+    fn section_names(f: &file::ELF64) -> Vec<&str> {
+        f.sections.iter().map(|s| s.name.as_str()).collect()
+    }
+
+    fn symbols(f: &file::ELF64, symtab_idx: usize) -> Vec<symbol::Symbol64> {
+        match &f.sections[symtab_idx].contents {
+            Contents64::Symbols(syms) => syms.clone(),
+            _ => unreachable!(),
+        }
+    }
+
+    /// exit status of the executable
+    #[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+    fn run(exe: &file::ELF64, name: &str) -> Option<i32> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path =
+            std::env::temp_dir().join(format!("elf-utilities-{}-{}", name, std::process::id()));
+        std::fs::write(&path, exe.to_le_bytes()).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let status = std::process::Command::new(&path).status().unwrap();
+        std::fs::remove_file(&path).unwrap();
+        status.code()
+    }
+
+    #[test]
+    fn strip_debug_test() {
+        let mut f = parser::parse_elf64("src/parser/testdata/strip_debug.o").unwrap();
+        f.strip(&strip::StripOptions::new(strip::StripMode::Debug));
+
+        assert_eq!(
+            vec![
+                "",
+                ".text",
+                ".rela.text",
+                ".data",
+                ".bss",
+                ".comment",
+                ".note.GNU-stack",
+                ".symtab",
+                ".strtab",
+                ".shstrtab"
+            ],
+            section_names(&f)
+        );
+        let syms = symbols(&f, 7);
+        let names: Vec<&str> = syms.iter().map(|s| s.symbol_name.as_str()).collect();
+        assert_eq!(
+            vec!["", "strip.c", "", "", "counter", "answer", "_start"],
+            names
+        );
+        assert_eq!(5, f.sections[7].header.sh_info);
+        assert_eq!(8, f.sections[7].header.sh_link);
+        assert_eq!(7, f.sections[2].header.sh_link);
+
+        // .data(セクションシンボル)とanswerへの再配置
+        if let Contents64::RelaSymbols(ref relas) = f.sections[2].contents {
+            let sym_indices: Vec<u64> = relas.iter().map(|rela| rela.get_sym()).collect();
+            assert_eq!(vec![3, 5], sym_indices);
+        } else {
+            unreachable!();
+        }
+
+        let parsed = parser::parse_elf64_bytes(&f.to_le_bytes()).unwrap();
+        let names: Vec<String> = symbols(&parsed, 7)
+            .into_iter()
+            .map(|s| s.symbol_name)
+            .collect();
+        assert_eq!(
+            vec!["", "strip.c", "", "", "counter", "answer", "_start"],
+            names
+        );
+
+        #[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+        {
+            let exe = link::Linker::new().object(parsed).link().unwrap();
+            assert_eq!(Some(42), run(&exe, "strip-debug"));
+        }
+    }
+
+    #[test]
+    fn strip_unneeded_test() {
+        let mut f = parser::parse_elf64("src/parser/testdata/strip_debug.o").unwrap();
+        f.strip(&strip::StripOptions::new(strip::StripMode::Unneeded));
+
+        let syms = symbols(&f, 7);
+        let names: Vec<&str> = syms.iter().map(|s| s.symbol_name.as_str()).collect();
+        assert_eq!(vec!["", "", "answer", "_start"], names);
+        assert_eq!(symbol::Type::Section, syms[1].get_type());
+        assert_eq!(3, syms[1].st_shndx);
+        assert_eq!(2, f.sections[7].header.sh_info);
+
+        #[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+        {
+            let exe = link::Linker::new().object(f).link().unwrap();
+            assert_eq!(Some(42), run(&exe, "strip-unneeded"));
+        }
+
+        // 実行ファイルではグローバルシンボルも不要なので，.symtabと.strtabも無くなる
+        let mut f = parser::parse_elf64("src/parser/testdata/strip_debug").unwrap();
+        f.strip(&strip::StripOptions::new(strip::StripMode::Unneeded));
+        assert_eq!(
+            vec![
+                "",
+                ".note.gnu.build-id",
+                ".text",
+                ".data",
+                ".comment",
+                ".shstrtab"
+            ],
+            section_names(&f)
+        );
+        assert_eq!(5, f.shstrndx());
+        #[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+        assert_eq!(Some(42), run(&f, "strip-unneeded-exe"));
+    }
+
+    #[test]
+    fn strip_all_test() {
+        // 再配置に使われるシンボルとkeep_symbolは残る
+        let mut f = parser::parse_elf64("src/parser/testdata/strip_debug.o").unwrap();
+        f.strip(&strip::StripOptions::new(strip::StripMode::All).keep_symbol("_start"));
+        let syms = symbols(&f, 7);
+        let names: Vec<&str> = syms.iter().map(|s| s.symbol_name.as_str()).collect();
+        assert_eq!(vec!["", "", "answer", "_start"], names);
+        #[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+        {
+            let exe = link::Linker::new().object(f).link().unwrap();
+            assert_eq!(Some(42), run(&exe, "strip-all-obj"));
+        }
+
+        let mut f = parser::parse_elf64("src/parser/testdata/strip_debug").unwrap();
+        let before = f.clone();
+        f.strip(&strip::StripOptions::new(strip::StripMode::All));
+        assert_eq!(
+            vec![
+                "",
+                ".note.gnu.build-id",
+                ".text",
+                ".data",
+                ".comment",
+                ".shstrtab"
+            ],
+            section_names(&f)
+        );
+        assert_eq!(5, f.shstrndx());
+        for (before, after) in before.segments.iter().zip(f.segments.iter()) {
+            assert_eq!(before.header, after.header);
+        }
+        let data = (&before.sections[3].header, &f.sections[3].header);
+        assert_eq!(data.0.sh_offset, data.1.sh_offset);
+        assert_eq!(data.0.sh_addr, data.1.sh_addr);
+        #[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+        assert_eq!(Some(42), run(&f, "strip-all-exe"));
+    }
+    fn temp_path(name: &str) -> String {
+        std::env::temp_dir()
+            .join(format!("elf-utilities-{}-{}", name, std::process::id()))
+            .to_str()
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn to_raw_binary_test() {
+        let f = parser::parse_elf64("src/parser/testdata/strip_debug").unwrap();
+        let image = f.to_raw_binary().unwrap();
+
+        // 0x400000(ヘッダを含む) から .data の末尾まで
+        assert_eq!(0x2004, image.len());
+        assert_eq!([0x7f, b'E', b'L', b'F'], image[..4]);
+        assert_eq!(vec![0x00; 0x1000 - 0x17c], image[0x17c..0x1000]);
+        let text = f.first_section_by(|sct| sct.name == ".text").unwrap();
+        assert_eq!(text.to_le_bytes(), image[0x1000..0x102e]);
+        assert_eq!([40, 0, 0, 0], image[0x2000..]);
+
+        // PT_LOADを持たないファイルは空になる
+        let obj = parser::parse_elf64("src/parser/testdata/reloc.o").unwrap();
+        assert!(obj.to_raw_binary().unwrap().is_empty());
+    }
+
+    #[test]
+    fn dump_section_test() {
+        let f = parser::parse_elf64("src/parser/testdata/strip_debug.o").unwrap();
+        let path = temp_path("dump-section");
+        f.dump_section(".data", &path).unwrap();
+        assert_eq!(vec![40, 0, 0, 0], std::fs::read(&path).unwrap());
+        f.dump_section(".symtab", &path).unwrap();
+        assert_eq!(
+            13 * symbol::Symbol64::SIZE,
+            std::fs::read(&path).unwrap().len()
+        );
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(matches!(
+            f.dump_section(".bss", &path),
+            Err(objcopy::ObjcopyError::NoContents { .. })
+        ));
+        assert!(matches!(
+            f.dump_section(".nothing", &path),
+            Err(objcopy::ObjcopyError::SectionNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn add_section_from_file_test() {
+        let mut f = parser::parse_elf64("src/parser/testdata/strip_debug.o").unwrap();
+        let path = temp_path("add-section");
+        std::fs::write(&path, b"firmware version 1.0").unwrap();
+
+        let hdr = section::ShdrPreparation64 {
+            sh_addralign: 4,
+            ..Default::default()
+        }
+        .ty(section::Type::ProgBits)
+        .flags([section::Flag::Alloc].iter());
+        let sct_idx = f
+            .add_section_from_file(".version".to_string(), hdr, &path)
+            .unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(20, sct_idx);
+        assert_eq!(21, f.shstrndx());
+
+        f.finalize();
+        let parsed = parser::parse_elf64_bytes(&f.to_le_bytes()).unwrap();
+        let sct = parsed
+            .first_section_by(|sct| sct.name == ".version")
+            .unwrap();
+        assert_eq!(b"firmware version 1.0".to_vec(), sct.to_le_bytes());
+        assert_eq!(4, sct.header.sh_addralign);
+        assert!(sct.header.get_flags().contains(&section::Flag::Alloc));
+        assert_eq!(0, sct.header.sh_offset % 4);
+
+        assert!(matches!(
+            f.add_section_from_file(
+                ".missing".to_string(),
+                Default::default(),
+                "src/parser/testdata/nothing"
+            ),
+            Err(objcopy::ObjcopyError::CantRead { .. })
+        ));
+    }
+    #[test]
+    fn add_section_shstrtab_not_last_test() {
+        // llvm-mcの出力は.shstrtabを.strtabと共用して先頭に置く
+        let mut f = parser::parse_elf64("src/parser/testdata/reloc_aarch64.o").unwrap();
+        assert_eq!(1, f.shstrndx());
+        let names: Vec<String> = symbols(&f, 6)
+            .iter()
+            .map(|sym| sym.symbol_name.clone())
+            .collect();
+
+        let path = temp_path("add-section-shstrndx");
+        std::fs::write(&path, b"payload").unwrap();
+        let sct_idx = f
+            .add_section_from_file(".payload".to_string(), Default::default(), &path)
+            .unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        // 末尾に追加され，既存のセクション番号は変わらない
+        assert_eq!(7, sct_idx);
+        assert_eq!(".payload", f.sections[sct_idx].name);
+        assert_eq!(1, f.shstrndx());
+        assert_eq!(8, f.ehdr.e_shnum);
+        for name in [".rela.text", ".rela.data"] {
+            let rela = f.first_section_by(|sct| sct.name == name).unwrap();
+            assert_eq!(".symtab", f.sections[rela.header.sh_link as usize].name);
+        }
+
+        f.finalize();
+        let parsed = parser::parse_elf64_bytes(&f.to_le_bytes()).unwrap();
+        assert_eq!(".payload", parsed.sections[sct_idx].name);
+        assert_eq!(b"payload".to_vec(), parsed.sections[sct_idx].to_le_bytes());
+        assert_eq!(".symtab", parsed.sections[6].name);
+        let parsed_names: Vec<String> = symbols(&parsed, 6)
+            .iter()
+            .map(|sym| sym.symbol_name.clone())
+            .collect();
+        assert_eq!(names, parsed_names);
     }
 }